@@ -0,0 +1,409 @@
+use crate::error::{ClientError, Error, Result};
+use crate::macro_utils::generate_common_client_fns;
+use lib_core::model::project::{Project, ProjectForCreate, ProjectForUpdate};
+use lib_core::model::task::{Task, TaskForCreate, TaskForUpdate};
+use lib_core::model::taskprogress::{
+	TaskProgress, TaskProgressForCreate, TaskProgressForUpdate,
+};
+use lib_core::model::tasktime::{TaskTime, TaskTimeForCreate, TaskTimeForUpdate};
+use lib_core::model::timerecord::{
+	TimeRecord, TimeRecordForCreate, TimeRecordForUpdate,
+};
+use reqwest::header::{AUTHORIZATION, SET_COOKIE};
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+
+/// The name of the web-server auth token cookie.
+const AUTH_TOKEN: &str = "auth-token";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+	/// The auth token is kept in the cookie store of the client (as in a browser).
+	Cookie,
+	/// The auth token is sent as `Authorization: Bearer <token>`,
+	/// and refreshed from the `Set-Cookie` of each response.
+	Bearer,
+}
+
+pub struct RpcClient {
+	http: reqwest::Client,
+	base_url: String,
+	auth_mode: AuthMode,
+	/// The auth token (only for `AuthMode::Bearer`).
+	token: Mutex<Option<String>>,
+	next_id: AtomicI64,
+}
+
+// Constructors
+impl RpcClient {
+	/// New client authenticated by cookie (e.g., `http://localhost:8080`).
+	pub fn new(base_url: impl Into<String>) -> Result<Self> {
+		Self::new_with_auth_mode(base_url, AuthMode::Cookie)
+	}
+
+	pub fn new_with_auth_mode(
+		base_url: impl Into<String>,
+		auth_mode: AuthMode,
+	) -> Result<Self> {
+		let http = reqwest::Client::builder()
+			.cookie_store(auth_mode == AuthMode::Cookie)
+			.build()?;
+
+		Ok(RpcClient {
+			http,
+			base_url: base_url.into().trim_end_matches('/').to_string(),
+			auth_mode,
+			token: Mutex::default(),
+			next_id: AtomicI64::new(1),
+		})
+	}
+
+	/// New bearer client with an existing token (e.g., from `bearer_token()`
+	/// of a previous session), which does not need to `login`.
+	pub fn new_with_bearer_token(
+		base_url: impl Into<String>,
+		token: impl Into<String>,
+	) -> Result<Self> {
+		let client = Self::new_with_auth_mode(base_url, AuthMode::Bearer)?;
+		*client.token.lock().unwrap() = Some(token.into());
+
+		Ok(client)
+	}
+}
+
+// Accessors
+impl RpcClient {
+	pub fn auth_mode(&self) -> AuthMode {
+		self.auth_mode
+	}
+
+	/// The current bearer token (None for `AuthMode::Cookie`, or when logged off).
+	pub fn bearer_token(&self) -> Option<String> {
+		self.token.lock().unwrap().clone()
+	}
+}
+
+// Login & Logoff
+impl RpcClient {
+	pub async fn login(&self, username: &str, pwd: &str) -> Result<()> {
+		self.post("/api/login", json!({ "username": username, "pwd": pwd }))
+			.await?;
+
+		if self.auth_mode == AuthMode::Bearer && self.bearer_token().is_none() {
+			return Err(Error::LoginNoTokenInResponse);
+		}
+
+		Ok(())
+	}
+
+	pub async fn logoff(&self) -> Result<()> {
+		self.post("/api/logoff", json!({ "logoff": true })).await?;
+
+		// Note: For AuthMode::Cookie, the response already removed the cookie.
+		*self.token.lock().unwrap() = None;
+
+		Ok(())
+	}
+}
+
+// Rpc Call
+impl RpcClient {
+	/// Calls the json-rpc `method`, and returns its deserialized `result`.
+	/// (for the rpcs without a typed method, e.g., `sync_changes`)
+	pub async fn call<P, R>(&self, method: &str, params: P) -> Result<R>
+	where
+		P: Serialize,
+		R: DeserializeOwned,
+	{
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let body = json!({
+			"id": id,
+			"method": method,
+			"params": params,
+		});
+
+		let result = self.post("/api/rpc", body).await?;
+
+		Ok(serde_json::from_value(result)?)
+	}
+
+	/// Posts the json `body`, and returns the `result` of the response body
+	/// (or its `error` as `Error::Rpc`).
+	async fn post(&self, path: &str, body: Value) -> Result<Value> {
+		let mut req = self
+			.http
+			.post(format!("{}{path}", self.base_url))
+			.json(&body);
+		if let Some(token) = self.bearer_token() {
+			req = req.header(AUTHORIZATION, format!("Bearer {token}"));
+		}
+
+		let res = req.send().await?;
+		if self.auth_mode == AuthMode::Bearer {
+			self.update_bearer_token(&res);
+		}
+
+		let status = res.status().as_u16();
+		let mut body: Value = res.json().await?;
+
+		if let Some(error) = body.get_mut("error").map(Value::take) {
+			return Err(rpc_error(status, error));
+		}
+
+		body.get_mut("result")
+			.map(Value::take)
+			.ok_or(Error::ResponseNoResult)
+	}
+
+	/// Takes the (refreshed or removed) auth token of the response `Set-Cookie`.
+	fn update_bearer_token(&self, res: &Response) {
+		let token = res
+			.headers()
+			.get_all(SET_COOKIE)
+			.iter()
+			.filter_map(|value| value.to_str().ok())
+			.filter_map(|cookie| cookie.strip_prefix(AUTH_TOKEN)?.strip_prefix('='))
+			.map(|cookie| cookie.split(';').next().unwrap_or_default().to_string())
+			.next_back();
+
+		if let Some(token) = token {
+			*self.token.lock().unwrap() = (!token.is_empty()).then_some(token);
+		}
+	}
+}
+
+/// Decodes the `{"message", "data": {"req_uuid", "detail"}}` error
+/// of a response body.
+fn rpc_error(status: u16, error: Value) -> Error {
+	let req_uuid = error
+		.pointer("/data/req_uuid")
+		.and_then(Value::as_str)
+		.map(str::to_string);
+
+	let mut client_error = json!({ "message": error.get("message") });
+	if let Some(detail) = error.pointer("/data/detail").filter(|d| !d.is_null()) {
+		client_error["detail"] = detail.clone();
+	}
+	let error = serde_json::from_value(client_error).unwrap_or(ClientError::UNKNOWN);
+
+	Error::Rpc {
+		status,
+		error,
+		req_uuid,
+	}
+}
+
+// region:    --- Entity Rpcs
+
+generate_common_client_fns!(
+	Entity: Project,
+	ForCreate: ProjectForCreate,
+	ForUpdate: ProjectForUpdate,
+	Suffix: project,
+	PluralSuffix: projects,
+);
+
+generate_common_client_fns!(
+	Entity: Task,
+	ForCreate: TaskForCreate,
+	ForUpdate: TaskForUpdate,
+	Suffix: task,
+	PluralSuffix: tasks,
+);
+
+generate_common_client_fns!(
+	Entity: TaskProgress,
+	ForCreate: TaskProgressForCreate,
+	ForUpdate: TaskProgressForUpdate,
+	Suffix: taskprogress,
+	PluralSuffix: taskprogresses,
+);
+
+generate_common_client_fns!(
+	Entity: TaskTime,
+	ForCreate: TaskTimeForCreate,
+	ForUpdate: TaskTimeForUpdate,
+	Suffix: tasktime,
+	PluralSuffix: tasktimes,
+);
+
+generate_common_client_fns!(
+	Entity: TimeRecord,
+	ForCreate: TimeRecordForCreate,
+	ForUpdate: TimeRecordForUpdate,
+	Suffix: timerecord,
+	PluralSuffix: timerecords,
+);
+
+// endregion: --- Entity Rpcs
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ListParams;
+	use anyhow::Result;
+	use lib_core::_dev_utils;
+	use lib_core::model::ModelManager;
+	use serial_test::serial;
+	use tokio::net::TcpListener;
+
+	const DEMO_USERNAME: &str = "demo1";
+	const DEMO_PWD: &str = "supersecretpassword";
+
+	/// Starts the web-server in-process (on a free port), and returns its base url.
+	///
+	/// Note: Each `#[tokio::test]` has its own runtime, so the server gets its own
+	///       `ModelManager` (the db pool of `init_test` is bound to the first runtime).
+	async fn start_test_server() -> Result<String> {
+		_dev_utils::init_test().await;
+		let mm = ModelManager::new().await?;
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let addr = listener.local_addr()?;
+
+		let routes_all = web_server::routes_all(mm);
+		tokio::spawn(async move {
+			axum::serve(listener, routes_all.into_make_service()).await
+		});
+
+		Ok(format!("http://{addr}"))
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_cookie_create_list_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let base_url = start_test_server().await?;
+		let client = RpcClient::new(base_url)?;
+		let fx_name = "test_cookie_create_list_delete_ok project";
+
+		// -- Exec
+		client.login(DEMO_USERNAME, DEMO_PWD).await?;
+		let project = client
+			.create_project(ProjectForCreate {
+				name: fx_name.to_string(),
+				client_id: None,
+			})
+			.await?;
+		let projects = client
+			.list_projects(ListParams::with_filters(json!({ "name": fx_name })))
+			.await?;
+
+		// -- Check
+		assert_eq!(projects.len(), 1);
+		assert_eq!(projects[0].id, project.id);
+		assert_eq!(projects[0].name, fx_name);
+
+		// -- Clean
+		client.delete_project(project.id).await?;
+		client.logoff().await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_bearer_update_logoff_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let base_url = start_test_server().await?;
+		let client = RpcClient::new_with_auth_mode(base_url, AuthMode::Bearer)?;
+		let fx_name = "test_bearer_update_logoff_ok project";
+		let fx_name_new = "test_bearer_update_logoff_ok project new";
+
+		// -- Exec
+		client.login(DEMO_USERNAME, DEMO_PWD).await?;
+		let project = client
+			.create_project(ProjectForCreate {
+				name: fx_name.to_string(),
+				client_id: None,
+			})
+			.await?;
+		let project = client
+			.update_project(
+				project.id,
+				ProjectForUpdate {
+					name: Some(fx_name_new.to_string()),
+					owner_id: None,
+					client_id: None,
+				},
+			)
+			.await?;
+		client.delete_project(project.id).await?;
+		client.logoff().await?;
+
+		// -- Check
+		assert_eq!(project.name, fx_name_new);
+		assert!(client.bearer_token().is_none());
+		let res = client.list_projects(ListParams::default()).await;
+		assert!(
+			matches!(
+				res,
+				Err(Error::Rpc {
+					error: ClientError::NO_AUTH,
+					..
+				})
+			),
+			"should be NO_AUTH, but was {res:?}"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_login_err_login_fail() -> Result<()> {
+		// -- Setup & Fixtures
+		let base_url = start_test_server().await?;
+		let client = RpcClient::new(base_url)?;
+
+		// -- Exec
+		let res = client.login(DEMO_USERNAME, "not the pwd").await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::Rpc {
+					status: 403,
+					error: ClientError::LOGIN_FAIL,
+					..
+				})
+			),
+			"should be LOGIN_FAIL, but was {res:?}"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_err_entity_not_found() -> Result<()> {
+		// -- Setup & Fixtures
+		let base_url = start_test_server().await?;
+		let client = RpcClient::new(base_url)?;
+		let fx_id = 100;
+
+		// -- Exec
+		client.login(DEMO_USERNAME, DEMO_PWD).await?;
+		let res = client.delete_project(fx_id).await;
+
+		// -- Check
+		match res {
+			Err(Error::Rpc {
+				error: ClientError::ENTITY_NOT_FOUND { entity, id },
+				..
+			}) => {
+				assert_eq!(entity, "project");
+				assert_eq!(id, fx_id);
+			}
+			other => panic!("should be ENTITY_NOT_FOUND, but was {other:?}"),
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,68 @@
+use derive_more::From;
+use serde::Deserialize;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(Debug, From)]
+pub enum Error {
+	/// Error returned by the web-server (i.e., its `ClientError` body).
+	Rpc {
+		status: u16,
+		error: ClientError,
+		req_uuid: Option<String>,
+	},
+
+	// -- Response
+	ResponseNoResult,
+	LoginNoTokenInResponse,
+
+	// -- External Modules
+	#[from]
+	Reqwest(reqwest::Error),
+	#[from]
+	SerdeJson(serde_json::Error),
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
+
+// region:    --- Client Error
+
+/// The `ClientError` of the web-server, decoded from the `error.message`
+/// and `error.data.detail` of the response body.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "message", content = "detail")]
+#[allow(non_camel_case_types)]
+pub enum ClientError {
+	LOGIN_FAIL,
+	NO_AUTH,
+	ENTITY_NOT_FOUND {
+		entity: String,
+		id: i64,
+	},
+	INVALID_REQUEST {
+		cause: String,
+	},
+	/// The rejected anomalies (see `lib_core::model::anomaly::Anomaly`).
+	ANOMALY_REJECTED {
+		anomalies: Vec<serde_json::Value>,
+	},
+
+	SERVICE_ERROR,
+
+	/// A client error not known by this version of the client.
+	#[serde(other)]
+	UNKNOWN,
+}
+
+// endregion: --- Client Error
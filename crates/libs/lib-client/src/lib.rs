@@ -0,0 +1,26 @@
+//! Typed async client of the web-server json-rpc api
+//! (e.g., for automation written in Rust).
+//!
+//! ```ignore
+//! let client = RpcClient::new("http://localhost:8080")?;
+//! client.login("demo1", "supersecretpassword").await?;
+//! let project = client
+//!     .create_project(ProjectForCreate {
+//!         name: "project AAA".to_string(),
+//!         client_id: None,
+//!     })
+//!     .await?;
+//! ```
+
+// region:    --- Modules
+
+mod client;
+mod error;
+mod macro_utils;
+mod params;
+
+pub use self::client::{AuthMode, RpcClient};
+pub use self::error::{ClientError, Error, Result};
+pub use params::ListParams;
+
+// endregion: --- Modules
@@ -0,0 +1,115 @@
+//! Declarative macro generating the typed `RpcClient` methods of an entity,
+//! mirroring the common rpcs of `lib_rpc::generate_common_rpc_fns!`.
+//!
+//! e.g.,
+//!
+//! ```ignore
+//! generate_common_client_fns!(
+//!     Entity: Task,
+//!     ForCreate: TaskForCreate,
+//!     ForUpdate: TaskForUpdate,
+//!     Suffix: task,
+//!     PluralSuffix: tasks,
+//! );
+//! ```
+//! Generates `create_task`, `list_tasks`, `update_task`, `delete_task`,
+//! `bulk_create_tasks`, `bulk_update_tasks`, and `bulk_delete_tasks`.
+
+macro_rules! generate_common_client_fns {
+	(
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Suffix: $suffix:ident,
+		PluralSuffix: $plural:ident
+		$(,)?
+	) => {
+		paste::paste! {
+			impl $crate::RpcClient {
+				pub async fn [<create_ $suffix>](
+					&self,
+					data: $for_create,
+				) -> $crate::Result<$entity> {
+					self.call(
+						concat!("create_", stringify!($suffix)),
+						serde_json::json!({ "data": data }),
+					)
+					.await
+				}
+
+				pub async fn [<list_ $plural>](
+					&self,
+					params: $crate::ListParams,
+				) -> $crate::Result<Vec<$entity>> {
+					self.call(concat!("list_", stringify!($plural)), params)
+						.await
+				}
+
+				pub async fn [<update_ $suffix>](
+					&self,
+					id: i64,
+					data: $for_update,
+				) -> $crate::Result<$entity> {
+					self.call(
+						concat!("update_", stringify!($suffix)),
+						serde_json::json!({ "id": id, "data": data }),
+					)
+					.await
+				}
+
+				/// Returns the deleted entity.
+				pub async fn [<delete_ $suffix>](
+					&self,
+					id: i64,
+				) -> $crate::Result<$entity> {
+					self.call(
+						concat!("delete_", stringify!($suffix)),
+						serde_json::json!({ "id": id }),
+					)
+					.await
+				}
+
+				pub async fn [<bulk_create_ $plural>](
+					&self,
+					data: Vec<$for_create>,
+				) -> $crate::Result<Vec<$entity>> {
+					self.call(
+						concat!("bulk_create_", stringify!($plural)),
+						serde_json::json!({ "data": data }),
+					)
+					.await
+				}
+
+				pub async fn [<bulk_update_ $plural>](
+					&self,
+					items: Vec<(i64, $for_update)>,
+				) -> $crate::Result<Vec<$entity>> {
+					let data: Vec<serde_json::Value> = items
+						.into_iter()
+						.map(|(id, data)| {
+							serde_json::json!({ "id": id, "data": data })
+						})
+						.collect();
+					self.call(
+						concat!("bulk_update_", stringify!($plural)),
+						serde_json::json!({ "data": data }),
+					)
+					.await
+				}
+
+				/// Returns the deleted entities.
+				pub async fn [<bulk_delete_ $plural>](
+					&self,
+					ids: Vec<i64>,
+				) -> $crate::Result<Vec<$entity>> {
+					self.call(
+						concat!("bulk_delete_", stringify!($plural)),
+						serde_json::json!({ "ids": ids }),
+					)
+					.await
+				}
+			}
+		}
+	};
+}
+pub(crate) use generate_common_client_fns;
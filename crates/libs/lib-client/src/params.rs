@@ -0,0 +1,24 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Params of the `list_...` calls.
+///
+/// The `filters` and `list_options` are given as json, as in the json-rpc api
+/// (e.g., `json!({"title": {"$contains": "BB"}})` for the filters,
+/// and `json!({"order_bys": "!title"})` for the list options).
+#[derive(Debug, Default, Serialize)]
+pub struct ListParams {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub filters: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub list_options: Option<Value>,
+}
+
+impl ListParams {
+	pub fn with_filters(filters: Value) -> Self {
+		ListParams {
+			filters: Some(filters),
+			list_options: None,
+		}
+	}
+}
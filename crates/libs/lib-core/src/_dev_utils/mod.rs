@@ -54,6 +54,7 @@ pub async fn seed_project(
 		mm,
 		ProjectForCreate {
 			name: name.to_string(),
+			client_id: None,
 		},
 	)
 	.await
@@ -116,6 +117,7 @@ pub async fn seed_timerecords(
 			mm,
 			TimeRecordForCreate {
 				place: place.to_string(),
+				place_id: None,
 				start_time: Some(now_utc()),
 				stop_time: Some(now_utc()),
 			},
@@ -146,6 +148,7 @@ pub async fn seed_tasktimes(
 				comment: comment.to_string(),
 				start_time: Some(now_utc()),
 				stop_time: Some(now_utc()),
+				billable: None,
 			},
 		)
 		.await?;
@@ -1,4 +1,5 @@
-use lib_utils::envs::get_env;
+use crate::model::anomaly::AnomalyStrictness;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
 
 pub fn core_config() -> &'static CoreConfig {
@@ -18,6 +19,10 @@ pub struct CoreConfig {
 
 	// -- Web
 	pub WEB_FOLDER: String,
+
+	// -- Anomalies
+	pub ANOMALY_STRICTNESS: AnomalyStrictness,
+	pub ANOMALY_MAX_ENTRY_HOURS: f64,
 }
 
 impl CoreConfig {
@@ -28,6 +33,12 @@ impl CoreConfig {
 
 			// -- Web
 			WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
+
+			// -- Anomalies
+			ANOMALY_STRICTNESS: get_env_parse("SERVICE_ANOMALY_STRICTNESS")?,
+			ANOMALY_MAX_ENTRY_HOURS: get_env_parse(
+				"SERVICE_ANOMALY_MAX_ENTRY_HOURS",
+			)?,
 		})
 	}
 }
@@ -0,0 +1,226 @@
+//! Timesheet csv of the `TimeEntry`s.
+//!
+//! - The header row has the column names (e.g., "date,start,stop,duration,title").
+//! - The dates and times are formatted with `time` format descriptions
+//!   (e.g., "[day]/[month]/[year]"), in the `CsvOptions::utc_offset`.
+//! - The `duration` is in decimal hours (e.g., "1.50"), as is the
+//!   `rounded_duration` (per the rounding policies, see `model::rounding`).
+
+use crate::export::{Error, Result, TimeEntry};
+use std::str::FromStr;
+use time::format_description::{self, FormatItem};
+use time::{Duration, UtcOffset};
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn {
+	Kind,
+	Id,
+	Date,
+	Start,
+	Stop,
+	Duration,
+	RoundedDuration,
+	Title,
+	Comment,
+	Place,
+}
+
+impl CsvColumn {
+	pub fn name(&self) -> &'static str {
+		match self {
+			CsvColumn::Kind => "kind",
+			CsvColumn::Id => "id",
+			CsvColumn::Date => "date",
+			CsvColumn::Start => "start",
+			CsvColumn::Stop => "stop",
+			CsvColumn::Duration => "duration",
+			CsvColumn::RoundedDuration => "rounded_duration",
+			CsvColumn::Title => "title",
+			CsvColumn::Comment => "comment",
+			CsvColumn::Place => "place",
+		}
+	}
+
+	/// Parses a comma separated list of column names
+	/// (e.g., "date,start,stop,title").
+	pub fn parse_list(columns: &str) -> Result<Vec<CsvColumn>> {
+		columns
+			.split(',')
+			.map(str::trim)
+			.filter(|name| !name.is_empty())
+			.map(CsvColumn::from_str)
+			.collect()
+	}
+}
+
+impl FromStr for CsvColumn {
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self> {
+		let column = match name {
+			"kind" => CsvColumn::Kind,
+			"id" => CsvColumn::Id,
+			"date" => CsvColumn::Date,
+			"start" => CsvColumn::Start,
+			"stop" => CsvColumn::Stop,
+			"duration" => CsvColumn::Duration,
+			"rounded_duration" => CsvColumn::RoundedDuration,
+			"title" => CsvColumn::Title,
+			"comment" => CsvColumn::Comment,
+			"place" => CsvColumn::Place,
+			_ => return Err(Error::CsvColumnUnknown(name.to_string())),
+		};
+
+		Ok(column)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+	pub columns: Vec<CsvColumn>,
+	/// The format of the `date` column (of the start time).
+	pub date_format: String,
+	/// The format of the `start` and `stop` columns.
+	pub time_format: String,
+	/// The offset the dates and times are displayed in.
+	pub utc_offset: UtcOffset,
+}
+
+impl Default for CsvOptions {
+	fn default() -> Self {
+		Self {
+			columns: vec![
+				CsvColumn::Date,
+				CsvColumn::Start,
+				CsvColumn::Stop,
+				CsvColumn::Duration,
+				CsvColumn::RoundedDuration,
+				CsvColumn::Title,
+				CsvColumn::Comment,
+				CsvColumn::Place,
+			],
+			date_format: "[year]-[month]-[day]".to_string(),
+			time_format: "[hour]:[minute]".to_string(),
+			utc_offset: UtcOffset::UTC,
+		}
+	}
+}
+
+// endregion: --- Types
+
+/// Renders the `entries` as csv (header row first).
+pub fn to_csv(entries: &[TimeEntry], options: &CsvOptions) -> Result<Vec<u8>> {
+	let date_format = parse_format(&options.date_format)?;
+	let time_format = parse_format(&options.time_format)?;
+
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(options.columns.iter().map(CsvColumn::name))?;
+
+	for entry in entries {
+		let start_time = entry.start_time.to_offset(options.utc_offset);
+		let stop_time = entry.stop_time.to_offset(options.utc_offset);
+
+		let record = options
+			.columns
+			.iter()
+			.map(|column| -> Result<String> {
+				let cell = match column {
+					CsvColumn::Kind => entry.kind.as_str().to_string(),
+					CsvColumn::Id => entry.id.to_string(),
+					CsvColumn::Date => start_time.format(&date_format)?,
+					CsvColumn::Start => start_time.format(&time_format)?,
+					CsvColumn::Stop => stop_time.format(&time_format)?,
+					CsvColumn::Duration => format_hours(entry.duration()),
+					CsvColumn::RoundedDuration => {
+						format_hours(entry.rounded_duration)
+					}
+					CsvColumn::Title => entry.title.clone(),
+					CsvColumn::Comment => entry.comment.clone().unwrap_or_default(),
+					CsvColumn::Place => entry.place.clone().unwrap_or_default(),
+				};
+				Ok(cell)
+			})
+			.collect::<Result<Vec<String>>>()?;
+		writer.write_record(&record)?;
+	}
+
+	writer
+		.into_inner()
+		.map_err(|err| csv::Error::from(err.into_error()).into())
+}
+
+/// e.g., 1h30 -> "1.50"
+fn format_hours(duration: Duration) -> String {
+	format!("{:.2}", duration.as_seconds_f64() / 3600.)
+}
+
+fn parse_format(format: &str) -> Result<Vec<FormatItem<'_>>> {
+	format_description::parse_borrowed::<2>(format).map_err(|err| {
+		Error::CsvFormatInvalid {
+			format: format.to_string(),
+			cause: err.to_string(),
+		}
+	})
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::export::TimeEntryKind;
+	use anyhow::Result;
+	use time::macros::datetime;
+
+	#[test]
+	fn test_to_csv_columns_and_formats_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_entries = [TimeEntry {
+			kind: TimeEntryKind::TaskTime,
+			id: 1000,
+			title: "Write report, part 1".to_string(),
+			comment: None,
+			place: None,
+			start_time: datetime!(2024-03-01 23:30 UTC),
+			stop_time: datetime!(2024-03-02 01:00 UTC),
+			mtime: datetime!(2024-03-02 01:00 UTC),
+			rounded_duration: Duration::hours(2),
+		}];
+		let fx_options = CsvOptions {
+			columns: CsvColumn::parse_list(
+				"date, start,stop,duration,rounded_duration,title",
+			)?,
+			date_format: "[day]/[month]/[year]".to_string(),
+			utc_offset: UtcOffset::from_hms(1, 0, 0)?,
+			..Default::default()
+		};
+
+		// -- Exec
+		let csv = to_csv(&fx_entries, &fx_options)?;
+
+		// -- Check
+		assert_eq!(
+			String::from_utf8(csv)?,
+			"date,start,stop,duration,rounded_duration,title\n\
+			 02/03/2024,00:30,02:00,1.50,2.00,\"Write report, part 1\"\n"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_csv_column_parse_err_unknown() -> Result<()> {
+		// -- Exec
+		let res = CsvColumn::parse_list("date,project");
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::CsvColumnUnknown(name)) if name == "project"),
+			"CsvColumnUnknown not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
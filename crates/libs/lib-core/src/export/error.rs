@@ -0,0 +1,47 @@
+use crate::model;
+use derive_more::From;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize, From)]
+pub enum Error {
+	DateRangeInvalid {
+		from: String,
+		to: String,
+	},
+	DateParseFail(String),
+	UtcOffsetParseFail(String),
+	CsvColumnUnknown(String),
+	CsvFormatInvalid {
+		format: String,
+		cause: String,
+	},
+
+	// -- Modules
+	#[from]
+	Model(model::Error),
+
+	// -- Externals
+	#[from]
+	Csv(#[serde_as(as = "DisplayFromStr")] csv::Error),
+	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+	#[from]
+	TimeFormat(#[serde_as(as = "DisplayFromStr")] time::error::Format),
+}
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
@@ -0,0 +1,402 @@
+//! Flex-time balance, i.e., the worked time (the `TimeRecord`s) against the
+//! contracted hours (the `WorkSchedule`s), per day, week or month.
+//!
+//! For each period:
+//!
+//! - `scheduled_sec`: the weekday hours, per the schedule in effect each day.
+//! - `absence_sec`: the `Absence`s time (a full day absence being
+//!   the scheduled hours), capped to the scheduled hours of each day.
+//! - `target_sec`: the scheduled time minus the absences time.
+//! - `worked_sec`: the timerecords time (overlapping ones counted once).
+//! - `balance_sec`: the worked time minus the target (overtime if positive).
+//!
+//! The running balance starts at the first schedule `valid_from`, the days
+//! before the report range being summed in the `opening_balance_sec`.
+//! The `absences` of the period days are listed as well (e.g., a holiday).
+//!
+//! Note: A period targets the days starting within it (i.e., a range not
+//!       starting at midnight skips its first day target).
+
+use crate::ctx::Ctx;
+use crate::export::reconciliation::{
+	intersect, length, merge, period_bounds, Interval, ReportPeriod,
+};
+use crate::export::{
+	period_absences, time_entries, DateRange, ReportAbsence, Result, TimeEntry,
+	TimeEntryKind,
+};
+use crate::model::absence::{Absence, AbsenceBmc};
+use crate::model::work_schedule::{schedule_on, WorkSchedule, WorkScheduleBmc};
+use crate::model::ModelManager;
+use lib_utils::time::Rfc3339;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_with::serde_as;
+use std::collections::BTreeMap;
+use time::{Date, Duration, OffsetDateTime, UtcOffset};
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct FlexBalance {
+	pub scheduled_sec: i64,
+	pub absence_sec: i64,
+	pub target_sec: i64,
+	pub worked_sec: i64,
+	pub balance_sec: i64,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PeriodFlexBalance {
+	/// The first day of the period (e.g., "2024-03-04").
+	pub date: String,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop: OffsetDateTime,
+
+	#[serde(flatten)]
+	pub balance: FlexBalance,
+	/// The balance from the start, up to the end of the period.
+	pub running_balance_sec: i64,
+	/// The absences of the period days, by date.
+	pub absences: Vec<ReportAbsence>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FlexReport {
+	pub period: ReportPeriod,
+	/// The balance from the start, up to the report range.
+	pub opening_balance_sec: i64,
+	/// The balance from the start, up to the end of the report range.
+	pub closing_balance_sec: i64,
+	pub periods: Vec<PeriodFlexBalance>,
+}
+
+/// The absences hours of each day (`None` for a full day).
+type DayAbsences = BTreeMap<Date, Vec<Option<f64>>>;
+
+// endregion: --- Types
+
+/// Returns the flex-time balance of the ctx user in `range`.
+pub async fn flex_balance(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	range: DateRange,
+	period: ReportPeriod,
+	offset: UtcOffset,
+) -> Result<FlexReport> {
+	let schedules = WorkScheduleBmc::list_for_user(ctx, mm).await?;
+
+	// -- The balance start (the first schedule, if before the range)
+	let balance_from = schedules
+		.first()
+		.map(|schedule| schedule.valid_from.midnight().assume_offset(offset))
+		.map_or(range.from, |from| from.min(range.from));
+	let balance_range = DateRange::new(balance_from, range.to)?;
+
+	// -- Load the entries and absences from the balance start
+	let entries = time_entries(ctx, mm, balance_range).await?;
+	let absences = AbsenceBmc::list_for_user(
+		ctx,
+		mm,
+		balance_from.to_offset(offset).date(),
+		range.to.to_offset(offset).date() + Duration::days(1),
+	)
+	.await?;
+
+	Ok(balance_entries(
+		&entries,
+		&schedules,
+		&absences,
+		balance_range,
+		range,
+		period,
+		offset,
+	))
+}
+
+/// Computes the flex-time balance of the `entries` (e.g., from `time_entries`)
+/// per `period` of `range`, the running balance starting at `balance_range.from`.
+///
+/// Note: `schedules` must be ordered by `valid_from`.
+pub fn balance_entries(
+	entries: &[TimeEntry],
+	schedules: &[WorkSchedule],
+	absences: &[Absence],
+	balance_range: DateRange,
+	range: DateRange,
+	period: ReportPeriod,
+	offset: UtcOffset,
+) -> FlexReport {
+	let presence = merge(
+		entries
+			.iter()
+			.filter(|entry| entry.kind == TimeEntryKind::TimeRecord)
+			.map(|entry| (entry.start_time, entry.stop_time))
+			.collect(),
+	);
+	let mut day_absences = DayAbsences::new();
+	for absence in absences {
+		day_absences
+			.entry(absence.date)
+			.or_default()
+			.push(absence.hours);
+	}
+
+	// -- The balance before the range
+	let opening_balance_sec = if balance_range.from < range.from {
+		let bounds = (balance_range.from, range.from);
+		balance_within(&presence, schedules, &day_absences, bounds, offset)
+			.balance_sec
+	} else {
+		0
+	};
+
+	// -- The balance per period
+	let mut running_balance_sec = opening_balance_sec;
+	let periods = period_bounds(range, period, offset)
+		.into_iter()
+		.map(|(date, bounds)| {
+			let balance =
+				balance_within(&presence, schedules, &day_absences, bounds, offset);
+			running_balance_sec += balance.balance_sec;
+
+			PeriodFlexBalance {
+				date: date.to_string(),
+				start: bounds.0,
+				stop: bounds.1,
+				balance,
+				running_balance_sec,
+				absences: period_absences(absences, bounds, offset),
+			}
+		})
+		.collect();
+
+	FlexReport {
+		period,
+		opening_balance_sec,
+		closing_balance_sec: running_balance_sec,
+		periods,
+	}
+}
+
+/// The balance of the `presence` (merged) within `bounds`, against the
+/// targets of the days starting within `bounds`.
+fn balance_within(
+	presence: &[Interval],
+	schedules: &[WorkSchedule],
+	day_absences: &DayAbsences,
+	bounds: Interval,
+	offset: UtcOffset,
+) -> FlexBalance {
+	let mut balance = FlexBalance::default();
+
+	// -- The targets of the days
+	let mut date = bounds.0.to_offset(offset).date();
+	loop {
+		let start = date.midnight().assume_offset(offset);
+		if start >= bounds.1 {
+			break;
+		}
+		if start >= bounds.0 {
+			let scheduled_sec = schedule_on(schedules, date)
+				.map(|schedule| hours_to_sec(schedule.hours(date.weekday())))
+				.unwrap_or_default();
+			let absence_sec: i64 = day_absences
+				.get(&date)
+				.into_iter()
+				.flatten()
+				.map(|hours| hours.map_or(scheduled_sec, hours_to_sec))
+				.sum();
+
+			balance.scheduled_sec += scheduled_sec;
+			balance.absence_sec += absence_sec.min(scheduled_sec);
+		}
+		date += Duration::days(1);
+	}
+	balance.target_sec = balance.scheduled_sec - balance.absence_sec;
+
+	// -- The worked time
+	balance.worked_sec = length(&intersect(presence, &[bounds])).whole_seconds();
+	balance.balance_sec = balance.worked_sec - balance.target_sec;
+
+	balance
+}
+
+fn hours_to_sec(hours: f64) -> i64 {
+	(hours * 3600.).round() as i64
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::{date, datetime};
+
+	const HOUR: i64 = 3600;
+
+	/// A timerecord of the hours `start_h` to `stop_h` of the 2024-03-04
+	/// (a Monday).
+	fn fx_timerecord(start_h: i64, stop_h: i64) -> TimeEntry {
+		let day = datetime!(2024-03-04 00:00 UTC);
+
+		TimeEntry {
+			kind: TimeEntryKind::TimeRecord,
+			id: 0,
+			title: String::new(),
+			comment: None,
+			place: None,
+			start_time: day + Duration::hours(start_h),
+			stop_time: day + Duration::hours(stop_h),
+			mtime: day,
+			rounded_duration: Duration::hours(stop_h - start_h),
+		}
+	}
+
+	/// A Monday to Friday 8 hours schedule.
+	fn fx_schedule(valid_from: Date) -> WorkSchedule {
+		let ctime = datetime!(2024-01-01 00:00 UTC);
+
+		WorkSchedule {
+			id: 0,
+			valid_from,
+			mon_hours: 8.,
+			tue_hours: 8.,
+			wed_hours: 8.,
+			thu_hours: 8.,
+			fri_hours: 8.,
+			sat_hours: 0.,
+			sun_hours: 0.,
+			cid: 0,
+			ctime,
+			mid: 0,
+			mtime: ctime,
+		}
+	}
+
+	fn fx_absence(date: Date, hours: Option<f64>) -> Absence {
+		let ctime = datetime!(2024-01-01 00:00 UTC);
+
+		Absence {
+			id: 0,
+			date,
+			kind: "vacation".to_string(),
+			hours,
+			comment: String::new(),
+			cid: 0,
+			ctime,
+			mid: 0,
+			mtime: ctime,
+		}
+	}
+
+	fn fx_entries() -> Vec<TimeEntry> {
+		vec![
+			// Monday 9h, Tuesday 8h (overlapping timerecords)
+			fx_timerecord(8, 17),
+			fx_timerecord(24 + 8, 24 + 14),
+			fx_timerecord(24 + 12, 24 + 16),
+			// Wednesday off, Thursday half off 4h, Friday 6h
+			fx_timerecord(3 * 24 + 8, 3 * 24 + 12),
+			fx_timerecord(4 * 24 + 8, 4 * 24 + 14),
+		]
+	}
+
+	#[test]
+	fn test_balance_entries_week_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_schedules = [fx_schedule(date!(2024 - 03 - 04))];
+		let fx_absences = [
+			fx_absence(date!(2024 - 03 - 06), None),
+			fx_absence(date!(2024 - 03 - 07), Some(4.)),
+		];
+		let fx_range = DateRange::new(
+			datetime!(2024-03-04 00:00 UTC),
+			datetime!(2024-03-11 00:00 UTC),
+		)?;
+
+		// -- Exec
+		let report = balance_entries(
+			&fx_entries(),
+			&fx_schedules,
+			&fx_absences,
+			fx_range,
+			fx_range,
+			ReportPeriod::Week,
+			UtcOffset::UTC,
+		);
+
+		// -- Check
+		assert_eq!(report.opening_balance_sec, 0);
+		assert_eq!(report.periods.len(), 1);
+		assert_eq!(
+			report.periods[0].balance,
+			FlexBalance {
+				scheduled_sec: 40 * HOUR,
+				absence_sec: 12 * HOUR,
+				target_sec: 28 * HOUR,
+				worked_sec: 27 * HOUR,
+				balance_sec: -HOUR,
+			}
+		);
+		assert_eq!(report.closing_balance_sec, -HOUR);
+		let absence_dates: Vec<&str> = report.periods[0]
+			.absences
+			.iter()
+			.map(|absence| absence.date.as_str())
+			.collect();
+		assert_eq!(absence_dates, vec!["2024-03-06", "2024-03-07"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_balance_entries_day_running_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_schedules = [fx_schedule(date!(2024 - 03 - 04))];
+		let fx_balance_range = DateRange::new(
+			datetime!(2024-03-04 00:00 UTC),
+			datetime!(2024-03-13 00:00 UTC),
+		)?;
+		let fx_range = DateRange::new(
+			datetime!(2024-03-11 00:00 UTC),
+			datetime!(2024-03-13 00:00 UTC),
+		)?;
+		let fx_entries = [
+			fx_entries(),
+			// the next Monday 10h
+			vec![fx_timerecord(7 * 24 + 8, 7 * 24 + 18)],
+		]
+		.concat();
+
+		// -- Exec
+		let report = balance_entries(
+			&fx_entries,
+			&fx_schedules,
+			&[],
+			fx_balance_range,
+			fx_range,
+			ReportPeriod::Day,
+			UtcOffset::UTC,
+		);
+
+		// -- Check
+		// (the previous week without absences: 27h worked for 40h)
+		assert_eq!(report.opening_balance_sec, -13 * HOUR);
+		let running: Vec<i64> = report
+			.periods
+			.iter()
+			.map(|period| period.running_balance_sec)
+			.collect();
+		assert_eq!(running, vec![-11 * HOUR, -19 * HOUR]);
+		assert_eq!(report.closing_balance_sec, -19 * HOUR);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,159 @@
+//! RFC 5545 iCalendar of the `TimeEntry`s, one `VEVENT` per entry.
+//!
+//! - The event `UID` is stable per entity (e.g., "tasktime-1000@project-a"),
+//!   so a subscribed calendar updates the events in place.
+//! - The times are in UTC (e.g., "20240301T083000Z").
+//! - The lines end with CRLF, and are folded at 75 octets.
+
+use crate::export::TimeEntry;
+use lib_utils::time::now_utc;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
+
+const PRODID: &str = "-//project-a//Time Export//EN";
+const UID_DOMAIN: &str = "project-a";
+const LINE_MAX_OCTETS: usize = 75;
+
+const UTC_FORMAT: &[FormatItem<'static>] =
+	format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// Renders the `entries` as an iCalendar named `calendar_name`.
+pub fn to_ics(entries: &[TimeEntry], calendar_name: &str) -> String {
+	let dtstamp = format_utc(now_utc());
+
+	let mut ics = String::new();
+	push_line(&mut ics, "BEGIN:VCALENDAR");
+	push_line(&mut ics, "VERSION:2.0");
+	push_line(&mut ics, &format!("PRODID:{PRODID}"));
+	push_line(&mut ics, "CALSCALE:GREGORIAN");
+	push_line(&mut ics, "METHOD:PUBLISH");
+	push_line(&mut ics, &format!("X-WR-CALNAME:{}", escape(calendar_name)));
+
+	for entry in entries {
+		let kind = entry.kind.as_str();
+		push_line(&mut ics, "BEGIN:VEVENT");
+		push_line(&mut ics, &format!("UID:{kind}-{}@{UID_DOMAIN}", entry.id));
+		push_line(&mut ics, &format!("DTSTAMP:{dtstamp}"));
+		push_line(
+			&mut ics,
+			&format!("DTSTART:{}", format_utc(entry.start_time)),
+		);
+		push_line(&mut ics, &format!("DTEND:{}", format_utc(entry.stop_time)));
+		push_line(
+			&mut ics,
+			&format!("LAST-MODIFIED:{}", format_utc(entry.mtime)),
+		);
+		push_line(&mut ics, &format!("SUMMARY:{}", escape(&entry.title)));
+		if let Some(comment) = &entry.comment {
+			push_line(&mut ics, &format!("DESCRIPTION:{}", escape(comment)));
+		}
+		if let Some(place) = &entry.place {
+			push_line(&mut ics, &format!("LOCATION:{}", escape(place)));
+		}
+		push_line(&mut ics, &format!("CATEGORIES:{kind}"));
+		push_line(&mut ics, "END:VEVENT");
+	}
+
+	push_line(&mut ics, "END:VCALENDAR");
+
+	ics
+}
+
+fn format_utc(time: OffsetDateTime) -> String {
+	// Note: The format has no fallible component, so it cannot fail.
+	time.to_offset(UtcOffset::UTC)
+		.format(UTC_FORMAT)
+		.unwrap_or_default()
+}
+
+/// Escapes a TEXT value (RFC 5545, 3.3.11).
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'\\' => escaped.push_str("\\\\"),
+			';' => escaped.push_str("\\;"),
+			',' => escaped.push_str("\\,"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => (),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Pushes the content `line`, folded at 75 octets (RFC 5545, 3.1),
+/// without splitting the utf-8 chars.
+fn push_line(ics: &mut String, line: &str) {
+	let mut octets = 0;
+	for c in line.chars() {
+		if octets + c.len_utf8() > LINE_MAX_OCTETS {
+			ics.push_str("\r\n ");
+			// the continuation line starts with the folding space
+			octets = 1;
+		}
+		ics.push(c);
+		octets += c.len_utf8();
+	}
+	ics.push_str("\r\n");
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::export::TimeEntryKind;
+	use anyhow::Result;
+	use time::macros::datetime;
+	use time::Duration;
+
+	#[test]
+	fn test_to_ics_event_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_entries = [TimeEntry {
+			kind: TimeEntryKind::TimeRecord,
+			id: 1000,
+			title: "Office; floor 2, room 3".to_string(),
+			comment: None,
+			place: Some("Office; floor 2, room 3".to_string()),
+			start_time: datetime!(2024-03-01 09:30 +01:00),
+			stop_time: datetime!(2024-03-01 12:00 +01:00),
+			mtime: datetime!(2024-03-01 12:00 UTC),
+			rounded_duration: Duration::minutes(150),
+		}];
+
+		// -- Exec
+		let ics = to_ics(&fx_entries, "demo1");
+
+		// -- Check
+		assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+		assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+		assert!(ics.contains("\r\nUID:timerecord-1000@project-a\r\n"));
+		assert!(ics.contains("\r\nDTSTART:20240301T083000Z\r\n"));
+		assert!(ics.contains("\r\nDTEND:20240301T110000Z\r\n"));
+		assert!(ics.contains("\r\nLOCATION:Office\\; floor 2\\, room 3\r\n"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_push_line_folding_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_line = format!("SUMMARY:{}", "é".repeat(40));
+
+		// -- Exec
+		let mut ics = String::new();
+		push_line(&mut ics, &fx_line);
+
+		// -- Check
+		let lines: Vec<&str> = ics.split_terminator("\r\n").collect();
+		assert_eq!(lines.len(), 2);
+		assert!(lines.iter().all(|line| line.len() <= LINE_MAX_OCTETS));
+		assert!(lines[1].starts_with(' '));
+		assert_eq!(lines.concat().replace(' ', ""), fx_line);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,345 @@
+//! HTML and CSV rendering of an `Invoice` (see `model::invoice`).
+//!
+//! - The invoice number is "INV-00042" once issued, and "DRAFT-<id>" before.
+//! - The amounts are in the invoice currency, with 2 decimals
+//!   (e.g., "1234.50 EUR" in the html, "1234.50" in the csv).
+//! - The `duration` is in decimal hours (e.g., "1.50"), as in the timesheets,
+//!   next to the billed `rounded_duration` (see `model::rounding`).
+
+use crate::ctx::Ctx;
+use crate::export::Result;
+use crate::model::client::{Client, ClientBmc};
+use crate::model::invoice::{Invoice, InvoiceBmc, InvoiceLine, InvoiceLineBmc};
+use crate::model::project::{Project, ProjectBmc, ProjectFilter};
+use crate::model::ModelManager;
+use futures::TryStreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+
+// region:    --- Types
+
+/// An invoice, with what its rendering needs.
+#[derive(Debug, Clone)]
+pub struct InvoiceDocument {
+	pub invoice: Invoice,
+	pub client: Client,
+	pub lines: Vec<InvoiceDocumentLine>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InvoiceDocumentLine {
+	pub line: InvoiceLine,
+	/// The current project name (empty if deleted).
+	pub project_name: String,
+}
+
+impl InvoiceDocument {
+	pub fn number(&self) -> String {
+		invoice_number(&self.invoice)
+	}
+}
+
+// endregion: --- Types
+
+/// Loads the invoice `id`, with its client, and its lines by date.
+pub async fn invoice_document(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+) -> Result<InvoiceDocument> {
+	let invoice = InvoiceBmc::get(ctx, mm, id).await?;
+	let client = ClientBmc::get(ctx, mm, invoice.client_id).await?;
+	let mut lines = InvoiceLineBmc::list_for_invoice(ctx, mm, id).await?;
+	lines.sort_by_key(|line| (line.date, line.id));
+
+	let mut project_ids: Vec<i64> = lines.iter().map(|l| l.project_id).collect();
+	project_ids.sort_unstable();
+	project_ids.dedup();
+	// (list, as the projects may have been deleted since)
+	let projects: Vec<Project> = if project_ids.is_empty() {
+		Vec::new()
+	} else {
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"id": {"$in": project_ids}}))?;
+		ProjectBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+			.try_collect()
+			.await?
+	};
+	let project_names: HashMap<i64, String> = projects
+		.into_iter()
+		.map(|project| (project.id, project.name))
+		.collect();
+
+	let lines = lines
+		.into_iter()
+		.map(|line| InvoiceDocumentLine {
+			project_name: project_names
+				.get(&line.project_id)
+				.cloned()
+				.unwrap_or_default(),
+			line,
+		})
+		.collect();
+
+	Ok(InvoiceDocument {
+		invoice,
+		client,
+		lines,
+	})
+}
+
+/// Returns the display number of the invoice (e.g., "INV-00042").
+pub fn invoice_number(invoice: &Invoice) -> String {
+	match invoice.number {
+		Some(number) => format!("INV-{number:05}"),
+		None => format!("DRAFT-{}", invoice.id),
+	}
+}
+
+// region:    --- Html
+
+/// Renders the invoice as a standalone (printable) html page.
+pub fn to_html(doc: &InvoiceDocument) -> String {
+	let invoice = &doc.invoice;
+	let currency = escape(&invoice.currency);
+	let number = escape(&doc.number());
+
+	let mut html = String::new();
+	html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+	html.push_str(&format!("<title>Invoice {number}</title>\n"));
+	html.push_str("</head>\n<body>\n");
+	html.push_str(&format!("<h1>Invoice {number}</h1>\n"));
+
+	// -- Header
+	html.push_str("<p class=\"client\">");
+	html.push_str(&format!("<strong>{}</strong>", escape(&doc.client.name)));
+	for address_line in doc.client.address.lines() {
+		html.push_str(&format!("<br>{}", escape(address_line)));
+	}
+	html.push_str("</p>\n");
+	html.push_str(&format!(
+		"<p class=\"period\">Period: {} to {}</p>\n",
+		invoice.period_start, invoice.period_end
+	));
+	if let Some(issued_at) = invoice.issued_at {
+		html.push_str(&format!(
+			"<p class=\"issued\">Issued: {}</p>\n",
+			issued_at.date()
+		));
+	}
+
+	// -- Lines
+	html.push_str("<table>\n<thead>\n<tr>");
+	let headers = [
+		"Date",
+		"Project",
+		"Description",
+		"Hours",
+		"Billed hours",
+		"Rate",
+		"Amount",
+	];
+	for header in headers {
+		html.push_str(&format!("<th>{header}</th>"));
+	}
+	html.push_str("</tr>\n</thead>\n<tbody>\n");
+	for InvoiceDocumentLine { line, project_name } in &doc.lines {
+		html.push_str(&format!(
+			"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+			 <td>{} {currency}</td><td>{} {currency}</td></tr>\n",
+			line.date,
+			escape(project_name),
+			escape(&line.description),
+			format_hours(line.duration_sec),
+			format_hours(line.rounded_sec),
+			format_cents(line.hourly_rate_cents),
+			format_cents(line.amount_cents),
+		));
+	}
+	html.push_str("</tbody>\n<tfoot>\n");
+	html.push_str(&format!(
+		"<tr><th colspan=\"6\">Total</th><th>{} {currency}</th></tr>\n",
+		format_cents(invoice.total_cents)
+	));
+	html.push_str("</tfoot>\n</table>\n</body>\n</html>\n");
+
+	html
+}
+
+/// Escapes the html special characters of a text.
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+// endregion: --- Html
+
+// region:    --- Csv
+
+/// Renders the invoice lines as csv (header row first, no total row).
+pub fn to_csv(doc: &InvoiceDocument) -> Result<Vec<u8>> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record([
+		"invoice",
+		"date",
+		"project",
+		"description",
+		"duration",
+		"rounded_duration",
+		"hourly_rate",
+		"amount",
+		"currency",
+	])?;
+
+	let number = doc.number();
+	for InvoiceDocumentLine { line, project_name } in &doc.lines {
+		writer.write_record([
+			number.as_str(),
+			&line.date.to_string(),
+			project_name,
+			&line.description,
+			&format_hours(line.duration_sec),
+			&format_hours(line.rounded_sec),
+			&format_cents(line.hourly_rate_cents),
+			&format_cents(line.amount_cents),
+			&doc.invoice.currency,
+		])?;
+	}
+
+	writer
+		.into_inner()
+		.map_err(|err| csv::Error::from(err.into_error()).into())
+}
+
+// endregion: --- Csv
+
+/// e.g., 123450 -> "1234.50"
+fn format_cents(cents: i64) -> String {
+	let sign = if cents < 0 { "-" } else { "" };
+	let cents = cents.unsigned_abs();
+	format!("{sign}{}.{:02}", cents / 100, cents % 100)
+}
+
+/// e.g., 5400 -> "1.50"
+fn format_hours(duration_sec: i64) -> String {
+	format!("{:.2}", duration_sec as f64 / 3600.)
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_utils::time::now_utc;
+	use time::macros::date;
+
+	fn fx_document() -> InvoiceDocument {
+		let now = now_utc();
+		InvoiceDocument {
+			invoice: Invoice {
+				id: 1000,
+				client_id: 1000,
+				number: Some(42),
+				status: "issued".to_string(),
+				period_start: date!(2024 - 03 - 01),
+				period_end: date!(2024 - 03 - 31),
+				currency: "EUR".to_string(),
+				total_cents: 18_000,
+				issued_at: Some(now),
+				cid: 0,
+				ctime: now,
+				mid: 0,
+				mtime: now,
+			},
+			client: Client {
+				id: 1000,
+				name: "Smith & <Sons>".to_string(),
+				address: "1 Main St\nSpringfield".to_string(),
+				currency: "EUR".to_string(),
+				cid: 0,
+				ctime: now,
+				mid: 0,
+				mtime: now,
+			},
+			lines: vec![InvoiceDocumentLine {
+				line: InvoiceLine {
+					id: 1000,
+					invoice_id: 1000,
+					tasktime_id: Some(1000),
+					project_id: 1000,
+					task_id: 1000,
+					user_id: 0,
+					date: date!(2024 - 03 - 04),
+					description: "Write report, part 1".to_string(),
+					duration_sec: 5400,
+					rounded_sec: 5400,
+					hourly_rate_cents: 12_000,
+					amount_cents: 18_000,
+					cid: 0,
+					ctime: now,
+					mid: 0,
+					mtime: now,
+				},
+				project_name: "Project A".to_string(),
+			}],
+		}
+	}
+
+	#[test]
+	fn test_to_csv_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_doc = fx_document();
+
+		// -- Exec
+		let csv = to_csv(&fx_doc)?;
+
+		// -- Check
+		assert_eq!(
+			String::from_utf8(csv)?,
+			"invoice,date,project,description,duration,rounded_duration,\
+			 hourly_rate,amount,currency\n\
+			 INV-00042,2024-03-04,Project A,\"Write report, part 1\",1.50,1.50,\
+			 120.00,180.00,EUR\n"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_to_html_escaped() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_doc = fx_document();
+
+		// -- Exec
+		let html = to_html(&fx_doc);
+
+		// -- Check
+		assert!(html.contains("<h1>Invoice INV-00042</h1>"));
+		assert!(html.contains("<strong>Smith &amp; &lt;Sons&gt;</strong>"));
+		assert!(html.contains("<br>Springfield"));
+		assert!(html.contains("<td>180.00 EUR</td>"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_format_cents() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(format_cents(123_450), "1234.50");
+		assert_eq!(format_cents(5), "0.05");
+		assert_eq!(format_cents(-5), "-0.05");
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
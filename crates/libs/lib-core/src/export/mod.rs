@@ -0,0 +1,463 @@
+//! Export of the tracked time (e.g., timesheets, calendars).
+//!
+//! Design:
+//!
+//! - `time_entries` loads the `TaskTime`s and `TimeRecord`s of the ctx user
+//!   overlapping a `DateRange` as `TimeEntry`s, ordered by start time.
+//!   (no list limit cap, see `base::list_stream`)
+//! - `csv::to_csv` renders the entries as a timesheet, with configurable
+//!   columns and date/time formats (see `CsvOptions`).
+//! - `ics::to_ics` renders the entries as an RFC 5545 calendar,
+//!   with one `VEVENT` per entry.
+//! - `reconciliation::reconciliation` compares the presence and worked time
+//!   of the entries, per day, week or month, and per place.
+//! - `flex::flex_balance` computes the flex-time balance of the timerecords
+//!   against the work schedules and absences, per day, week or month.
+//! - Both mark the `Absence`s of their periods (e.g., a holiday), so a day
+//!   off is not reported as a day without time (see `period_absences`).
+//! - `travel::travel_report` sums the `Travel`s time and distance per month.
+//! - `invoice::to_html` and `invoice::to_csv` render an `Invoice`
+//!   (see `model::invoice`).
+//! - The entries carry their duration rounded per the ctx user
+//!   `RoundingPolicy`s (see `model::rounding`), reported next to the raw one.
+//!
+//! Note: The web-server exposes both as authenticated downloads, and the ics
+//!       as a read-only calendar url, tokenized with the user `calendar_token`
+//!       (see `UserBmc::calendar_token`).
+
+// region:    --- Modules
+
+pub mod csv;
+mod error;
+pub mod flex;
+pub mod ics;
+pub mod invoice;
+pub mod reconciliation;
+pub mod travel;
+
+pub use self::error::{Error, Result};
+
+use crate::ctx::Ctx;
+use crate::model::absence::{Absence, AbsenceBmc};
+use crate::model::place::{Place, PlaceBmc};
+use crate::model::rounding::RoundingPolicyBmc;
+use crate::model::task::{Task, TaskBmc};
+use crate::model::tasktime::{TaskTime, TaskTimeBmc, TaskTimeFilter};
+use crate::model::timerecord::{TimeRecord, TimeRecordBmc, TimeRecordFilter};
+use crate::model::ModelManager;
+use futures::TryStreamExt;
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{Date, Duration, OffsetDateTime, UtcOffset};
+
+// endregion: --- Modules
+
+const DATE_FORMAT: &[FormatItem<'static>] =
+	format_description!("[year]-[month]-[day]");
+const UTC_OFFSET_FORMAT: &[FormatItem<'static>] =
+	format_description!("[offset_hour sign:mandatory]:[offset_minute]");
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeEntryKind {
+	TaskTime,
+	TimeRecord,
+}
+
+impl TimeEntryKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TimeEntryKind::TaskTime => "tasktime",
+			TimeEntryKind::TimeRecord => "timerecord",
+		}
+	}
+}
+
+/// A `TaskTime` or `TimeRecord`, as exported.
+#[derive(Debug, Clone)]
+pub struct TimeEntry {
+	pub kind: TimeEntryKind,
+	pub id: i64,
+
+	/// The task title (for a `TaskTime`), or the place (for a `TimeRecord`).
+	pub title: String,
+	pub comment: Option<String>,
+	pub place: Option<String>,
+
+	pub start_time: OffsetDateTime,
+	pub stop_time: OffsetDateTime,
+	pub mtime: OffsetDateTime,
+
+	/// The `duration` per the rounding policy of the entry project
+	/// (or the user default one for a `TimeRecord`).
+	pub rounded_duration: Duration,
+}
+
+impl TimeEntry {
+	pub fn duration(&self) -> Duration {
+		self.stop_time - self.start_time
+	}
+}
+
+/// An `Absence` of a report period (e.g., to mark a holiday).
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ReportAbsence {
+	/// e.g., "2024-03-04"
+	pub date: String,
+	/// The `AbsenceKind` (e.g., "holiday").
+	pub kind: String,
+	/// The hours off, or `None` for the full day.
+	pub hours: Option<f64>,
+	/// e.g., the holiday name.
+	pub comment: String,
+}
+
+/// The `[from, to)` range of the exported entries.
+#[derive(Debug, Clone, Copy)]
+pub struct DateRange {
+	pub from: OffsetDateTime,
+	pub to: OffsetDateTime,
+}
+
+impl DateRange {
+	pub fn new(from: OffsetDateTime, to: OffsetDateTime) -> Result<Self> {
+		if to <= from {
+			return Err(Error::DateRangeInvalid {
+				from: format_time(from),
+				to: format_time(to),
+			});
+		}
+
+		Ok(Self { from, to })
+	}
+
+	/// Parses `from` and `to`, each being an Rfc3339 time or a date
+	/// (e.g., "2024-03-01", at midnight in `offset`).
+	///
+	/// Note: A `to` date is inclusive (i.e., up to the next day midnight).
+	pub fn parse(from: &str, to: &str, offset: UtcOffset) -> Result<Self> {
+		let from = parse_moment(from, offset, false)?;
+		let to = parse_moment(to, offset, true)?;
+
+		Self::new(from, to)
+	}
+
+	/// The range from `days_before` now, up to `days_after` now
+	/// (e.g., for the subscribed calendars).
+	pub fn around_now(days_before: i64, days_after: i64) -> Self {
+		let now = now_utc();
+		Self {
+			from: now - Duration::days(days_before),
+			to: now + Duration::days(days_after),
+		}
+	}
+}
+
+/// Parses an utc offset (e.g., "+02:00", "-05:30", or "Z").
+pub fn parse_utc_offset(offset: &str) -> Result<UtcOffset> {
+	if offset == "Z" {
+		return Ok(UtcOffset::UTC);
+	}
+
+	UtcOffset::parse(offset, UTC_OFFSET_FORMAT)
+		.map_err(|_| Error::UtcOffsetParseFail(offset.to_string()))
+}
+
+fn parse_moment(
+	moment: &str,
+	offset: UtcOffset,
+	end_of_day: bool,
+) -> Result<OffsetDateTime> {
+	if let Ok(time) = OffsetDateTime::parse(moment, &Rfc3339) {
+		return Ok(time);
+	}
+
+	let date = Date::parse(moment, DATE_FORMAT)
+		.map_err(|_| Error::DateParseFail(moment.to_string()))?;
+	let date = if end_of_day {
+		date.next_day()
+			.ok_or_else(|| Error::DateParseFail(moment.to_string()))?
+	} else {
+		date
+	};
+
+	Ok(date.midnight().assume_offset(offset))
+}
+
+// endregion: --- Types
+
+// region:    --- Time Entries
+
+/// Returns the `TaskTime`s and `TimeRecord`s of the ctx user overlapping
+/// the `range`, ordered by start time.
+///
+/// Note: The timerecord places are their current `Place` names
+///       (i.e., renaming a place renames it in the past entries too).
+pub async fn time_entries(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	range: DateRange,
+) -> Result<Vec<TimeEntry>> {
+	// -- Overlapping the range, for the ctx user
+	let filter_json = json!({
+		"cid": ctx.user_id(),
+		"start_time": {"$lt": format_time(range.to)},
+		"stop_time": {"$gt": format_time(range.from)},
+	});
+
+	// -- Load the tasktimes, with their task titles
+	let filter: TaskTimeFilter = serde_json::from_value(filter_json.clone())?;
+	let tasktimes: Vec<TaskTime> =
+		TaskTimeBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+			.try_collect()
+			.await?;
+
+	let mut task_ids: Vec<i64> = tasktimes.iter().map(|tt| tt.task_id).collect();
+	task_ids.sort_unstable();
+	task_ids.dedup();
+	let tasks: Vec<Task> = TaskBmc::get_many(ctx, mm, &task_ids).await?;
+	let task_project_ids: HashMap<i64, i64> = tasks
+		.iter()
+		.map(|task| (task.id, task.project_id))
+		.collect();
+	let titles: HashMap<i64, String> = tasks
+		.into_iter()
+		.map(|task| (task.id, task.title))
+		.collect();
+
+	// -- Load the timerecords
+	let filter: TimeRecordFilter = serde_json::from_value(filter_json)?;
+	let timerecords: Vec<TimeRecord> =
+		TimeRecordBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+			.try_collect()
+			.await?;
+
+	// -- Load the current names of the timerecord places
+	let mut place_ids: Vec<i64> =
+		timerecords.iter().filter_map(|tr| tr.place_id).collect();
+	place_ids.sort_unstable();
+	place_ids.dedup();
+	let places: Vec<Place> = PlaceBmc::get_many(ctx, mm, &place_ids).await?;
+	let place_names: HashMap<i64, String> = places
+		.into_iter()
+		.map(|place| (place.id, place.name))
+		.collect();
+	let place_name = |tr: &TimeRecord| {
+		tr.place_id
+			.and_then(|place_id| place_names.get(&place_id).cloned())
+			.unwrap_or_else(|| tr.place.clone())
+	};
+
+	// -- Round the durations (per project, or the user default)
+	let roundings = RoundingPolicyBmc::roundings_for_user(ctx, mm).await?;
+	let rounded_duration = |project_id: Option<i64>, duration: Duration| {
+		Duration::seconds(roundings.round(project_id, duration.whole_seconds()))
+	};
+
+	// -- Merge as TimeEntries
+	let mut entries: Vec<TimeEntry> = tasktimes
+		.into_iter()
+		.map(|tt| TimeEntry {
+			kind: TimeEntryKind::TaskTime,
+			id: tt.id,
+			title: titles.get(&tt.task_id).cloned().unwrap_or_default(),
+			comment: Some(tt.comment).filter(|c| !c.is_empty()),
+			place: None,
+			start_time: tt.start_time,
+			stop_time: tt.stop_time,
+			mtime: tt.mtime,
+			rounded_duration: rounded_duration(
+				task_project_ids.get(&tt.task_id).copied(),
+				tt.stop_time - tt.start_time,
+			),
+		})
+		.chain(timerecords.iter().map(|tr| TimeEntry {
+			kind: TimeEntryKind::TimeRecord,
+			id: tr.id,
+			title: place_name(tr),
+			comment: None,
+			place: Some(place_name(tr)),
+			start_time: tr.start_time,
+			stop_time: tr.stop_time,
+			mtime: tr.mtime,
+			rounded_duration: rounded_duration(None, tr.stop_time - tr.start_time),
+		}))
+		.collect();
+	entries.sort_by_key(|entry| (entry.start_time, entry.kind.as_str(), entry.id));
+
+	Ok(entries)
+}
+
+// endregion: --- Time Entries
+
+// region:    --- Absences
+
+/// Returns the `Absence`s of the ctx user on the days overlapping `range`
+/// (in `offset`), by date.
+pub async fn range_absences(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	range: DateRange,
+	offset: UtcOffset,
+) -> Result<Vec<Absence>> {
+	let from = range.from.to_offset(offset).date();
+	let to = range.to.to_offset(offset).date() + Duration::days(1);
+	let absences = AbsenceBmc::list_for_user(ctx, mm, from, to).await?;
+
+	Ok(absences)
+}
+
+/// Returns the `absences` on the days overlapping the `[start, stop)` period
+/// (in `offset`), by date.
+pub fn period_absences(
+	absences: &[Absence],
+	(start, stop): (OffsetDateTime, OffsetDateTime),
+	offset: UtcOffset,
+) -> Vec<ReportAbsence> {
+	let first_day = start.to_offset(offset).date();
+
+	absences
+		.iter()
+		.filter(|absence| {
+			absence.date >= first_day
+				&& absence.date.midnight().assume_offset(offset) < stop
+		})
+		.map(|absence| ReportAbsence {
+			date: absence.date.to_string(),
+			kind: absence.kind.clone(),
+			hours: absence.hours,
+			comment: absence.comment.clone(),
+		})
+		.collect()
+}
+
+// endregion: --- Absences
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::tasktime::TaskTimeForCreate;
+	use crate::model::timerecord::TimeRecordForCreate;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_time_entries_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_start = now_utc() + Duration::days(400);
+		let fx_range = DateRange::new(fx_start, fx_start + Duration::hours(8))?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"project for export test_time_entries_ok",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"task for export test_time_entries_ok",
+		)
+		.await?;
+		// in range
+		let fx_tasktime_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_time_entries_ok 01".to_string(),
+				start_time: Some(fx_start + Duration::hours(2)),
+				stop_time: Some(fx_start + Duration::hours(3)),
+				billable: None,
+			},
+		)
+		.await?;
+		// overlapping the range start
+		let fx_timerecord_id = TimeRecordBmc::create(
+			&ctx,
+			&mm,
+			TimeRecordForCreate {
+				place: "test_time_entries_ok office".to_string(),
+				place_id: None,
+				start_time: Some(fx_start - Duration::hours(1)),
+				stop_time: Some(fx_start + Duration::hours(1)),
+			},
+		)
+		.await?;
+		// after the range
+		let fx_after_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_time_entries_ok 02".to_string(),
+				start_time: Some(fx_start + Duration::hours(9)),
+				stop_time: Some(fx_start + Duration::hours(10)),
+				billable: None,
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let entries = time_entries(&ctx, &mm, fx_range).await?;
+
+		// -- Check
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].kind, TimeEntryKind::TimeRecord);
+		assert_eq!(entries[0].id, fx_timerecord_id);
+		assert_eq!(entries[1].kind, TimeEntryKind::TaskTime);
+		assert_eq!(entries[1].id, fx_tasktime_id);
+		assert_eq!(entries[1].title, "task for export test_time_entries_ok");
+		assert_eq!(entries[1].duration(), Duration::hours(1));
+
+		// -- Clean
+		TimeRecordBmc::delete(&ctx, &mm, fx_timerecord_id).await?;
+		TaskTimeBmc::delete(&ctx, &mm, fx_after_id).await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_date_range_parse_dates_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_offset = parse_utc_offset("+02:00")?;
+
+		// -- Exec
+		let range = DateRange::parse("2024-03-01", "2024-03-31", fx_offset)?;
+
+		// -- Check
+		assert_eq!(format_time(range.from), "2024-03-01T00:00:00+02:00");
+		assert_eq!(format_time(range.to), "2024-04-01T00:00:00+02:00");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_date_range_parse_err_invalid() -> Result<()> {
+		// -- Exec
+		let res = DateRange::parse("2024-03-31", "2024-03-01", UtcOffset::UTC);
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::DateRangeInvalid { .. })),
+			"DateRangeInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
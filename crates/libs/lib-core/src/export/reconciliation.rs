@@ -0,0 +1,511 @@
+//! Reconciliation of the presence time (`TimeRecord`s) and the worked time
+//! (`TaskTime`s), per day, week or month, and per place.
+//!
+//! For each period (and each place of the period):
+//!
+//! - `presence_sec`: the time at the place (overlapping timerecords counted once).
+//! - `worked_sec`: the tasktimes time at the place
+//!   (overlapping tasktimes each counted).
+//! - `unaccounted_sec`: the presence time without any tasktime.
+//! - `double_counted_sec`: the time counted in more than one tasktime.
+//!
+//! The tasktimes out of any timerecord are reported under no place
+//! (`place: None`), and the period `total` covers all the places.
+//! The period `worked_rounded_sec` is the billable view of the worked time,
+//! i.e., the rounded durations (see `model::rounding`) of the tasktimes
+//! starting in the period (as a rounded duration can not be clipped).
+//! The `absences` of the period days are listed as well (e.g., a holiday).
+//!
+//! Note: The periods are the days (or Monday based weeks, or months) in the
+//!       report `utc_offset`, clipped to the report range.
+
+use crate::ctx::Ctx;
+use crate::export::{
+	period_absences, range_absences, time_entries, DateRange, ReportAbsence, Result,
+	TimeEntry, TimeEntryKind,
+};
+use crate::model::absence::Absence;
+use crate::model::ModelManager;
+use lib_utils::time::Rfc3339;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::collections::BTreeMap;
+use time::{Date, Duration, Month, OffsetDateTime, UtcOffset};
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+	Day,
+	Week,
+	Month,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Reconciliation {
+	pub presence_sec: i64,
+	pub worked_sec: i64,
+	pub unaccounted_sec: i64,
+	pub double_counted_sec: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlaceReconciliation {
+	/// `None` for the tasktimes out of any timerecord.
+	pub place: Option<String>,
+	#[serde(flatten)]
+	pub reconciliation: Reconciliation,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PeriodReconciliation {
+	/// The first day of the period (e.g., "2024-03-04").
+	pub date: String,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop: OffsetDateTime,
+
+	pub total: Reconciliation,
+	/// The rounded duration of the tasktimes starting in the period.
+	pub worked_rounded_sec: i64,
+	/// Ordered by place (the no place last).
+	pub places: Vec<PlaceReconciliation>,
+	/// The absences of the period days, by date.
+	pub absences: Vec<ReportAbsence>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ReconciliationReport {
+	pub period: ReportPeriod,
+	pub periods: Vec<PeriodReconciliation>,
+}
+
+/// A `[start, stop)` time interval.
+pub(super) type Interval = (OffsetDateTime, OffsetDateTime);
+
+// endregion: --- Types
+
+/// Returns the reconciliation of the ctx user entries in `range`.
+pub async fn reconciliation(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	range: DateRange,
+	period: ReportPeriod,
+	offset: UtcOffset,
+) -> Result<ReconciliationReport> {
+	let entries = time_entries(ctx, mm, range).await?;
+	let absences = range_absences(ctx, mm, range, offset).await?;
+
+	Ok(reconcile_entries(
+		&entries, &absences, range, period, offset,
+	))
+}
+
+/// Reconciles the `entries` (e.g., from `time_entries`) per `period`.
+pub fn reconcile_entries(
+	entries: &[TimeEntry],
+	absences: &[Absence],
+	range: DateRange,
+	period: ReportPeriod,
+	offset: UtcOffset,
+) -> ReconciliationReport {
+	let periods = period_bounds(range, period, offset)
+		.into_iter()
+		.map(|(date, bounds)| {
+			let absences = period_absences(absences, bounds, offset);
+			reconcile_period(entries, date, bounds, absences)
+		})
+		.collect();
+
+	ReconciliationReport { period, periods }
+}
+
+fn reconcile_period(
+	entries: &[TimeEntry],
+	date: Date,
+	bounds: Interval,
+	absences: Vec<ReportAbsence>,
+) -> PeriodReconciliation {
+	// -- Clip the entries to the period
+	let mut tasks: Vec<Interval> = Vec::new();
+	let mut places: BTreeMap<&str, Vec<Interval>> = BTreeMap::new();
+	let mut worked_rounded = Duration::ZERO;
+	for entry in entries {
+		if entry.kind == TimeEntryKind::TaskTime
+			&& bounds.0 <= entry.start_time
+			&& entry.start_time < bounds.1
+		{
+			worked_rounded += entry.rounded_duration;
+		}
+		let Some(interval) = clip((entry.start_time, entry.stop_time), bounds)
+		else {
+			continue;
+		};
+		match entry.kind {
+			TimeEntryKind::TaskTime => tasks.push(interval),
+			TimeEntryKind::TimeRecord => places
+				.entry(entry.place.as_deref().unwrap_or_default())
+				.or_default()
+				.push(interval),
+		}
+	}
+	let places: BTreeMap<&str, Vec<Interval>> = places
+		.into_iter()
+		.map(|(place, intervals)| (place, merge(intervals)))
+		.collect();
+	let presence = merge(places.values().flatten().copied().collect());
+
+	// -- Reconcile per place
+	let mut place_reconciliations: Vec<PlaceReconciliation> = places
+		.iter()
+		.map(|(place, intervals)| PlaceReconciliation {
+			place: Some(place.to_string()),
+			reconciliation: reconcile(intervals, &tasks, intervals),
+		})
+		.collect();
+
+	let outside = complement(&presence, bounds);
+	let unplaced = reconcile(&[], &tasks, &outside);
+	if unplaced.worked_sec > 0 {
+		place_reconciliations.push(PlaceReconciliation {
+			place: None,
+			reconciliation: unplaced,
+		});
+	}
+
+	PeriodReconciliation {
+		date: date.to_string(),
+		start: bounds.0,
+		stop: bounds.1,
+		total: reconcile(&presence, &tasks, &[bounds]),
+		worked_rounded_sec: worked_rounded.whole_seconds(),
+		places: place_reconciliations,
+		absences,
+	}
+}
+
+/// Reconciles the `presence` (merged) with the `tasks` time within `scope`
+/// (merged).
+fn reconcile(
+	presence: &[Interval],
+	tasks: &[Interval],
+	scope: &[Interval],
+) -> Reconciliation {
+	let worked: Duration = tasks
+		.iter()
+		.map(|task| length(&intersect(&[*task], scope)))
+		.sum();
+	let tasks = merge(tasks.to_vec());
+	let worked_once = length(&intersect(&tasks, scope));
+	let presence_worked = length(&intersect(&tasks, presence));
+
+	Reconciliation {
+		presence_sec: length(presence).whole_seconds(),
+		worked_sec: worked.whole_seconds(),
+		unaccounted_sec: (length(presence) - presence_worked).whole_seconds(),
+		double_counted_sec: (worked - worked_once).whole_seconds(),
+	}
+}
+
+// region:    --- Periods
+
+/// The periods (first day, and bounds clipped to the range) of `range`.
+pub(super) fn period_bounds(
+	range: DateRange,
+	period: ReportPeriod,
+	offset: UtcOffset,
+) -> Vec<(Date, Interval)> {
+	let date = range.from.to_offset(offset).date();
+	let mut date = match period {
+		ReportPeriod::Day => date,
+		ReportPeriod::Week => {
+			let weekday = date.weekday().number_days_from_monday();
+			date - Duration::days(weekday.into())
+		}
+		ReportPeriod::Month => date.replace_day(1).unwrap_or(date),
+	};
+
+	let mut periods = Vec::new();
+	loop {
+		let start = date.midnight().assume_offset(offset);
+		if start >= range.to {
+			break;
+		}
+		let next = next_period_date(date, period);
+		let stop = next.midnight().assume_offset(offset);
+		periods.push((date, (start.max(range.from), stop.min(range.to))));
+		date = next;
+	}
+
+	periods
+}
+
+/// The first day of the period following the one starting at `date`.
+fn next_period_date(date: Date, period: ReportPeriod) -> Date {
+	match period {
+		ReportPeriod::Day => date + Duration::days(1),
+		ReportPeriod::Week => date + Duration::days(7),
+		ReportPeriod::Month => {
+			let (year, month) = match date.month() {
+				Month::December => (date.year() + 1, Month::January),
+				month => (date.year(), month.next()),
+			};
+			// Note: Cannot fail, the first day of a month being valid.
+			Date::from_calendar_date(year, month, 1).unwrap_or(date)
+		}
+	}
+}
+
+// endregion: --- Periods
+
+// region:    --- Intervals
+
+pub(super) fn clip(interval: Interval, bounds: Interval) -> Option<Interval> {
+	let clipped = (interval.0.max(bounds.0), interval.1.min(bounds.1));
+
+	(clipped.0 < clipped.1).then_some(clipped)
+}
+
+/// The union of `intervals`, as sorted and disjoint intervals.
+pub(super) fn merge(mut intervals: Vec<Interval>) -> Vec<Interval> {
+	intervals.retain(|(start, stop)| start < stop);
+	intervals.sort();
+
+	let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+	for (start, stop) in intervals {
+		match merged.last_mut() {
+			Some(last) if start <= last.1 => last.1 = last.1.max(stop),
+			_ => merged.push((start, stop)),
+		}
+	}
+
+	merged
+}
+
+/// The intersection of two merged interval lists.
+pub(super) fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+	let mut intersection = Vec::new();
+	let (mut i, mut j) = (0, 0);
+
+	while i < a.len() && j < b.len() {
+		if let Some(interval) = clip(a[i], b[j]) {
+			intersection.push(interval);
+		}
+		if a[i].1 < b[j].1 {
+			i += 1;
+		} else {
+			j += 1;
+		}
+	}
+
+	intersection
+}
+
+/// The parts of `bounds` out of `intervals` (merged).
+fn complement(intervals: &[Interval], bounds: Interval) -> Vec<Interval> {
+	let mut complement = Vec::new();
+	let mut cursor = bounds.0;
+
+	for &(start, stop) in intervals {
+		if start > cursor {
+			complement.push((cursor, start.min(bounds.1)));
+		}
+		cursor = cursor.max(stop);
+	}
+	if cursor < bounds.1 {
+		complement.push((cursor, bounds.1));
+	}
+
+	complement.retain(|(start, stop)| start < stop);
+	complement
+}
+
+pub(super) fn length(intervals: &[Interval]) -> Duration {
+	intervals.iter().map(|(start, stop)| *stop - *start).sum()
+}
+
+// endregion: --- Intervals
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::datetime;
+
+	/// An entry of the hours `start_h` to `stop_h` of the 2024-03-04 (a Monday).
+	fn fx_entry(place: Option<&str>, start_h: i64, stop_h: i64) -> TimeEntry {
+		let day = datetime!(2024-03-04 00:00 UTC);
+		let kind = match place {
+			Some(_) => TimeEntryKind::TimeRecord,
+			None => TimeEntryKind::TaskTime,
+		};
+
+		TimeEntry {
+			kind,
+			id: 0,
+			title: String::new(),
+			comment: None,
+			place: place.map(str::to_string),
+			start_time: day + Duration::hours(start_h),
+			stop_time: day + Duration::hours(stop_h),
+			mtime: day,
+			rounded_duration: Duration::hours(stop_h - start_h),
+		}
+	}
+
+	fn fx_range(from_h: i64, to_h: i64) -> Result<DateRange> {
+		let day = datetime!(2024-03-04 00:00 UTC);
+		let range = DateRange::new(
+			day + Duration::hours(from_h),
+			day + Duration::hours(to_h),
+		)?;
+
+		Ok(range)
+	}
+
+	#[test]
+	fn test_reconcile_entries_day_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut fx_entries = [
+			fx_entry(Some("Office"), 8, 12),
+			fx_entry(Some("Home"), 13, 17),
+			// 2h at the office, 1h double counted
+			fx_entry(None, 8, 10),
+			fx_entry(None, 9, 10),
+			// 1h at home, 1h out of any place
+			fx_entry(None, 16, 18),
+			// the next day
+			fx_entry(Some("Office"), 32, 33),
+		];
+		// (rounded up to 1h15)
+		fx_entries[3].rounded_duration = Duration::minutes(75);
+
+		// -- Exec
+		let report = reconcile_entries(
+			&fx_entries,
+			&[],
+			fx_range(0, 48)?,
+			ReportPeriod::Day,
+			UtcOffset::UTC,
+		);
+
+		// -- Check
+		assert_eq!(report.periods.len(), 2);
+		let day = &report.periods[0];
+		assert_eq!(day.date, "2024-03-04");
+		let hour = 3600;
+		assert_eq!(
+			day.total,
+			Reconciliation {
+				presence_sec: 8 * hour,
+				worked_sec: 5 * hour,
+				unaccounted_sec: 5 * hour,
+				double_counted_sec: hour,
+			}
+		);
+		assert_eq!(day.worked_rounded_sec, 5 * hour + 15 * 60);
+		let places: Vec<(Option<&str>, Reconciliation)> = day
+			.places
+			.iter()
+			.map(|place| (place.place.as_deref(), place.reconciliation))
+			.collect();
+		assert_eq!(
+			places,
+			vec![
+				(
+					Some("Home"),
+					Reconciliation {
+						presence_sec: 4 * hour,
+						worked_sec: hour,
+						unaccounted_sec: 3 * hour,
+						double_counted_sec: 0,
+					}
+				),
+				(
+					Some("Office"),
+					Reconciliation {
+						presence_sec: 4 * hour,
+						worked_sec: 3 * hour,
+						unaccounted_sec: 2 * hour,
+						double_counted_sec: hour,
+					}
+				),
+				(
+					None,
+					Reconciliation {
+						presence_sec: 0,
+						worked_sec: hour,
+						unaccounted_sec: 0,
+						double_counted_sec: 0,
+					}
+				),
+			]
+		);
+		assert_eq!(report.periods[1].total.presence_sec, hour);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_reconcile_entries_week_clipped_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		// (from the Wednesday noon, to the next Tuesday)
+		let fx_range = fx_range(2 * 24 + 12, 8 * 24)?;
+		let fx_entries = [
+			fx_entry(Some("Office"), 2 * 24 + 8, 2 * 24 + 16),
+			fx_entry(Some("Office"), 7 * 24 + 8, 7 * 24 + 10),
+		];
+
+		// -- Exec
+		let report = reconcile_entries(
+			&fx_entries,
+			&[],
+			fx_range,
+			ReportPeriod::Week,
+			UtcOffset::UTC,
+		);
+
+		// -- Check
+		let dates: Vec<&str> = report
+			.periods
+			.iter()
+			.map(|period| period.date.as_str())
+			.collect();
+		assert_eq!(dates, vec!["2024-03-04", "2024-03-11"]);
+		assert_eq!(report.periods[0].start, fx_range.from);
+		assert_eq!(report.periods[0].total.presence_sec, 4 * 3600);
+		assert_eq!(report.periods[1].total.presence_sec, 2 * 3600);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_period_bounds_month_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_range = DateRange::new(
+			datetime!(2023-12-15 00:00 UTC),
+			datetime!(2024-02-10 00:00 UTC),
+		)?;
+
+		// -- Exec
+		let periods = period_bounds(fx_range, ReportPeriod::Month, UtcOffset::UTC);
+
+		// -- Check
+		let dates: Vec<String> =
+			periods.iter().map(|(date, _)| date.to_string()).collect();
+		assert_eq!(dates, vec!["2023-12-01", "2024-01-01", "2024-02-01"]);
+		assert_eq!(periods[0].1 .0, fx_range.from);
+		assert_eq!(periods[1].1 .1, datetime!(2024-02-01 00:00 UTC));
+		assert_eq!(periods[2].1 .1, fx_range.to);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
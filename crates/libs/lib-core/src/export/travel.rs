@@ -0,0 +1,187 @@
+//! Monthly commute time and mileage of the `Travel`s.
+//!
+//! - A travel counts in the month (in the report `utc_offset`)
+//!   of its start time.
+//! - Only the months with travels are reported.
+
+use crate::ctx::Ctx;
+use crate::export::{DateRange, Result};
+use crate::model::travel::{Travel, TravelBmc, TravelFilter};
+use crate::model::ModelManager;
+use futures::TryStreamExt;
+use lib_utils::time::format_time;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use time::UtcOffset;
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct TravelTotal {
+	pub count: i64,
+	pub travel_sec: i64,
+	/// The sum of the known distances.
+	pub distance_km: f64,
+}
+
+impl TravelTotal {
+	fn add(&mut self, travel: &Travel) {
+		self.count += 1;
+		self.travel_sec += (travel.stop_time - travel.start_time).whole_seconds();
+		self.distance_km += travel.distance_km.unwrap_or_default();
+	}
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TravelModeTotal {
+	pub mode: String,
+	#[serde(flatten)]
+	pub total: TravelTotal,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TravelMonth {
+	/// e.g., "2024-03"
+	pub month: String,
+	pub total: TravelTotal,
+	/// Ordered by mode.
+	pub modes: Vec<TravelModeTotal>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TravelReport {
+	pub months: Vec<TravelMonth>,
+}
+
+// endregion: --- Types
+
+/// Returns the monthly totals of the ctx user travels starting in `range`.
+pub async fn travel_report(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	range: DateRange,
+	offset: UtcOffset,
+) -> Result<TravelReport> {
+	let filter: TravelFilter = serde_json::from_value(json!({
+		"cid": ctx.user_id(),
+		"start_time": {"$gte": format_time(range.from), "$lt": format_time(range.to)},
+	}))?;
+	let travels: Vec<Travel> =
+		TravelBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+			.try_collect()
+			.await?;
+
+	Ok(summarize_travels(&travels, offset))
+}
+
+/// Sums the `travels` per month (in `offset`), and per mode.
+pub fn summarize_travels(travels: &[Travel], offset: UtcOffset) -> TravelReport {
+	let mut months: BTreeMap<(i32, u8), BTreeMap<&str, TravelTotal>> =
+		BTreeMap::new();
+	for travel in travels {
+		let date = travel.start_time.to_offset(offset).date();
+		months
+			.entry((date.year(), date.month() as u8))
+			.or_default()
+			.entry(travel.mode.as_str())
+			.or_default()
+			.add(travel);
+	}
+
+	let months = months
+		.into_iter()
+		.map(|((year, month), modes)| {
+			let mut total = TravelTotal::default();
+			for mode_total in modes.values() {
+				total.count += mode_total.count;
+				total.travel_sec += mode_total.travel_sec;
+				total.distance_km += mode_total.distance_km;
+			}
+
+			TravelMonth {
+				month: format!("{year:04}-{month:02}"),
+				total,
+				modes: modes
+					.into_iter()
+					.map(|(mode, total)| TravelModeTotal {
+						mode: mode.to_string(),
+						total,
+					})
+					.collect(),
+			}
+		})
+		.collect();
+
+	TravelReport { months }
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::{datetime, offset};
+	use time::{Duration, OffsetDateTime};
+
+	fn fx_travel(
+		mode: &str,
+		start_time: OffsetDateTime,
+		minutes: i64,
+		distance_km: Option<f64>,
+	) -> Travel {
+		Travel {
+			id: 0,
+			from_place_id: 1,
+			to_place_id: 2,
+			mode: mode.to_string(),
+			distance_km,
+			start_time,
+			stop_time: start_time + Duration::minutes(minutes),
+			cid: 0,
+			ctime: start_time,
+			mid: 0,
+			mtime: start_time,
+		}
+	}
+
+	#[test]
+	fn test_summarize_travels_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_travels = [
+			fx_travel("car", datetime!(2024-03-04 07:30 UTC), 30, Some(20.)),
+			fx_travel("car", datetime!(2024-03-04 17:00 UTC), 40, Some(20.)),
+			fx_travel("bike", datetime!(2024-03-05 08:00 UTC), 20, None),
+			// in April in +02:00
+			fx_travel("car", datetime!(2024-03-31 23:00 UTC), 30, Some(5.5)),
+		];
+
+		// -- Exec
+		let report = summarize_travels(&fx_travels, offset!(+2));
+
+		// -- Check
+		assert_eq!(report.months.len(), 2);
+		let march = &report.months[0];
+		assert_eq!(march.month, "2024-03");
+		assert_eq!(
+			march.total,
+			TravelTotal {
+				count: 3,
+				travel_sec: 90 * 60,
+				distance_km: 40.,
+			}
+		);
+		let modes: Vec<(&str, i64)> = march
+			.modes
+			.iter()
+			.map(|mode| (mode.mode.as_str(), mode.total.count))
+			.collect();
+		assert_eq!(modes, vec![("bike", 1), ("car", 2)]);
+		assert_eq!(report.months[1].month, "2024-04");
+		assert_eq!(report.months[1].total.distance_km, 5.5);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
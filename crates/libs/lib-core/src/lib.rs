@@ -1,5 +1,6 @@
 pub mod config;
 pub mod ctx;
+pub mod export;
 pub mod model;
 
 // #[cfg(test)] // Commented during early development.
@@ -0,0 +1,306 @@
+//! Absences, i.e., the days (or part of days) off of the user
+//! (e.g., vacation, sick, holiday), crediting their scheduled hours.
+//!
+//! (see `export::flex` for the flex-time balance)
+
+use crate::ctx::Ctx;
+use crate::model::base;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::{date_to_sea_value, iso_date, time_to_sea_value};
+use crate::model::{Error, ModelManager};
+use futures::TryStreamExt;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{ListOptions, OpValsFloat64, OpValsString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use time::Date;
+
+/// The max hours of a partial day absence.
+const ABSENCE_HOURS_MAX: f64 = 24.;
+
+// region:    --- Absence Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AbsenceKind {
+	Vacation,
+	Sick,
+	Holiday,
+	Other,
+}
+
+impl AbsenceKind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AbsenceKind::Vacation => "vacation",
+			AbsenceKind::Sick => "sick",
+			AbsenceKind::Holiday => "holiday",
+			AbsenceKind::Other => "other",
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Absence {
+	pub id: i64,
+
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub date: Date,
+	/// The `AbsenceKind` (e.g., "vacation").
+	pub kind: String,
+	/// The hours off, or `None` for the full scheduled day.
+	pub hours: Option<f64>,
+	pub comment: String,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct AbsenceForCreate {
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub date: Date,
+	pub kind: AbsenceKind,
+	pub hours: Option<f64>,
+	#[serde(default)]
+	pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
+pub struct AbsenceForUpdate {
+	#[serde(default, with = "iso_date::option")]
+	#[schemars(with = "Option<String>")]
+	pub date: Option<Date>,
+	pub kind: Option<AbsenceKind>,
+	pub hours: Option<f64>,
+	pub comment: Option<String>,
+}
+
+/// The `AbsenceForCreate` with the `kind` as stored.
+#[derive(Fields)]
+struct AbsenceForCreateInner {
+	pub date: Date,
+	pub kind: String,
+	pub hours: Option<f64>,
+	pub comment: String,
+}
+
+#[derive(Fields)]
+struct AbsenceForUpdateInner {
+	pub date: Option<Date>,
+	pub kind: Option<String>,
+	pub hours: Option<f64>,
+	pub comment: Option<String>,
+}
+
+generate_filter!(
+	AbsenceFilter {
+		kind: OpValsString,
+		hours: OpValsFloat64,
+		comment: OpValsString,
+	},
+	Dates: [date],
+);
+// endregion: --- Absence Types
+
+// region:    --- AbsenceBmc
+pub struct AbsenceBmc;
+
+generate_common_bmc_fns!(
+	Bmc: AbsenceBmc,
+	Table: "absence",
+	Entity: Absence,
+	ForCreate: AbsenceForCreate,
+	ForUpdate: AbsenceForUpdate,
+	Filter: AbsenceFilter,
+	Fns: [get, list, list_stream, delete, get_many, delete_many],
+);
+
+// Note: `create`, `update` (and their bulk versions) are overridden
+//       to store the `kind` as text (see `AbsenceForCreateInner`).
+impl AbsenceBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		absence_c: AbsenceForCreate,
+	) -> crate::model::Result<i64> {
+		base::create::<Self, _>(ctx, mm, absence_c.into_inner()?).await
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		absences_c: Vec<AbsenceForCreate>,
+	) -> crate::model::Result<Vec<i64>> {
+		let absences_c = absences_c
+			.into_iter()
+			.map(AbsenceForCreate::into_inner)
+			.collect::<crate::model::Result<Vec<_>>>()?;
+		base::create_many::<Self, _>(ctx, mm, absences_c).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		absence_u: AbsenceForUpdate,
+	) -> crate::model::Result<()> {
+		base::update::<Self, _>(ctx, mm, id, absence_u.into_inner()?).await
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		absences_u: Vec<(i64, AbsenceForUpdate)>,
+	) -> crate::model::Result<()> {
+		let absences_u = absences_u
+			.into_iter()
+			.map(|(id, absence_u)| Ok((id, absence_u.into_inner()?)))
+			.collect::<crate::model::Result<Vec<_>>>()?;
+		base::update_many::<Self, _>(ctx, mm, absences_u).await
+	}
+
+	/// Returns the absences of the ctx user in the `[from, to)` dates,
+	/// by date.
+	pub async fn list_for_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		from: Date,
+		to: Date,
+	) -> crate::model::Result<Vec<Absence>> {
+		let filter: AbsenceFilter = serde_json::from_value(json!({
+			"cid": ctx.user_id(),
+			"date": {"$gte": iso_date::format(from), "$lt": iso_date::format(to)},
+		}))?;
+		let list_options = ListOptions {
+			order_bys: Some("date".into()),
+			..Default::default()
+		};
+
+		Self::list_stream(ctx, mm, Some(vec![filter]), Some(list_options))?
+			.try_collect()
+			.await
+	}
+}
+
+fn check_hours(hours: Option<f64>) -> crate::model::Result<()> {
+	match hours {
+		Some(hours)
+			if !hours.is_finite() || !(0. ..=ABSENCE_HOURS_MAX).contains(&hours) =>
+		{
+			Err(Error::AbsenceHoursInvalid(hours))
+		}
+		_ => Ok(()),
+	}
+}
+
+impl AbsenceForCreate {
+	fn into_inner(self) -> crate::model::Result<AbsenceForCreateInner> {
+		check_hours(self.hours)?;
+
+		Ok(AbsenceForCreateInner {
+			date: self.date,
+			kind: self.kind.as_str().to_string(),
+			hours: self.hours,
+			comment: self.comment,
+		})
+	}
+}
+
+impl AbsenceForUpdate {
+	fn into_inner(self) -> crate::model::Result<AbsenceForUpdateInner> {
+		check_hours(self.hours)?;
+
+		Ok(AbsenceForUpdateInner {
+			date: self.date,
+			kind: self.kind.map(|kind| kind.as_str().to_string()),
+			hours: self.hours,
+			comment: self.comment,
+		})
+	}
+}
+// endregion: --- AbsenceBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+	use time::macros::date;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_for_user_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_dates = [
+			date!(2019 - 12 - 31),
+			date!(2020 - 01 - 01),
+			date!(2020 - 01 - 31),
+			date!(2020 - 02 - 01),
+		];
+		let absences_c = fx_dates
+			.iter()
+			.map(|date| AbsenceForCreate {
+				date: *date,
+				kind: AbsenceKind::Vacation,
+				hours: None,
+				comment: "test_list_for_user_ok".to_string(),
+			})
+			.collect();
+		let ids = AbsenceBmc::create_many(&ctx, &mm, absences_c).await?;
+
+		// -- Exec
+		let absences = AbsenceBmc::list_for_user(
+			&ctx,
+			&mm,
+			date!(2020 - 01 - 01),
+			date!(2020 - 02 - 01),
+		)
+		.await?;
+
+		// -- Check
+		let dates: Vec<Date> = absences.iter().map(|a| a.date).collect();
+		assert_eq!(dates, &fx_dates[1..3]);
+		assert_eq!(absences[0].kind, "vacation");
+
+		// -- Clean
+		AbsenceBmc::delete_many(&ctx, &mm, &ids).await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_hours_err_over_max() -> Result<()> {
+		// -- Exec
+		let res = check_hours(Some(25.));
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::AbsenceHoursInvalid(_))),
+			"AbsenceHoursInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
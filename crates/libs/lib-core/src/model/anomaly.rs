@@ -0,0 +1,689 @@
+//! Anomalies of the tracked time (i.e., `TaskTime`s and `TimeRecord`s).
+//!
+//! Design:
+//!
+//! - The anomalies are:
+//!   - `Overlap`: two entries of the same kind overlapping in time.
+//!   - `TooLong`: an entry longer than `ANOMALY_MAX_ENTRY_HOURS`.
+//!   - `Future`: an entry ending in the future
+//!     (past `FUTURE_GRACE_SEC`, for the clock skews).
+//!   - `NotCovered`: a `TaskTime` not (fully) within the `TimeRecord`s.
+//! - `AnomalyBmc::report` lists the anomalies of the ctx user entries
+//!   in a time range.
+//! - The `TaskTimeBmc` and `TimeRecordBmc` `after_create`/`after_update` hooks
+//!   check the written entry, and reject it (rolling back the write) per the
+//!   `ANOMALY_STRICTNESS` config (see `AnomalyStrictness`).
+//!
+//! Note: Zero length entries (e.g., a started timer) never overlap,
+//!       and are always covered.
+
+use crate::config::core_config;
+use crate::ctx::Ctx;
+use crate::model::tasktime::{TaskTime, TaskTimeBmc, TaskTimeFilter};
+use crate::model::timerecord::{TimeRecord, TimeRecordBmc, TimeRecordFilter};
+use crate::model::{Error, ModelManager, Result};
+use futures::TryStreamExt;
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use modql::filter::ListOptions;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+use serde_with::serde_as;
+use std::str::FromStr;
+use time::{Duration, OffsetDateTime};
+
+/// The clock skew tolerated before an entry is in the `Future`.
+const FUTURE_GRACE_SEC: i64 = 300;
+
+/// The max number of overlapping entries reported by the write check.
+const CHECK_OVERLAPS_MAX: i64 = 10;
+
+// region:    --- Types
+
+/// Which anomalies are rejected at create/update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyStrictness {
+	/// Nothing rejected (the anomalies are only reported).
+	Off,
+	/// `TooLong` and `Future` entries rejected.
+	Lenient,
+	/// All anomalies rejected.
+	Strict,
+}
+
+impl AnomalyStrictness {
+	pub fn rejects(&self, kind: AnomalyKind) -> bool {
+		match self {
+			AnomalyStrictness::Off => false,
+			AnomalyStrictness::Lenient => {
+				matches!(kind, AnomalyKind::TooLong | AnomalyKind::Future)
+			}
+			AnomalyStrictness::Strict => true,
+		}
+	}
+}
+
+impl FromStr for AnomalyStrictness {
+	type Err = String;
+
+	fn from_str(val: &str) -> core::result::Result<Self, Self::Err> {
+		match val {
+			"off" => Ok(AnomalyStrictness::Off),
+			"lenient" => Ok(AnomalyStrictness::Lenient),
+			"strict" => Ok(AnomalyStrictness::Strict),
+			other => Err(format!("unknown anomaly strictness '{other}'")),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+	Overlap,
+	TooLong,
+	Future,
+	NotCovered,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyEntity {
+	TaskTime,
+	TimeRecord,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Anomaly {
+	pub kind: AnomalyKind,
+	pub entity: AnomalyEntity,
+	pub id: i64,
+	/// The other entry id (for an `Overlap`).
+	pub other_id: Option<i64>,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+
+	/// Human readable detail (e.g., "lasts 20.5 hours (max 16)").
+	pub detail: String,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AnomalyReport {
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub from: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub to: OffsetDateTime,
+
+	/// Ordered by entry start time.
+	pub anomalies: Vec<Anomaly>,
+}
+
+/// The entry time span, as checked.
+#[derive(Debug, Clone, Copy)]
+struct Span {
+	entity: AnomalyEntity,
+	id: i64,
+	start: OffsetDateTime,
+	stop: OffsetDateTime,
+}
+
+impl Span {
+	fn anomaly(
+		&self,
+		kind: AnomalyKind,
+		other_id: Option<i64>,
+		detail: String,
+	) -> Anomaly {
+		Anomaly {
+			kind,
+			entity: self.entity,
+			id: self.id,
+			other_id,
+			start_time: self.start,
+			stop_time: self.stop,
+			detail,
+		}
+	}
+}
+
+impl From<&TaskTime> for Span {
+	fn from(tasktime: &TaskTime) -> Self {
+		Span {
+			entity: AnomalyEntity::TaskTime,
+			id: tasktime.id,
+			start: tasktime.start_time,
+			stop: tasktime.stop_time,
+		}
+	}
+}
+
+impl From<&TimeRecord> for Span {
+	fn from(timerecord: &TimeRecord) -> Self {
+		Span {
+			entity: AnomalyEntity::TimeRecord,
+			id: timerecord.id,
+			start: timerecord.start_time,
+			stop: timerecord.stop_time,
+		}
+	}
+}
+
+/// The `TooLong` and `Future` limits.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+	max_entry: Duration,
+	now: OffsetDateTime,
+}
+
+impl Limits {
+	fn from_config() -> Self {
+		Limits {
+			max_entry: Duration::seconds_f64(
+				core_config().ANOMALY_MAX_ENTRY_HOURS * 3600.,
+			),
+			now: now_utc(),
+		}
+	}
+}
+
+// endregion: --- Types
+
+// region:    --- AnomalyBmc
+pub struct AnomalyBmc;
+
+impl AnomalyBmc {
+	/// Returns the anomalies of the ctx user entries overlapping `[from, to)`.
+	pub async fn report(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		from: OffsetDateTime,
+		to: OffsetDateTime,
+	) -> Result<AnomalyReport> {
+		let limits = Limits::from_config();
+
+		// -- Load the entries
+		let tasktimes: Vec<TaskTime> = TaskTimeBmc::list_stream(
+			ctx,
+			mm,
+			Some(vec![serde_json::from_value(overlapping(ctx, from, to))?]),
+			None,
+		)?
+		.try_collect()
+		.await?;
+		let mut tasktimes: Vec<Span> = tasktimes.iter().map(Span::from).collect();
+
+		// (the timerecords covering the tasktimes sticking out of the range too)
+		let covers_from = tasktimes.iter().map(|tt| tt.start).fold(from, Ord::min);
+		let covers_to = tasktimes.iter().map(|tt| tt.stop).fold(to, Ord::max);
+		let timerecords: Vec<TimeRecord> = TimeRecordBmc::list_stream(
+			ctx,
+			mm,
+			Some(vec![serde_json::from_value(overlapping(
+				ctx,
+				covers_from,
+				covers_to,
+			))?]),
+			None,
+		)?
+		.try_collect()
+		.await?;
+		let mut timerecords: Vec<Span> =
+			timerecords.iter().map(Span::from).collect();
+
+		// -- Check the entries
+		sort_spans(&mut tasktimes);
+		sort_spans(&mut timerecords);
+
+		let mut anomalies = overlaps(&tasktimes);
+		anomalies.extend(overlaps(&timerecords));
+		for tasktime in tasktimes.iter() {
+			anomalies.extend(span_anomalies(tasktime, limits));
+			anomalies.extend(not_covered(tasktime, &timerecords));
+		}
+		for timerecord in timerecords
+			.iter()
+			.filter(|tr| tr.start < to && tr.stop > from)
+		{
+			anomalies.extend(span_anomalies(timerecord, limits));
+		}
+
+		// (the overlaps of the timerecords out of range excluded)
+		anomalies
+			.retain(|anomaly| anomaly.start_time < to && anomaly.stop_time > from);
+		anomalies.sort_by_key(|anomaly| (anomaly.start_time, anomaly.id));
+
+		Ok(AnomalyReport {
+			from,
+			to,
+			anomalies,
+		})
+	}
+
+	/// Returns the anomalies of the entry `id` rejected by `strictness`.
+	///
+	/// Note: Uses `mm` (not the db pool) for the queries,
+	///       so that it sees the transaction of the hooks.
+	pub async fn check_entry(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		entity: AnomalyEntity,
+		id: i64,
+		strictness: AnomalyStrictness,
+	) -> Result<Vec<Anomaly>> {
+		if strictness == AnomalyStrictness::Off {
+			return Ok(Vec::new());
+		}
+
+		// -- Load the entry
+		let (span, cid) = match entity {
+			AnomalyEntity::TaskTime => {
+				let tasktime = TaskTimeBmc::get(ctx, mm, id).await?;
+				(Span::from(&tasktime), tasktime.cid)
+			}
+			AnomalyEntity::TimeRecord => {
+				let timerecord = TimeRecordBmc::get(ctx, mm, id).await?;
+				(Span::from(&timerecord), timerecord.cid)
+			}
+		};
+
+		let mut anomalies = span_anomalies(&span, Limits::from_config());
+
+		// -- Check the other entries (only when rejected)
+		if strictness.rejects(AnomalyKind::Overlap) && span.start < span.stop {
+			let mut filter = overlapping_of(cid, span.start, span.stop);
+			filter["id"] = json!({"$not": id});
+			let list_options = ListOptions {
+				limit: Some(CHECK_OVERLAPS_MAX),
+				..Default::default()
+			};
+			let others: Vec<Span> = match entity {
+				AnomalyEntity::TaskTime => {
+					let filter: TaskTimeFilter = serde_json::from_value(filter)?;
+					TaskTimeBmc::list(
+						ctx,
+						mm,
+						Some(vec![filter]),
+						Some(list_options),
+					)
+					.await?
+					.iter()
+					.map(Span::from)
+					.collect()
+				}
+				AnomalyEntity::TimeRecord => {
+					let filter: TimeRecordFilter = serde_json::from_value(filter)?;
+					TimeRecordBmc::list(
+						ctx,
+						mm,
+						Some(vec![filter]),
+						Some(list_options),
+					)
+					.await?
+					.iter()
+					.map(Span::from)
+					.collect()
+				}
+			};
+			anomalies.extend(
+				others
+					.iter()
+					.filter(|other| other.start < other.stop)
+					.map(|other| overlap(&span, other)),
+			);
+		}
+
+		if entity == AnomalyEntity::TaskTime
+			&& strictness.rejects(AnomalyKind::NotCovered)
+		{
+			let filter: TimeRecordFilter =
+				serde_json::from_value(overlapping_of(cid, span.start, span.stop))?;
+			let timerecords =
+				TimeRecordBmc::list(ctx, mm, Some(vec![filter]), None).await?;
+			let mut covers: Vec<Span> = timerecords.iter().map(Span::from).collect();
+			sort_spans(&mut covers);
+			anomalies.extend(not_covered(&span, &covers));
+		}
+
+		anomalies.retain(|anomaly| strictness.rejects(anomaly.kind));
+
+		Ok(anomalies)
+	}
+
+	/// Fails with `Error::AnomalyRejected` when the entry `id` has anomalies
+	/// rejected by the `ANOMALY_STRICTNESS` config.
+	/// (Called from the `DbBmc` hooks, so, rolling back the write)
+	pub(in crate::model) async fn reject_anomalies(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		entity: AnomalyEntity,
+		id: i64,
+	) -> Result<()> {
+		let strictness = core_config().ANOMALY_STRICTNESS;
+		let anomalies = Self::check_entry(ctx, mm, entity, id, strictness).await?;
+
+		if anomalies.is_empty() {
+			Ok(())
+		} else {
+			Err(Error::AnomalyRejected { anomalies })
+		}
+	}
+}
+// endregion: --- AnomalyBmc
+
+// region:    --- Checks
+
+/// The filter json of the ctx user entries overlapping `[from, to)`.
+fn overlapping(ctx: &Ctx, from: OffsetDateTime, to: OffsetDateTime) -> Value {
+	overlapping_of(ctx.user_id(), from, to)
+}
+
+fn overlapping_of(cid: i64, from: OffsetDateTime, to: OffsetDateTime) -> Value {
+	json!({
+		"cid": cid,
+		"start_time": {"$lt": format_time(to)},
+		"stop_time": {"$gt": format_time(from)},
+	})
+}
+
+fn sort_spans(spans: &mut [Span]) {
+	spans.sort_by_key(|span| (span.start, span.stop, span.id));
+}
+
+fn hours(duration: Duration) -> String {
+	format!("{:.1}", duration.as_seconds_f64() / 3600.)
+}
+
+/// The `TooLong` and `Future` anomalies of `span`.
+fn span_anomalies(span: &Span, limits: Limits) -> Vec<Anomaly> {
+	let mut anomalies = Vec::new();
+
+	let duration = span.stop - span.start;
+	if duration > limits.max_entry {
+		anomalies.push(span.anomaly(
+			AnomalyKind::TooLong,
+			None,
+			format!(
+				"lasts {} hours (max {})",
+				hours(duration),
+				hours(limits.max_entry)
+			),
+		));
+	}
+
+	if span.stop > limits.now + Duration::seconds(FUTURE_GRACE_SEC) {
+		anomalies.push(span.anomaly(
+			AnomalyKind::Future,
+			None,
+			format!("ends in the future ({})", format_time(span.stop)),
+		));
+	}
+
+	anomalies
+}
+
+fn overlap(span: &Span, other: &Span) -> Anomaly {
+	let entity = match other.entity {
+		AnomalyEntity::TaskTime => "tasktime",
+		AnomalyEntity::TimeRecord => "timerecord",
+	};
+
+	span.anomaly(
+		AnomalyKind::Overlap,
+		Some(other.id),
+		format!("overlaps {entity} {}", other.id),
+	)
+}
+
+/// The `Overlap` anomalies of `spans` (sorted by start),
+/// one per later overlapping span.
+fn overlaps(spans: &[Span]) -> Vec<Anomaly> {
+	let mut anomalies = Vec::new();
+
+	// The span reaching the latest so far.
+	let mut latest: Option<&Span> = None;
+	for span in spans.iter().filter(|span| span.start < span.stop) {
+		match latest {
+			Some(prev) if prev.stop > span.start => {
+				anomalies.push(overlap(span, prev));
+				if span.stop > prev.stop {
+					latest = Some(span);
+				}
+			}
+			_ => latest = Some(span),
+		}
+	}
+
+	anomalies
+}
+
+/// The `NotCovered` anomaly of `span` (if any part of it is out of `covers`,
+/// sorted by start).
+fn not_covered(span: &Span, covers: &[Span]) -> Option<Anomaly> {
+	let mut uncovered = Duration::ZERO;
+	let mut cursor = span.start;
+
+	for cover in covers {
+		if cursor >= span.stop || cover.start >= span.stop {
+			break;
+		}
+		if cover.stop <= cursor {
+			continue;
+		}
+		if cover.start > cursor {
+			uncovered += cover.start - cursor;
+		}
+		cursor = cover.stop;
+	}
+	if cursor < span.stop {
+		uncovered += span.stop - cursor;
+	}
+
+	(uncovered > Duration::ZERO).then(|| {
+		span.anomaly(
+			AnomalyKind::NotCovered,
+			None,
+			format!("{} hours not covered by a timerecord", hours(uncovered)),
+		)
+	})
+}
+
+// endregion: --- Checks
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::tasktime::TaskTimeForCreate;
+	use crate::model::timerecord::TimeRecordForCreate;
+	use anyhow::Result;
+	use serial_test::serial;
+	use time::macros::datetime;
+
+	/// A span of the hours `start_h` to `stop_h` of the 2024-03-01.
+	fn fx_span(entity: AnomalyEntity, id: i64, start_h: i64, stop_h: i64) -> Span {
+		let day = datetime!(2024-03-01 00:00 UTC);
+		Span {
+			entity,
+			id,
+			start: day + Duration::hours(start_h),
+			stop: day + Duration::hours(stop_h),
+		}
+	}
+
+	#[test]
+	fn test_overlaps_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_spans = [
+			fx_span(AnomalyEntity::TaskTime, 1, 8, 12),
+			fx_span(AnomalyEntity::TaskTime, 2, 9, 10),
+			fx_span(AnomalyEntity::TaskTime, 3, 11, 13),
+			// adjacent, so, not overlapping
+			fx_span(AnomalyEntity::TaskTime, 4, 13, 14),
+			// zero length, so, not overlapping
+			fx_span(AnomalyEntity::TaskTime, 5, 13, 13),
+		];
+
+		// -- Exec
+		let anomalies = overlaps(&fx_spans);
+
+		// -- Check
+		let pairs: Vec<(i64, Option<i64>)> = anomalies
+			.iter()
+			.map(|anomaly| (anomaly.id, anomaly.other_id))
+			.collect();
+		assert_eq!(pairs, vec![(2, Some(1)), (3, Some(1))]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_not_covered_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_tasktime = fx_span(AnomalyEntity::TaskTime, 1, 8, 12);
+		let fx_covers = [
+			fx_span(AnomalyEntity::TimeRecord, 10, 7, 9),
+			fx_span(AnomalyEntity::TimeRecord, 11, 10, 11),
+		];
+		let fx_whole_day = [fx_span(AnomalyEntity::TimeRecord, 12, 6, 18)];
+
+		// -- Exec
+		let anomaly = not_covered(&fx_tasktime, &fx_covers);
+		let covered = not_covered(&fx_tasktime, &fx_whole_day);
+
+		// -- Check
+		let anomaly =
+			anomaly.ok_or_else(|| anyhow::anyhow!("should be not covered"))?;
+		assert_eq!(anomaly.detail, "2.0 hours not covered by a timerecord");
+		assert!(covered.is_none(), "should be covered");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_span_anomalies_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_limits = Limits {
+			max_entry: Duration::hours(16),
+			now: datetime!(2024-03-02 12:00 UTC),
+		};
+		let fx_span = fx_span(AnomalyEntity::TimeRecord, 1, 8, 56);
+
+		// -- Exec
+		let anomalies = span_anomalies(&fx_span, fx_limits);
+
+		// -- Check
+		let kinds: Vec<AnomalyKind> =
+			anomalies.iter().map(|anomaly| anomaly.kind).collect();
+		assert_eq!(kinds, vec![AnomalyKind::TooLong, AnomalyKind::Future]);
+		assert_eq!(anomalies[0].detail, "lasts 48.0 hours (max 16.0)");
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_check_entry_strict_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_start = now_utc() - Duration::days(400);
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"project for anomaly test_check_entry_strict_ok",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"task for anomaly test_check_entry_strict_ok",
+		)
+		.await?;
+		let fx_timerecord_id = TimeRecordBmc::create(
+			&ctx,
+			&mm,
+			TimeRecordForCreate {
+				place: "test_check_entry_strict_ok office".to_string(),
+				place_id: None,
+				start_time: Some(fx_start),
+				stop_time: Some(fx_start + Duration::hours(2)),
+			},
+		)
+		.await?;
+		let mut fx_tasktime_ids = Vec::new();
+		for hour in [1, 2] {
+			let id = TaskTimeBmc::create(
+				&ctx,
+				&mm,
+				TaskTimeForCreate {
+					task_id: fx_task_id,
+					comment: format!("test_check_entry_strict_ok {hour}"),
+					start_time: Some(fx_start + Duration::hours(hour)),
+					stop_time: Some(fx_start + Duration::hours(hour + 2)),
+					billable: None,
+				},
+			)
+			.await?;
+			fx_tasktime_ids.push(id);
+		}
+
+		// -- Exec
+		let strict = AnomalyBmc::check_entry(
+			&ctx,
+			&mm,
+			AnomalyEntity::TaskTime,
+			fx_tasktime_ids[1],
+			AnomalyStrictness::Strict,
+		)
+		.await?;
+		let lenient = AnomalyBmc::check_entry(
+			&ctx,
+			&mm,
+			AnomalyEntity::TaskTime,
+			fx_tasktime_ids[1],
+			AnomalyStrictness::Lenient,
+		)
+		.await?;
+		let report =
+			AnomalyBmc::report(&ctx, &mm, fx_start, fx_start + Duration::hours(8))
+				.await?;
+
+		// -- Check
+		let kinds: Vec<AnomalyKind> =
+			strict.iter().map(|anomaly| anomaly.kind).collect();
+		assert_eq!(kinds, vec![AnomalyKind::Overlap, AnomalyKind::NotCovered]);
+		assert_eq!(strict[0].other_id, Some(fx_tasktime_ids[0]));
+		assert!(lenient.is_empty(), "lenient should not reject");
+		let kinds: Vec<(AnomalyKind, i64)> = report
+			.anomalies
+			.iter()
+			.map(|anomaly| (anomaly.kind, anomaly.id))
+			.collect();
+		assert_eq!(
+			kinds,
+			vec![
+				(AnomalyKind::NotCovered, fx_tasktime_ids[0]),
+				(AnomalyKind::Overlap, fx_tasktime_ids[1]),
+				(AnomalyKind::NotCovered, fx_tasktime_ids[1]),
+			]
+		);
+
+		// -- Clean
+		TimeRecordBmc::delete(&ctx, &mm, fx_timerecord_id).await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
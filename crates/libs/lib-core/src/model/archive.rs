@@ -0,0 +1,1223 @@
+//! Account data archive (i.e., backup and restore of the user data).
+//!
+//! Design:
+//!
+//! - `ArchiveBmc::export_my_data` returns the `Archive` of the ctx user:
+//!   the clients and projects (by `cid`), the project tasks, task progresses
+//!   and tasktimes, the rates (by `cid`, on the archived projects and tasks,
+//!   and for any user or the ctx user), the places, timerecords and travels,
+//!   and the work schedules and absences (by `cid`).
+//! - The archive ids are remapped (1, 2, ... per entity, in the db id order),
+//!   so an archive does not depend on the db it comes from, and the references
+//!   (e.g., `ArchiveTask.project_id`) are archive ids.
+//! - `ArchiveBmc::import_my_data` restores an archive into the ctx user account
+//!   (the same, or another one), as new entities, in one transaction.
+//!   The places are the exception, as they are unique per name (see `model::place`),
+//!   so an archive place reuses the account place of the same name, if any.
+//!   Likewise, an archive work schedule updates the account one of the same
+//!   `valid_from`, if any.
+//!   The archive `version` must be at most the `ARCHIVE_VERSION` of the server.
+//!   An older archive lacks the entities added since (e.g., the places of v2,
+//!   the clients of v4), imported as empty (see the `serde(default)` fields).
+//!
+//! Note: The timestamps (ctime, mtime) are the ones of the import.
+
+use crate::ctx::Ctx;
+use crate::model::absence::{
+	Absence, AbsenceBmc, AbsenceFilter, AbsenceForCreate, AbsenceKind,
+};
+use crate::model::client::{Client, ClientBmc, ClientFilter, ClientForCreate};
+use crate::model::modql_utils::iso_date;
+use crate::model::place::{Place, PlaceBmc, PlaceFilter};
+use crate::model::project::{Project, ProjectBmc, ProjectFilter, ProjectForCreate};
+use crate::model::rate::{Rate, RateBmc, RateFilter, RateForCreate};
+use crate::model::task::{Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate};
+use crate::model::taskprogress::{
+	TaskProgress, TaskProgressBmc, TaskProgressFilter, TaskProgressForCreate,
+};
+use crate::model::tasktime::{
+	TaskTime, TaskTimeBmc, TaskTimeFilter, TaskTimeForCreate,
+};
+use crate::model::timerecord::{
+	TimeRecord, TimeRecordBmc, TimeRecordFilter, TimeRecordForCreate,
+};
+use crate::model::travel::{
+	Travel, TravelBmc, TravelFilter, TravelForCreate, TravelMode,
+};
+use crate::model::work_schedule::{
+	WorkSchedule, WorkScheduleBmc, WorkScheduleFilter, WorkScheduleForCreate,
+	WorkScheduleForUpdate,
+};
+use crate::model::{EntityStream, Error, ModelManager, Result};
+use futures::TryStreamExt;
+use lib_utils::time::{now_utc, Rfc3339};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use std::collections::HashMap;
+use time::Date;
+
+/// The version of the `Archive` format (to be bumped on any change).
+///
+/// - v1: projects, tasks, taskprogresses, tasktimes, and timerecords.
+/// - v2: places, travels, and `ArchiveTimeRecord.place_id`.
+/// - v3: work_schedules and absences.
+/// - v4: clients, rates, `ArchiveProject.client_id`, and `ArchiveTaskTime.billable`.
+pub const ARCHIVE_VERSION: u32 = 4;
+
+/// The max number of parent ids per `$in` query.
+pub(in crate::model) const IN_IDS_MAX: usize = 1000;
+
+// region:    --- Archive Types
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Archive {
+	pub version: u32,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub exported_at: OffsetDateTime,
+
+	#[serde(default)]
+	pub clients: Vec<ArchiveClient>,
+	pub projects: Vec<ArchiveProject>,
+	pub tasks: Vec<ArchiveTask>,
+	pub taskprogresses: Vec<ArchiveTaskProgress>,
+	pub tasktimes: Vec<ArchiveTaskTime>,
+	#[serde(default)]
+	pub rates: Vec<ArchiveRate>,
+	#[serde(default)]
+	pub places: Vec<ArchivePlace>,
+	pub timerecords: Vec<ArchiveTimeRecord>,
+	#[serde(default)]
+	pub travels: Vec<ArchiveTravel>,
+	#[serde(default)]
+	pub work_schedules: Vec<ArchiveWorkSchedule>,
+	#[serde(default)]
+	pub absences: Vec<ArchiveAbsence>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveClient {
+	pub id: i64,
+	pub name: String,
+	pub address: String,
+	pub currency: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveProject {
+	pub id: i64,
+	/// `None` as well for a client not archived (i.e., of another user).
+	#[serde(default)]
+	pub client_id: Option<i64>,
+	pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveTask {
+	pub id: i64,
+	pub project_id: i64,
+	pub title: String,
+	pub done: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveTaskProgress {
+	pub task_id: i64,
+	pub progress: i32,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveTaskTime {
+	pub task_id: i64,
+	pub comment: String,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+	/// Defaults to `true` (for the archives before v4, which predate it).
+	#[serde(default = "default_billable")]
+	pub billable: bool,
+}
+
+fn default_billable() -> bool {
+	true
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveRate {
+	pub project_id: Option<i64>,
+	pub task_id: Option<i64>,
+	/// Whether the rate is for the archive user only
+	/// (i.e., the ctx user of the export, then of the import).
+	pub user_specific: bool,
+	pub hourly_rate_cents: i64,
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchivePlace {
+	pub id: i64,
+	pub name: String,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveTimeRecord {
+	pub place: String,
+	#[serde(default)]
+	pub place_id: Option<i64>,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveTravel {
+	pub from_place_id: i64,
+	pub to_place_id: i64,
+	pub mode: TravelMode,
+	pub distance_km: Option<f64>,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveWorkSchedule {
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+	pub mon_hours: f64,
+	pub tue_hours: f64,
+	pub wed_hours: f64,
+	pub thu_hours: f64,
+	pub fri_hours: f64,
+	pub sat_hours: f64,
+	pub sun_hours: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ArchiveAbsence {
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub date: Date,
+	pub kind: AbsenceKind,
+	pub hours: Option<f64>,
+	pub comment: String,
+}
+
+/// The number of entities imported by `import_my_data`
+/// (i.e., created, but for the places and work schedules,
+/// which can be existing ones).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ArchiveImportReport {
+	pub clients: usize,
+	pub projects: usize,
+	pub tasks: usize,
+	pub taskprogresses: usize,
+	pub tasktimes: usize,
+	pub rates: usize,
+	pub places: usize,
+	pub timerecords: usize,
+	pub travels: usize,
+	pub work_schedules: usize,
+	pub absences: usize,
+}
+
+// endregion: --- Archive Types
+
+// region:    --- ArchiveBmc
+
+pub struct ArchiveBmc;
+
+impl ArchiveBmc {
+	pub async fn export_my_data(ctx: &Ctx, mm: &ModelManager) -> Result<Archive> {
+		let owned_filter = json!({"cid": ctx.user_id()});
+
+		// -- Load the entities
+		let filter: ClientFilter = serde_json::from_value(owned_filter.clone())?;
+		let clients: Vec<Client> =
+			ClientBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let client_ids: Vec<i64> = clients.iter().map(|c| c.id).collect();
+		let filter: ProjectFilter = serde_json::from_value(owned_filter.clone())?;
+		let projects: Vec<Project> =
+			ProjectBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let project_ids: Vec<i64> = projects.iter().map(|p| p.id).collect();
+		let tasks: Vec<Task> =
+			list_by_parents(&project_ids, "project_id", |f: TaskFilter| {
+				TaskBmc::list_stream(ctx, mm, Some(vec![f]), None)
+			})
+			.await?;
+		let task_ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+		let taskprogresses: Vec<TaskProgress> =
+			list_by_parents(&task_ids, "task_id", |f: TaskProgressFilter| {
+				TaskProgressBmc::list_stream(ctx, mm, Some(vec![f]), None)
+			})
+			.await?;
+		let tasktimes: Vec<TaskTime> =
+			list_by_parents(&task_ids, "task_id", |f: TaskTimeFilter| {
+				TaskTimeBmc::list_stream(ctx, mm, Some(vec![f]), None)
+			})
+			.await?;
+		let filter: RateFilter = serde_json::from_value(owned_filter.clone())?;
+		let rates: Vec<Rate> =
+			RateBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let filter: PlaceFilter = serde_json::from_value(owned_filter.clone())?;
+		let places: Vec<Place> =
+			PlaceBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let place_ids: Vec<i64> = places.iter().map(|p| p.id).collect();
+		let filter: TimeRecordFilter = serde_json::from_value(owned_filter.clone())?;
+		let timerecords: Vec<TimeRecord> =
+			TimeRecordBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let filter: TravelFilter = serde_json::from_value(owned_filter.clone())?;
+		let travels: Vec<Travel> =
+			TravelBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let work_schedules: Vec<WorkSchedule> =
+			WorkScheduleBmc::list_for_user(ctx, mm).await?;
+		let filter: AbsenceFilter = serde_json::from_value(owned_filter)?;
+		let absences: Vec<Absence> =
+			AbsenceBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+
+		// -- Remap the ids
+		let client_aids = archive_ids(&client_ids);
+		let project_aids = archive_ids(&project_ids);
+		let task_aids = archive_ids(&task_ids);
+		let place_aids = archive_ids(&place_ids);
+
+		let archive = Archive {
+			version: ARCHIVE_VERSION,
+			exported_at: now_utc(),
+			clients: clients
+				.into_iter()
+				.map(|c| ArchiveClient {
+					id: client_aids[&c.id],
+					name: c.name,
+					address: c.address,
+					currency: c.currency,
+				})
+				.collect(),
+			projects: projects
+				.into_iter()
+				.map(|p| ArchiveProject {
+					id: project_aids[&p.id],
+					client_id: p
+						.client_id
+						.and_then(|client_id| client_aids.get(&client_id).copied()),
+					name: p.name,
+				})
+				.collect(),
+			tasks: tasks
+				.into_iter()
+				.map(|t| ArchiveTask {
+					id: task_aids[&t.id],
+					project_id: project_aids[&t.project_id],
+					title: t.title,
+					done: t.done,
+				})
+				.collect(),
+			taskprogresses: taskprogresses
+				.into_iter()
+				.map(|tp| ArchiveTaskProgress {
+					task_id: task_aids[&tp.task_id],
+					progress: tp.progress,
+				})
+				.collect(),
+			tasktimes: tasktimes
+				.into_iter()
+				.map(|tt| ArchiveTaskTime {
+					task_id: task_aids[&tt.task_id],
+					comment: tt.comment,
+					start_time: tt.start_time,
+					stop_time: tt.stop_time,
+					billable: tt.billable,
+				})
+				.collect(),
+			// (skipping the rates on the entities not archived)
+			rates: rates
+				.into_iter()
+				.filter_map(|r| {
+					Some(ArchiveRate {
+						project_id: archive_ref(&project_aids, r.project_id)?,
+						task_id: archive_ref(&task_aids, r.task_id)?,
+						user_specific: match r.user_id {
+							None => false,
+							Some(user_id) if user_id == ctx.user_id() => true,
+							Some(_) => return None,
+						},
+						hourly_rate_cents: r.hourly_rate_cents,
+						valid_from: r.valid_from,
+					})
+				})
+				.collect(),
+			places: places
+				.into_iter()
+				.map(|p| ArchivePlace {
+					id: place_aids[&p.id],
+					name: p.name,
+				})
+				.collect(),
+			timerecords: timerecords
+				.into_iter()
+				.map(|tr| ArchiveTimeRecord {
+					place: tr.place,
+					place_id: tr.place_id.map(|place_id| place_aids[&place_id]),
+					start_time: tr.start_time,
+					stop_time: tr.stop_time,
+				})
+				.collect(),
+			travels: travels
+				.into_iter()
+				.map(|t| {
+					Ok(ArchiveTravel {
+						from_place_id: place_aids[&t.from_place_id],
+						to_place_id: place_aids[&t.to_place_id],
+						mode: serde_json::from_value(Value::from(t.mode))?,
+						distance_km: t.distance_km,
+						start_time: t.start_time,
+						stop_time: t.stop_time,
+					})
+				})
+				.collect::<Result<_>>()?,
+			work_schedules: work_schedules
+				.into_iter()
+				.map(|ws| ArchiveWorkSchedule {
+					valid_from: ws.valid_from,
+					mon_hours: ws.mon_hours,
+					tue_hours: ws.tue_hours,
+					wed_hours: ws.wed_hours,
+					thu_hours: ws.thu_hours,
+					fri_hours: ws.fri_hours,
+					sat_hours: ws.sat_hours,
+					sun_hours: ws.sun_hours,
+				})
+				.collect(),
+			absences: absences
+				.into_iter()
+				.map(|a| {
+					Ok(ArchiveAbsence {
+						date: a.date,
+						kind: serde_json::from_value(Value::from(a.kind))?,
+						hours: a.hours,
+						comment: a.comment,
+					})
+				})
+				.collect::<Result<_>>()?,
+		};
+
+		Ok(archive)
+	}
+
+	pub async fn import_my_data(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		archive: Archive,
+	) -> Result<ArchiveImportReport> {
+		if !(1..=ARCHIVE_VERSION).contains(&archive.version) {
+			return Err(Error::ArchiveVersionUnsupported {
+				version: archive.version,
+				supported: ARCHIVE_VERSION,
+			});
+		}
+		let Archive {
+			clients,
+			projects,
+			tasks,
+			taskprogresses,
+			tasktimes,
+			rates,
+			places,
+			timerecords,
+			travels,
+			work_schedules,
+			absences,
+			..
+		} = archive;
+		let report = ArchiveImportReport {
+			clients: clients.len(),
+			projects: projects.len(),
+			tasks: tasks.len(),
+			taskprogresses: taskprogresses.len(),
+			tasktimes: tasktimes.len(),
+			rates: rates.len(),
+			places: places.len(),
+			timerecords: timerecords.len(),
+			travels: travels.len(),
+			work_schedules: work_schedules.len(),
+			absences: absences.len(),
+		};
+
+		// -- Start the transaction
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			// -- Clients
+			let client_aids: Vec<i64> = clients.iter().map(|c| c.id).collect();
+			let clients_c = clients
+				.into_iter()
+				.map(|c| ClientForCreate {
+					name: c.name,
+					address: Some(c.address),
+					currency: Some(c.currency),
+				})
+				.collect();
+			let client_ids = ClientBmc::create_many(ctx, &mm, clients_c).await?;
+			let client_ids = id_map("client", &client_aids, client_ids)?;
+
+			// -- Projects
+			let project_aids: Vec<i64> = projects.iter().map(|p| p.id).collect();
+			let projects_c = projects
+				.into_iter()
+				.map(|p| {
+					Ok(ProjectForCreate {
+						name: p.name,
+						client_id: p
+							.client_id
+							.map(|client_id| db_id("client", &client_ids, client_id))
+							.transpose()?,
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			let project_ids = ProjectBmc::create_many(ctx, &mm, projects_c).await?;
+			let project_ids = id_map("project", &project_aids, project_ids)?;
+
+			// -- Tasks
+			let task_aids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+			let task_dones: Vec<bool> = tasks.iter().map(|t| t.done).collect();
+			let tasks_c = tasks
+				.into_iter()
+				.map(|t| {
+					Ok(TaskForCreate {
+						title: t.title,
+						project_id: db_id("project", &project_ids, t.project_id)?,
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			let task_ids = TaskBmc::create_many(ctx, &mm, tasks_c).await?;
+			let task_ids = id_map("task", &task_aids, task_ids)?;
+
+			// -- Task children
+			let taskprogresses_c = taskprogresses
+				.into_iter()
+				.map(|tp| {
+					Ok(TaskProgressForCreate {
+						progress: tp.progress,
+						task_id: db_id("task", &task_ids, tp.task_id)?,
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			TaskProgressBmc::create_many(ctx, &mm, taskprogresses_c).await?;
+
+			let tasktimes_c = tasktimes
+				.into_iter()
+				.map(|tt| {
+					Ok(TaskTimeForCreate {
+						task_id: db_id("task", &task_ids, tt.task_id)?,
+						comment: tt.comment,
+						start_time: Some(tt.start_time),
+						stop_time: Some(tt.stop_time),
+						billable: Some(tt.billable),
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			TaskTimeBmc::create_many(ctx, &mm, tasktimes_c).await?;
+
+			// -- Task done flags
+			//    (after the progresses, which can mark a task as done)
+			let tasks_u = task_aids
+				.iter()
+				.zip(task_dones)
+				.filter(|(_, done)| *done)
+				.map(|(aid, _)| {
+					let task_u = TaskForUpdate {
+						done: Some(true),
+						..Default::default()
+					};
+					(task_ids[aid], task_u)
+				})
+				.collect::<Vec<_>>();
+			if !tasks_u.is_empty() {
+				TaskBmc::update_many(ctx, &mm, tasks_u).await?;
+			}
+
+			// -- Rates
+			let rates_c = rates
+				.into_iter()
+				.map(|r| {
+					Ok(RateForCreate {
+						project_id: r
+							.project_id
+							.map(|project_id| {
+								db_id("project", &project_ids, project_id)
+							})
+							.transpose()?,
+						task_id: r
+							.task_id
+							.map(|task_id| db_id("task", &task_ids, task_id))
+							.transpose()?,
+						user_id: r.user_specific.then(|| ctx.user_id()),
+						hourly_rate_cents: r.hourly_rate_cents,
+						valid_from: r.valid_from,
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			RateBmc::create_many(ctx, &mm, rates_c).await?;
+
+			// -- Places
+			//    (merged by name with the account places, see module doc)
+			let mut place_ids = HashMap::with_capacity(places.len());
+			for p in places {
+				let place =
+					PlaceBmc::get_or_create_by_name(ctx, &mm, &p.name).await?;
+				if place_ids.insert(p.id, place.id).is_some() {
+					return Err(Error::ArchiveIdDuplicate {
+						entity: "place",
+						id: p.id,
+					});
+				}
+			}
+
+			// -- Timerecords
+			let timerecords_c = timerecords
+				.into_iter()
+				.map(|tr| {
+					Ok(TimeRecordForCreate {
+						place: tr.place,
+						place_id: tr
+							.place_id
+							.map(|place_id| db_id("place", &place_ids, place_id))
+							.transpose()?,
+						start_time: Some(tr.start_time),
+						stop_time: Some(tr.stop_time),
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			TimeRecordBmc::create_many(ctx, &mm, timerecords_c).await?;
+
+			// -- Travels
+			let travels_c = travels
+				.into_iter()
+				.map(|t| {
+					Ok(TravelForCreate {
+						from_place_id: db_id("place", &place_ids, t.from_place_id)?,
+						to_place_id: db_id("place", &place_ids, t.to_place_id)?,
+						mode: t.mode,
+						distance_km: t.distance_km,
+						start_time: t.start_time,
+						stop_time: t.stop_time,
+					})
+				})
+				.collect::<Result<Vec<_>>>()?;
+			TravelBmc::create_many(ctx, &mm, travels_c).await?;
+
+			// -- Work schedules
+			//    (updating the account one of the same valid_from, see module doc)
+			let mut work_schedules_c = Vec::new();
+			let mut work_schedules_u = Vec::new();
+			for ws in work_schedules {
+				let filter: WorkScheduleFilter = serde_json::from_value(json!({
+					"cid": ctx.user_id(),
+					"valid_from": iso_date::format(ws.valid_from),
+				}))?;
+				let existing =
+					WorkScheduleBmc::list(ctx, &mm, Some(vec![filter]), None)
+						.await?;
+				match existing.first() {
+					Some(existing) => work_schedules_u.push((
+						existing.id,
+						WorkScheduleForUpdate {
+							valid_from: None,
+							mon_hours: Some(ws.mon_hours),
+							tue_hours: Some(ws.tue_hours),
+							wed_hours: Some(ws.wed_hours),
+							thu_hours: Some(ws.thu_hours),
+							fri_hours: Some(ws.fri_hours),
+							sat_hours: Some(ws.sat_hours),
+							sun_hours: Some(ws.sun_hours),
+						},
+					)),
+					None => work_schedules_c.push(WorkScheduleForCreate {
+						valid_from: ws.valid_from,
+						mon_hours: ws.mon_hours,
+						tue_hours: ws.tue_hours,
+						wed_hours: ws.wed_hours,
+						thu_hours: ws.thu_hours,
+						fri_hours: ws.fri_hours,
+						sat_hours: ws.sat_hours,
+						sun_hours: ws.sun_hours,
+					}),
+				}
+			}
+			WorkScheduleBmc::create_many(ctx, &mm, work_schedules_c).await?;
+			if !work_schedules_u.is_empty() {
+				WorkScheduleBmc::update_many(ctx, &mm, work_schedules_u).await?;
+			}
+
+			// -- Absences
+			let absences_c = absences
+				.into_iter()
+				.map(|a| AbsenceForCreate {
+					date: a.date,
+					kind: a.kind,
+					hours: a.hours,
+					comment: a.comment,
+				})
+				.collect();
+			AbsenceBmc::create_many(ctx, &mm, absences_c).await?;
+
+			Ok(report)
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+}
+
+/// Lists the entities whose `parent_prop` is in `parent_ids`
+/// (by chunks of `IN_IDS_MAX` ids).
+pub(in crate::model) async fn list_by_parents<F, E, L>(
+	parent_ids: &[i64],
+	parent_prop: &str,
+	list_stream: L,
+) -> Result<Vec<E>>
+where
+	F: DeserializeOwned,
+	L: Fn(F) -> Result<EntityStream<E>>,
+{
+	let mut entities = Vec::new();
+
+	for ids in parent_ids.chunks(IN_IDS_MAX) {
+		let mut filter = Map::new();
+		filter.insert(parent_prop.to_string(), json!({"$in": ids}));
+		let filter: F = serde_json::from_value(Value::Object(filter))?;
+		let mut chunk: Vec<E> = list_stream(filter)?.try_collect().await?;
+		entities.append(&mut chunk);
+	}
+
+	Ok(entities)
+}
+
+/// Returns the db id -> archive id map (from 1, in the `ids` order).
+fn archive_ids(ids: &[i64]) -> HashMap<i64, i64> {
+	ids.iter().zip(1..).map(|(id, aid)| (*id, aid)).collect()
+}
+
+/// Returns the archive id of the optional `id` reference,
+/// or `None` if it is not archived.
+fn archive_ref(aids: &HashMap<i64, i64>, id: Option<i64>) -> Option<Option<i64>> {
+	match id {
+		Some(id) => aids.get(&id).map(|aid| Some(*aid)),
+		None => Some(None),
+	}
+}
+
+/// Returns the archive id -> db id map (failing on a duplicate archive id).
+fn id_map(
+	entity: &'static str,
+	archive_ids: &[i64],
+	db_ids: Vec<i64>,
+) -> Result<HashMap<i64, i64>> {
+	let mut map = HashMap::with_capacity(archive_ids.len());
+	for (aid, id) in archive_ids.iter().zip(db_ids) {
+		if map.insert(*aid, id).is_some() {
+			return Err(Error::ArchiveIdDuplicate { entity, id: *aid });
+		}
+	}
+
+	Ok(map)
+}
+
+fn db_id(
+	entity: &'static str,
+	id_map: &HashMap<i64, i64>,
+	archive_id: i64,
+) -> Result<i64> {
+	id_map
+		.get(&archive_id)
+		.copied()
+		.ok_or(Error::ArchiveRefInvalid {
+			entity,
+			id: archive_id,
+		})
+}
+
+// endregion: --- ArchiveBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectForUpdate;
+	use crate::model::user::{User, UserBmc};
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+	use time::macros::date;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_export_import_my_data_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let user: User = UserBmc::first_by_username(&Ctx::root_ctx(), &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(user.id)?;
+		let fx_project_name = "project for test_export_import_my_data_ok";
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, fx_project_name).await?;
+		let fx_client_name = "client for test_export_import_my_data_ok";
+		let fx_client_id = ClientBmc::create(
+			&ctx,
+			&mm,
+			ClientForCreate {
+				name: fx_client_name.to_string(),
+				address: None,
+				currency: Some("CHF".to_string()),
+			},
+		)
+		.await?;
+		ProjectBmc::update(
+			&ctx,
+			&mm,
+			fx_project_id,
+			ProjectForUpdate {
+				name: None,
+				owner_id: None,
+				client_id: Some(fx_client_id),
+			},
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"task for test_export_import_my_data_ok",
+		)
+		.await?;
+		_dev_utils::seed_tasktimes(&ctx, &mm, fx_task_id, &["tt 01", "tt 02"])
+			.await?;
+		_dev_utils::seed_taskprogresses(&ctx, &mm, fx_task_id, &[100]).await?;
+		let fx_rate_id = RateBmc::create(
+			&ctx,
+			&mm,
+			RateForCreate {
+				project_id: None,
+				task_id: Some(fx_task_id),
+				user_id: Some(ctx.user_id()),
+				hourly_rate_cents: 9_000,
+				valid_from: date!(1990 - 01 - 01),
+			},
+		)
+		.await?;
+		let fx_place = "place for test_export_import_my_data_ok";
+		let fx_timerecords =
+			_dev_utils::seed_timerecords(&ctx, &mm, &[fx_place]).await?;
+		let fx_place_id = fx_timerecords[0]
+			.place_id
+			.context("Should have the fixture place")?;
+		let fx_place_2 = PlaceBmc::get_or_create_by_name(
+			&ctx,
+			&mm,
+			"place 2 for test_export_import_my_data_ok",
+		)
+		.await?;
+		TravelBmc::create(
+			&ctx,
+			&mm,
+			TravelForCreate {
+				from_place_id: fx_place_id,
+				to_place_id: fx_place_2.id,
+				mode: TravelMode::Bike,
+				distance_km: Some(12.5),
+				start_time: now_utc(),
+				stop_time: now_utc(),
+			},
+		)
+		.await?;
+		let fx_valid_from = date!(1990 - 01 - 01);
+		let fx_schedule_id = WorkScheduleBmc::create(
+			&ctx,
+			&mm,
+			WorkScheduleForCreate {
+				valid_from: fx_valid_from,
+				mon_hours: 8.,
+				tue_hours: 8.,
+				wed_hours: 8.,
+				thu_hours: 8.,
+				fri_hours: 4.,
+				sat_hours: 0.,
+				sun_hours: 0.,
+			},
+		)
+		.await?;
+		let fx_absence_comment = "absence for test_export_import_my_data_ok";
+		AbsenceBmc::create(
+			&ctx,
+			&mm,
+			AbsenceForCreate {
+				date: date!(1990 - 01 - 02),
+				kind: AbsenceKind::Sick,
+				hours: Some(4.),
+				comment: fx_absence_comment.to_string(),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let archive = ArchiveBmc::export_my_data(&ctx, &mm).await?;
+		// (only keep the fixture entities, for the import)
+		let project = archive
+			.projects
+			.into_iter()
+			.find(|p| p.name == fx_project_name)
+			.context("Should have the fixture project")?;
+		let task = archive
+			.tasks
+			.into_iter()
+			.find(|t| t.project_id == project.id)
+			.context("Should have the fixture task")?;
+		let places: Vec<ArchivePlace> = archive
+			.places
+			.into_iter()
+			.filter(|p| p.name.ends_with("for test_export_import_my_data_ok"))
+			.collect();
+		let place_aids: Vec<i64> = places.iter().map(|p| p.id).collect();
+		let fx_archive = Archive {
+			version: ARCHIVE_VERSION,
+			exported_at: archive.exported_at,
+			clients: archive
+				.clients
+				.into_iter()
+				.filter(|c| Some(c.id) == project.client_id)
+				.collect(),
+			rates: archive
+				.rates
+				.into_iter()
+				.filter(|r| r.task_id == Some(task.id))
+				.collect(),
+			taskprogresses: archive
+				.taskprogresses
+				.into_iter()
+				.filter(|tp| tp.task_id == task.id)
+				.collect(),
+			tasktimes: archive
+				.tasktimes
+				.into_iter()
+				.filter(|tt| tt.task_id == task.id)
+				.collect(),
+			timerecords: archive
+				.timerecords
+				.into_iter()
+				.filter(|tr| tr.place == fx_place)
+				.collect(),
+			travels: archive
+				.travels
+				.into_iter()
+				.filter(|t| place_aids.contains(&t.from_place_id))
+				.collect(),
+			work_schedules: archive
+				.work_schedules
+				.into_iter()
+				.filter(|ws| ws.valid_from == fx_valid_from)
+				.collect(),
+			absences: archive
+				.absences
+				.into_iter()
+				.filter(|a| a.comment == fx_absence_comment)
+				.collect(),
+			places,
+			projects: vec![project],
+			tasks: vec![task],
+		};
+		let report = ArchiveBmc::import_my_data(&ctx, &mm, fx_archive).await?;
+
+		// -- Check
+		assert_eq!(report.clients, 1);
+		assert_eq!(report.projects, 1);
+		assert_eq!(report.tasks, 1);
+		assert_eq!(report.taskprogresses, 1);
+		assert_eq!(report.tasktimes, 2);
+		assert_eq!(report.rates, 1);
+		assert_eq!(report.places, 2);
+		assert_eq!(report.timerecords, 1);
+		assert_eq!(report.travels, 1);
+		assert_eq!(report.work_schedules, 1);
+		assert_eq!(report.absences, 1);
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"name": fx_project_name}))?;
+		let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(projects.len(), 2);
+		let imported = projects
+			.iter()
+			.find(|p| p.id != fx_project_id)
+			.context("Should have the imported project")?;
+		let imported_client_id = imported
+			.client_id
+			.context("Should have the imported project client")?;
+		assert_ne!(imported_client_id, fx_client_id);
+		let client = ClientBmc::get(&ctx, &mm, imported_client_id).await?;
+		assert_eq!(client.name, fx_client_name);
+		assert_eq!(client.currency, "CHF");
+		let filter: TaskFilter =
+			serde_json::from_value(json!({"project_id": imported.id}))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(tasks.len(), 1);
+		assert!(tasks[0].done, "imported task should be done");
+		let filter: RateFilter =
+			serde_json::from_value(json!({"task_id": tasks[0].id}))?;
+		let rates = RateBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(rates.len(), 1);
+		assert_eq!(rates[0].user_id, Some(ctx.user_id()));
+		assert_eq!(rates[0].hourly_rate_cents, 9_000);
+		let filter: TimeRecordFilter =
+			serde_json::from_value(json!({"place": fx_place}))?;
+		let timerecords =
+			TimeRecordBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(timerecords.len(), 2);
+		// (the places are reused, see module doc)
+		for timerecord in timerecords.iter() {
+			assert_eq!(timerecord.place_id, Some(fx_place_id));
+		}
+		let filter: TravelFilter =
+			serde_json::from_value(json!({"from_place_id": fx_place_id}))?;
+		let travels = TravelBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(travels.len(), 2);
+		for travel in travels.iter() {
+			assert_eq!(travel.to_place_id, fx_place_2.id);
+			assert_eq!(travel.mode, "bike");
+			assert_eq!(travel.distance_km, Some(12.5));
+		}
+		// (the work schedule is updated, see module doc)
+		let schedules: Vec<WorkSchedule> = WorkScheduleBmc::list_for_user(&ctx, &mm)
+			.await?
+			.into_iter()
+			.filter(|ws| ws.valid_from == fx_valid_from)
+			.collect();
+		assert_eq!(schedules.len(), 1);
+		assert_eq!(schedules[0].id, fx_schedule_id);
+		assert_eq!(schedules[0].fri_hours, 4.);
+		let filter: AbsenceFilter =
+			serde_json::from_value(json!({"comment": fx_absence_comment}))?;
+		let absences = AbsenceBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(absences.len(), 2);
+		for absence in absences.iter() {
+			assert_eq!(absence.kind, "sick");
+			assert_eq!(absence.hours, Some(4.));
+		}
+
+		// -- Clean
+		RateBmc::delete(&ctx, &mm, fx_rate_id).await?;
+		RateBmc::delete(&ctx, &mm, rates[0].id).await?;
+		for project in projects {
+			ProjectBmc::delete(&ctx, &mm, project.id).await?;
+		}
+		ClientBmc::delete(&ctx, &mm, fx_client_id).await?;
+		ClientBmc::delete(&ctx, &mm, imported_client_id).await?;
+		for timerecord in timerecords {
+			TimeRecordBmc::delete(&ctx, &mm, timerecord.id).await?;
+		}
+		for travel in travels {
+			TravelBmc::delete(&ctx, &mm, travel.id).await?;
+		}
+		PlaceBmc::delete(&ctx, &mm, fx_place_id).await?;
+		PlaceBmc::delete(&ctx, &mm, fx_place_2.id).await?;
+		WorkScheduleBmc::delete(&ctx, &mm, fx_schedule_id).await?;
+		for absence in absences {
+			AbsenceBmc::delete(&ctx, &mm, absence.id).await?;
+		}
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_my_data_err_version() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_archive = Archive {
+			version: ARCHIVE_VERSION + 1,
+			exported_at: now_utc(),
+			clients: Vec::new(),
+			projects: Vec::new(),
+			tasks: Vec::new(),
+			taskprogresses: Vec::new(),
+			tasktimes: Vec::new(),
+			rates: Vec::new(),
+			places: Vec::new(),
+			timerecords: Vec::new(),
+			travels: Vec::new(),
+			work_schedules: Vec::new(),
+			absences: Vec::new(),
+		};
+
+		// -- Exec
+		let res = ArchiveBmc::import_my_data(&ctx, &mm, fx_archive).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::ArchiveVersionUnsupported { .. })),
+			"ArchiveVersionUnsupported not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_my_data_v1_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_name = "project for test_import_my_data_v1_ok";
+		let fx_archive: Archive = serde_json::from_value(json!({
+			"version": 1,
+			"exported_at": "2024-03-01T10:00:00Z",
+			"projects": [{"id": 1, "name": fx_project_name}],
+			"tasks": [{"id": 1, "project_id": 1, "title": "task 01", "done": false}],
+			"taskprogresses": [{"task_id": 1, "progress": 50}],
+			"tasktimes": [{
+				"task_id": 1,
+				"comment": "test_import_my_data_v1_ok",
+				"start_time": "2024-02-28T09:00:00Z",
+				"stop_time": "2024-02-28T10:00:00Z",
+			}],
+			"timerecords": [{
+				"place": "test_import_my_data_v1_ok place",
+				"start_time": "2024-02-28T08:00:00Z",
+				"stop_time": "2024-02-28T17:00:00Z",
+			}],
+		}))?;
+
+		// -- Exec
+		let report = ArchiveBmc::import_my_data(&ctx, &mm, fx_archive).await?;
+
+		// -- Check
+		assert_eq!(report.projects, 1);
+		assert_eq!(report.tasks, 1);
+		assert_eq!(report.taskprogresses, 1);
+		assert_eq!(report.tasktimes, 1);
+		assert_eq!(report.timerecords, 1);
+		assert_eq!(report.clients, 0);
+		assert_eq!(report.places, 0);
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"name": fx_project_name}))?;
+		let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(projects.len(), 1);
+		assert_eq!(projects[0].client_id, None);
+		let filter: TaskFilter =
+			serde_json::from_value(json!({"project_id": projects[0].id}))?;
+		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(tasks.len(), 1);
+		let filter: TaskTimeFilter =
+			serde_json::from_value(json!({"task_id": tasks[0].id}))?;
+		let tasktimes =
+			TaskTimeBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(tasktimes.len(), 1);
+		assert!(tasktimes[0].billable, "should default to billable");
+		let filter: TimeRecordFilter = serde_json::from_value(
+			json!({"place": "test_import_my_data_v1_ok place"}),
+		)?;
+		let timerecords =
+			TimeRecordBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(timerecords.len(), 1);
+		// (the place is resolved from the name, see `model::place`)
+		let fx_place_id =
+			timerecords[0].place_id.context("Should have a place_id")?;
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, projects[0].id).await?;
+		TimeRecordBmc::delete(&ctx, &mm, timerecords[0].id).await?;
+		PlaceBmc::delete(&ctx, &mm, fx_place_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_my_data_err_ref_rollback() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_name = "project for test_import_my_data_err_ref_rollback";
+		let fx_archive = Archive {
+			version: ARCHIVE_VERSION,
+			exported_at: now_utc(),
+			clients: Vec::new(),
+			projects: vec![ArchiveProject {
+				id: 1,
+				client_id: None,
+				name: fx_project_name.to_string(),
+			}],
+			tasks: vec![ArchiveTask {
+				id: 1,
+				project_id: 2, // unknown project
+				title: "task".to_string(),
+				done: false,
+			}],
+			taskprogresses: Vec::new(),
+			tasktimes: Vec::new(),
+			rates: Vec::new(),
+			places: Vec::new(),
+			timerecords: Vec::new(),
+			travels: Vec::new(),
+			work_schedules: Vec::new(),
+			absences: Vec::new(),
+		};
+
+		// -- Exec
+		let res = ArchiveBmc::import_my_data(&ctx, &mm, fx_archive).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::ArchiveRefInvalid {
+					entity: "project",
+					id: 2
+				})
+			),
+			"ArchiveRefInvalid not matching"
+		);
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"name": fx_project_name}))?;
+		let projects = ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert!(projects.is_empty(), "project create should be rolled back");
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
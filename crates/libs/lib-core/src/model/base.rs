@@ -1,20 +1,31 @@
 use crate::ctx::Ctx;
+use crate::model::event::{ModelEvent, ModelEventKind};
+use crate::model::sync::{self, ChangeOp};
 use crate::model::ModelManager;
 use crate::model::{Error, Result};
+use async_stream::try_stream;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
 use lib_utils::time::now_utc;
 use modql::field::{Field, Fields, HasFields};
 use modql::filter::{FilterGroups, ListOptions};
 use modql::SIden;
 use sea_query::{
-	Condition, Expr, Iden, IntoIden, PostgresQueryBuilder, Query, TableRef,
+	Condition, DynIden, Expr, Iden, IntoIden, Keyword, LockType,
+	PostgresQueryBuilder, Query, SelectStatement, SimpleExpr, TableRef,
 };
 use sea_query_binder::SqlxBinder;
 use sqlx::postgres::PgRow;
 use sqlx::FromRow;
+use std::collections::HashMap;
 
 const LIST_LIMIT_DEFAULT: i64 = 1000;
 const LIST_LIMIT_MAX: i64 = 5000;
 
+/// The stream of entities of `list_stream`.
+pub type EntityStream<E> = BoxStream<'static, Result<E>>;
+
 #[derive(Iden)]
 pub enum CommonIden {
 	Id,
@@ -28,12 +39,69 @@ pub enum TimestampIden {
 	Mtime,
 }
 
+/// The `DbBmc` trait must be implemented by the Bmc structs of the entities
+/// backed by a table, and gives them access to the `model::base` functions.
+///
+/// It also has the optional lifecycle hooks, called by the `model::base`
+/// create, update, and delete functions (single and bulk).
+///
+/// - Hooks run inside the same transaction as the operation. The `mm` given
+///   to the hooks holds this transaction, so any Bmc call made with it
+///   (e.g., `TaskBmc::update(ctx, mm, ..)`) is part of it.
+/// - Returning an error from any hook vetoes the operation, and rollbacks
+///   it (including what the other hooks did). When nested (e.g., a Bmc call
+///   from a hook), only the nested operation is rolled back, so the caller
+///   can handle its error and still commit.
+/// - `before_*` hooks get the fields about to be written (without the timestamps),
+///   which they can also change.
+/// - `after_*` hooks run after the write, so the entity can be read back
+///   with its final values (e.g., for cross-entity validation).
+///
+/// The default implementations are no-op. To provide hooks, implement `DbBmc`
+/// by hand (i.e., without `Table` in `generate_common_bmc_fns!`), and override
+/// the needed ones with `#[async_trait]`.
+#[async_trait]
 pub trait DbBmc {
 	const TABLE: &'static str;
 
 	fn table_ref() -> TableRef {
 		TableRef::Table(SIden(Self::TABLE).into_iden())
 	}
+
+	// -- Lifecycle hooks
+
+	async fn before_create(
+		_ctx: &Ctx,
+		_mm: &ModelManager,
+		_fields: &mut Fields,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	async fn after_create(_ctx: &Ctx, _mm: &ModelManager, _id: i64) -> Result<()> {
+		Ok(())
+	}
+
+	async fn before_update(
+		_ctx: &Ctx,
+		_mm: &ModelManager,
+		_id: i64,
+		_fields: &mut Fields,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	async fn after_update(_ctx: &Ctx, _mm: &ModelManager, _id: i64) -> Result<()> {
+		Ok(())
+	}
+
+	async fn before_delete(_ctx: &Ctx, _mm: &ModelManager, _id: i64) -> Result<()> {
+		Ok(())
+	}
+
+	async fn after_delete(_ctx: &Ctx, _mm: &ModelManager, _id: i64) -> Result<()> {
+		Ok(())
+	}
 }
 
 pub fn compute_list_options(
@@ -70,28 +138,42 @@ where
 	MC: DbBmc,
 	E: HasFields,
 {
-	let db = mm.db();
-
 	// -- Extract fields (name / sea-query value expression)
 	let mut fields = data.not_none_fields();
-	add_timestamps_for_create(&mut fields, ctx.user_id());
-	let (columns, sea_values) = fields.for_sea_insert();
-
-	// -- Build query
-	let mut query = Query::insert();
-	query
-		.into_table(MC::table_ref())
-		.columns(columns)
-		.values(sea_values)?
-		.returning(Query::returning().columns([CommonIden::Id]));
 
-	// -- Exec query
-	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let (id,) = sqlx::query_as_with::<_, (i64,), _>(&sql, values)
-		.fetch_one(db)
-		.await?;
+	// -- Start the transaction (for the hooks)
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		MC::before_create(ctx, &mm, &mut fields).await?;
+		add_timestamps_for_create(&mut fields, ctx.user_id());
+		let (columns, sea_values) = fields.for_sea_insert();
+
+		// -- Build query
+		let mut query = Query::insert();
+		query
+			.into_table(MC::table_ref())
+			.columns(columns)
+			.values(sea_values)?
+			.returning(Query::returning().columns([CommonIden::Id]));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let (id,) = mm.dbx().fetch_one(sqlx_query).await?;
+
+		MC::after_create(ctx, &mm, id).await?;
+		let changes = [(id, ctx.user_id())];
+		sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Upsert).await?;
+		emit_event::<MC>(&mm, id, ModelEventKind::Create, ctx.user_id());
+
+		Ok(id)
+	}
+	.await;
 
-	Ok(id)
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
 }
 
 pub async fn get<MC, E>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
@@ -100,8 +182,6 @@ where
 	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
 	E: HasFields,
 {
-	let db = mm.db();
-
 	// -- Build query
 	let mut query = Query::select();
 	query
@@ -111,13 +191,15 @@ where
 
 	// -- Exec query
 	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let entity = sqlx::query_as_with::<_, E, _>(&sql, values)
-		.fetch_optional(db)
-		.await?
-		.ok_or(Error::EntityNotFound {
-			entity: MC::TABLE,
-			id,
-		})?;
+	let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+	let entity =
+		mm.dbx()
+			.fetch_optional(sqlx_query)
+			.await?
+			.ok_or(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			})?;
 
 	Ok(entity)
 }
@@ -134,9 +216,67 @@ where
 	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
 	E: HasFields,
 {
-	let db = mm.db();
+	// -- Build the query
+	let mut query = list_query::<MC, E, F>(filter)?;
+	// list options
+	let list_options = compute_list_options(list_options)?;
+	list_options.apply_to_sea_query(&mut query);
 
+	// -- Execute the query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+	let entities = mm.dbx().fetch_all(sqlx_query).await?;
+
+	Ok(entities)
+}
+
+/// Same as `list`, but streaming the rows as they are fetched,
+/// and without the `LIST_LIMIT_MAX` cap (e.g., for the exports).
+///
+/// Note: The `list_options.limit` is only applied when set, and the stream
+///       runs on the db pool (not in the `ModelManager` transaction).
+pub fn list_stream<MC, E, F>(
+	_ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	list_options: Option<ListOptions>,
+) -> Result<EntityStream<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send + 'static,
+	E: HasFields,
+{
 	// -- Build the query
+	let mut query = list_query::<MC, E, F>(filter)?;
+	// list options (no default limit)
+	let list_options = list_options.unwrap_or(ListOptions {
+		limit: None,
+		offset: None,
+		order_bys: Some("id".into()),
+	});
+	list_options.apply_to_sea_query(&mut query);
+
+	// -- Stream the query rows
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let db = mm.dbx().db().clone();
+	let stream = try_stream! {
+		let mut rows = sqlx::query_as_with::<_, E, _>(&sql, values).fetch(&db);
+		while let Some(entity) = rows.try_next().await? {
+			yield entity;
+		}
+	};
+
+	Ok(Box::pin(stream))
+}
+
+/// The select query of `list` and `list_stream` (without the list options).
+fn list_query<MC, E, F>(filter: Option<F>) -> Result<SelectStatement>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: HasFields,
+{
 	let mut query = Query::select();
 	query.from(MC::table_ref()).columns(E::field_column_refs());
 
@@ -146,17 +286,8 @@ where
 		let cond: Condition = filters.try_into()?;
 		query.cond_where(cond);
 	}
-	// list options
-	let list_options = compute_list_options(list_options)?;
-	list_options.apply_to_sea_query(&mut query);
 
-	// -- Execute the query
-	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let entities = sqlx::query_as_with::<_, E, _>(&sql, values)
-		.fetch_all(db)
-		.await?;
-
-	Ok(entities)
+	Ok(query)
 }
 
 pub async fn update<MC, E>(
@@ -169,69 +300,444 @@ where
 	MC: DbBmc,
 	E: HasFields,
 {
-	let db = mm.db();
-
 	let mut fields = data.not_none_fields();
-	add_timestamps_for_update(&mut fields, ctx.user_id());
-	let fields = fields.for_sea_update();
 
+	// -- Start the transaction (for the hooks)
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		MC::before_update(ctx, &mm, id, &mut fields).await?;
+		add_timestamps_for_update(&mut fields, ctx.user_id());
+		let fields = fields.for_sea_update();
+
+		// -- Build query
+		let mut query = Query::update();
+		query
+			.table(MC::table_ref())
+			.values(fields)
+			.and_where(Expr::col(CommonIden::Id).eq(id))
+			.returning(Query::returning().columns([TimestampIden::Cid]));
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let (owner_id,) = mm.dbx().fetch_optional(sqlx_query).await?.ok_or(
+			Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			},
+		)?;
+
+		MC::after_update(ctx, &mm, id).await?;
+		let changes = [(id, owner_id)];
+		sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Upsert).await?;
+		emit_event::<MC>(&mm, id, ModelEventKind::Update, owner_id);
+
+		Ok(())
+	}
+	.await;
+
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
+}
+
+pub async fn delete<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+	MC: DbBmc,
+{
+	// -- Start the transaction (for the hooks)
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		MC::before_delete(ctx, &mm, id).await?;
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.and_where(Expr::col(CommonIden::Id).eq(id))
+			.returning(Query::returning().columns([TimestampIden::Cid]));
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let (owner_id,) = mm.dbx().fetch_optional(sqlx_query).await?.ok_or(
+			Error::EntityNotFound {
+				entity: MC::TABLE,
+				id,
+			},
+		)?;
+
+		MC::after_delete(ctx, &mm, id).await?;
+		let changes = [(id, owner_id)];
+		sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Delete).await?;
+		emit_event::<MC>(&mm, id, ModelEventKind::Delete, owner_id);
+
+		Ok(())
+	}
+	.await;
+
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
+}
+
+// region:    --- Bulk
+
+/// Insert all `data` items with one multi-row insert, inside a single transaction.
+///
+/// Returns the created ids, in the same order as `data`.
+///
+/// Note: The columns are the union of the items set fields, an item not
+///       setting one (e.g., a `None` field) getting the column `DEFAULT`.
+pub async fn create_many<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	data: Vec<E>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	if data.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let items_fields: Vec<Fields> = data
+		.into_iter()
+		.map(|item| item.not_none_fields())
+		.collect();
+
+	// -- Start the transaction
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		// -- Prep the items values, and the union of their columns
+		let mut columns: Vec<DynIden> = Vec::new();
+		let mut items: Vec<HashMap<String, SimpleExpr>> = Vec::new();
+		for mut fields in items_fields {
+			MC::before_create(ctx, &mm, &mut fields).await?;
+			add_timestamps_for_create(&mut fields, ctx.user_id());
+			let (item_columns, sea_values) = fields.for_sea_insert();
+
+			let mut values = HashMap::new();
+			for (column, value) in item_columns.into_iter().zip(sea_values) {
+				let name = column.to_string();
+				if !columns.iter().any(|c| c.to_string() == name) {
+					columns.push(column);
+				}
+				values.insert(name, value);
+			}
+			items.push(values);
+		}
+
+		// -- Build query
+		let mut query = Query::insert();
+		query.into_table(MC::table_ref());
+		query.columns(columns.clone());
+		for mut values in items {
+			let sea_values = columns.iter().map(|column| {
+				values.remove(&column.to_string()).unwrap_or_else(|| {
+					SimpleExpr::Keyword(Keyword::Custom(
+						SIden("DEFAULT").into_iden(),
+					))
+				})
+			});
+			query.values(sea_values)?;
+		}
+		query.returning(Query::returning().columns([CommonIden::Id]));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let ids: Vec<i64> = mm
+			.dbx()
+			.fetch_all(sqlx_query)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		for id in ids.iter() {
+			MC::after_create(ctx, &mm, *id).await?;
+		}
+		let changes: Vec<(i64, i64)> =
+			ids.iter().map(|id| (*id, ctx.user_id())).collect();
+		sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Upsert).await?;
+		for id in ids.iter() {
+			emit_event::<MC>(&mm, *id, ModelEventKind::Create, ctx.user_id());
+		}
+
+		Ok(ids)
+	}
+	.await;
+
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
+}
+
+/// Get all entities matching `ids`, in the same order as `ids`.
+///
+/// Fails with `EntityNotFound` on the first missing id.
+pub async fn get_many<MC, E>(
+	_ctx: &Ctx,
+	mm: &ModelManager,
+	ids: &[i64],
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields + Clone,
+{
 	// -- Build query
-	let mut query = Query::update();
+	let mut query = Query::select();
 	query
-		.table(MC::table_ref())
-		.values(fields)
-		.and_where(Expr::col(CommonIden::Id).eq(id));
+		.from(MC::table_ref())
+		.columns(E::field_column_refs())
+		.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()));
 
-	// -- Execute query
+	// -- Exec query
 	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let count = sqlx::query_with(&sql, values)
-		.execute(db)
-		.await?
-		.rows_affected();
-
-	// -- Check result
-	if count == 0 {
-		Err(Error::EntityNotFound {
-			entity: MC::TABLE,
-			id,
+	let sqlx_query = sqlx::query_as_with::<_, IdedEntity<E>, _>(&sql, values);
+	let rows = mm.dbx().fetch_all(sqlx_query).await?;
+	let by_id: HashMap<i64, E> =
+		rows.into_iter().map(|row| (row.id, row.entity)).collect();
+
+	// -- Re-order as requested
+	ids.iter()
+		.map(|id| {
+			by_id.get(id).cloned().ok_or(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id: *id,
+			})
 		})
-	} else {
+		.collect()
+}
+
+/// Update each `(id, data)` item, inside a single transaction.
+///
+/// Fails (and rollbacks all items) with `EntityNotFound` on the first missing id.
+pub async fn update_many<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	items: Vec<(i64, E)>,
+) -> Result<()>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	let items_fields: Vec<(i64, Fields)> = items
+		.into_iter()
+		.map(|(id, data)| (id, data.not_none_fields()))
+		.collect();
+
+	// -- Start the transaction
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		for (id, mut fields) in items_fields {
+			MC::before_update(ctx, &mm, id, &mut fields).await?;
+			add_timestamps_for_update(&mut fields, ctx.user_id());
+			let fields = fields.for_sea_update();
+
+			// -- Build query
+			let mut query = Query::update();
+			query
+				.table(MC::table_ref())
+				.values(fields)
+				.and_where(Expr::col(CommonIden::Id).eq(id))
+				.returning(Query::returning().columns([TimestampIden::Cid]));
+
+			// -- Execute query
+			let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+			let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+			let (owner_id,) = mm.dbx().fetch_optional(sqlx_query).await?.ok_or(
+				Error::EntityNotFound {
+					entity: MC::TABLE,
+					id,
+				},
+			)?;
+
+			MC::after_update(ctx, &mm, id).await?;
+			let changes = [(id, owner_id)];
+			sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Upsert).await?;
+			emit_event::<MC>(&mm, id, ModelEventKind::Update, owner_id);
+		}
+
+		Ok(())
+	}
+	.await;
+
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
+}
+
+/// Delete all entities matching `ids` with one statement, in a single transaction.
+///
+/// Fails (and rollbacks) with `EntityNotFound` on the first missing id.
+pub async fn delete_many<MC>(ctx: &Ctx, mm: &ModelManager, ids: &[i64]) -> Result<()>
+where
+	MC: DbBmc,
+{
+	// -- Start the transaction
+	let mm = mm.new_with_txn();
+	mm.begin_txn().await?;
+
+	let res = async {
+		for id in ids {
+			MC::before_delete(ctx, &mm, *id).await?;
+		}
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()))
+			.returning(Query::returning().columns([
+				CommonIden::Id.into_iden(),
+				TimestampIden::Cid.into_iden(),
+			]));
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64, i64), _>(&sql, values);
+		let owner_by_id: HashMap<i64, i64> =
+			mm.dbx().fetch_all(sqlx_query).await?.into_iter().collect();
+
+		// -- Check result
+		if let Some(id) = ids.iter().find(|id| !owner_by_id.contains_key(id)) {
+			return Err(Error::EntityNotFound {
+				entity: MC::TABLE,
+				id: *id,
+			});
+		}
+
+		for id in ids {
+			MC::after_delete(ctx, &mm, *id).await?;
+		}
+		let changes: Vec<(i64, i64)> =
+			ids.iter().map(|id| (*id, owner_by_id[id])).collect();
+		sync::record_changes(&mm, MC::TABLE, &changes, ChangeOp::Delete).await?;
+		for (id, owner_id) in owner_by_id {
+			emit_event::<MC>(&mm, id, ModelEventKind::Delete, owner_id);
+		}
+
 		Ok(())
 	}
+	.await;
+
+	// -- Commit the transaction (or rollback on error)
+	mm.end_txn(res).await
+}
+
+/// Row wrapper to get the `id` along the entity without requiring an accessor
+/// on `E` (used to re-order `get_many` results).
+///
+/// Note: `E` fields must include the `id` column.
+struct IdedEntity<E> {
+	id: i64,
+	entity: E,
 }
 
-pub async fn delete<MC>(_ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+impl<'r, E> FromRow<'r, PgRow> for IdedEntity<E>
+where
+	E: FromRow<'r, PgRow>,
+{
+	fn from_row(row: &'r PgRow) -> core::result::Result<Self, sqlx::Error> {
+		use sqlx::Row;
+
+		Ok(IdedEntity {
+			id: row.try_get("id")?,
+			entity: E::from_row(row)?,
+		})
+	}
+}
+
+/// Lock the `MC` rows of `ids` (i.e., `SELECT ... FOR UPDATE`) until the end
+/// of the `mm` transaction, so the concurrent transactions locking or writing
+/// them wait for it.
+pub(in crate::model) async fn lock_for_update<MC>(
+	mm: &ModelManager,
+	ids: &[i64],
+) -> Result<()>
 where
 	MC: DbBmc,
 {
-	let db = mm.db();
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()))
+		.lock(LockType::Update);
+
+	// -- Execute query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	mm.dbx().fetch_all(sqlx_query).await?;
+
+	Ok(())
+}
 
+/// Delete (with `delete_many`, so with their hooks, change log, and events)
+/// the `MC` children of the `parent_id` parent (by their `parent_col`).
+///
+/// Note: Called by the parent `before_delete` hooks, in place of the
+///       db `ON DELETE CASCADE` (which would skip the children hooks,
+///       e.g., the period lock check, and leave no sync tombstones).
+pub(in crate::model) async fn delete_children<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	parent_col: &'static str,
+	parent_id: i64,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
 	// -- Build query
-	let mut query = Query::delete();
+	let mut query = Query::select();
 	query
-		.from_table(MC::table_ref())
-		.and_where(Expr::col(CommonIden::Id).eq(id));
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.and_where(Expr::col(SIden(parent_col)).eq(parent_id));
 
 	// -- Execute query
 	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-	let count = sqlx::query_with(&sql, values)
-		.execute(db)
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let ids: Vec<i64> = mm
+		.dbx()
+		.fetch_all(sqlx_query)
 		.await?
-		.rows_affected();
+		.into_iter()
+		.map(|(id,)| id)
+		.collect();
 
-	// -- Check result
-	if count == 0 {
-		Err(Error::EntityNotFound {
-			entity: MC::TABLE,
-			id,
-		})
-	} else {
-		Ok(())
+	if ids.is_empty() {
+		return Ok(());
 	}
+	delete_many::<MC>(ctx, mm, &ids).await
 }
 
+// endregion: --- Bulk
+
 // region:    --- Utils
 
+/// Emit the `ModelEvent` of the entity (held until the commit, see `model::event`).
+fn emit_event<MC>(mm: &ModelManager, id: i64, kind: ModelEventKind, owner_id: i64)
+where
+	MC: DbBmc,
+{
+	mm.emit_event(ModelEvent {
+		entity: MC::TABLE,
+		id,
+		kind,
+		owner_id,
+	});
+}
+
 /// Update the timestamps info for create
 /// (e.g., cid, ctime, and mid, mtime will be updated with the same values)
 pub fn add_timestamps_for_create(fields: &mut Fields, user_id: i64) {
@@ -0,0 +1,109 @@
+//! Clients, i.e., the invoiced customers of the projects
+//! (`project.client_id`, see `model::invoice`).
+
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::OpValsString;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Client Types
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Client {
+	pub id: i64,
+
+	pub name: String,
+	/// The postal address, as printed on the invoices (multi-line).
+	pub address: String,
+	/// The ISO 4217 currency of the rates and invoices (e.g., "EUR").
+	pub currency: String,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+/// Note: The omitted `address` is empty, and `currency` is "EUR".
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
+pub struct ClientForCreate {
+	pub name: String,
+	pub address: Option<String>,
+	pub currency: Option<String>,
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
+pub struct ClientForUpdate {
+	pub name: Option<String>,
+	pub address: Option<String>,
+	pub currency: Option<String>,
+}
+
+generate_filter!(ClientFilter {
+	name: OpValsString,
+	currency: OpValsString,
+});
+// endregion: --- Client Types
+
+// region:    --- ClientBmc
+pub struct ClientBmc;
+
+generate_common_bmc_fns!(
+	Bmc: ClientBmc,
+	Table: "client",
+	Entity: Client,
+	ForCreate: ClientForCreate,
+	ForUpdate: ClientForUpdate,
+	Filter: ClientFilter,
+);
+// endregion: --- ClientBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::ctx::Ctx;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+
+		// -- Exec
+		let client_c = ClientForCreate {
+			name: "test_create_ok client".to_string(),
+			address: None,
+			currency: None,
+		};
+		let id = ClientBmc::create(&ctx, &mm, client_c).await?;
+
+		// -- Check
+		let client = ClientBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(client.name, "test_create_ok client");
+		assert_eq!(client.address, "");
+		assert_eq!(client.currency, "EUR");
+
+		// -- Clean
+		ClientBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,3 +1,4 @@
+use crate::model::anomaly::Anomaly;
 use crate::model::store;
 use derive_more::From;
 use lib_auth::pwd;
@@ -17,6 +18,88 @@ pub enum Error {
 		max: i64,
 		actual: i64,
 	},
+	AdminOnly,
+	SyncPushDataMissing {
+		uuid: uuid::Uuid,
+	},
+	ImportFileInvalid(String),
+	ArchiveVersionUnsupported {
+		version: u32,
+		supported: u32,
+	},
+	ArchiveIdDuplicate {
+		entity: &'static str,
+		id: i64,
+	},
+	ArchiveRefInvalid {
+		entity: &'static str,
+		id: i64,
+	},
+	PlaceNameEmpty,
+	TravelDistanceInvalid(f64),
+	WorkScheduleHoursInvalid {
+		weekday: String,
+		hours: f64,
+	},
+	AbsenceHoursInvalid(f64),
+	HolidayCalendarNotFound(String),
+	HolidayRegionNotFound {
+		country: String,
+		region: String,
+	},
+	HolidayRulesInvalid(String),
+	RateAmountInvalid(i64),
+	InvoicePeriodInvalid {
+		period_start: String,
+		period_end: String,
+	},
+	InvoiceRateMissing {
+		tasktime_id: i64,
+	},
+	InvoiceTaskTimeInvoiced {
+		tasktime_id: i64,
+	},
+	InvoiceLocked {
+		id: i64,
+	},
+	RoundingIncrementInvalid(i64),
+	RoundingMinInvalid(i64),
+	PeriodLockInvalid {
+		start_time: String,
+		stop_time: String,
+	},
+	PeriodLocked {
+		lock_id: i64,
+		start_time: String,
+		stop_time: String,
+	},
+	PeriodLockNotActive {
+		id: i64,
+	},
+	TimesheetPeriodInvalid {
+		start_time: String,
+		stop_time: String,
+	},
+	TimesheetStatusInvalid {
+		id: i64,
+		status: String,
+		action: &'static str,
+	},
+	TimesheetNotOwner {
+		id: i64,
+	},
+	TimesheetNotApprover {
+		id: i64,
+	},
+	TimesheetApproverMissing {
+		user_id: i64,
+	},
+	TimesheetCommentRequired {
+		id: i64,
+	},
+	AnomalyRejected {
+		anomalies: Vec<Anomaly>,
+	},
 
 	// -- Modules
 	#[from]
@@ -30,6 +113,8 @@ pub enum Error {
 	#[from]
 	Sqlx(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
 	#[from]
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+	#[from]
 	ModqlIntoSea(#[serde_as(as = "DisplayFromStr")] modql::filter::IntoSeaError),
 }
 
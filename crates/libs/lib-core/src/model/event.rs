@@ -0,0 +1,173 @@
+//! In-process model events (e.g., for the web-server `/api/events` stream).
+//!
+//! - The `model::base` write functions emit a `ModelEvent` per created,
+//!   updated, or deleted entity.
+//! - Events emitted inside a transaction are held until the outermost commit
+//!   (and dropped with the rolled back level), so subscribers only see
+//!   committed changes.
+//! - Subscribers get a `broadcast::Receiver` from `ModelManager::subscribe_events`,
+//!   and are responsible for filtering the events they are allowed to see
+//!   (see `ModelEvent::is_visible_to`).
+
+use crate::ctx::Ctx;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Number of events kept for slow subscribers before they start lagging.
+const EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelEvent {
+	/// The entity table name (e.g., "task").
+	pub entity: &'static str,
+	pub id: i64,
+	pub kind: ModelEventKind,
+	/// The owner of the entity (i.e., its creator `cid`).
+	#[serde(skip)]
+	pub owner_id: i64,
+}
+
+impl ModelEvent {
+	/// Returns true if the user of the `ctx` owns the entity of this event.
+	pub fn is_visible_to(&self, ctx: &Ctx) -> bool {
+		self.owner_id == ctx.user_id()
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelEventKind {
+	Create,
+	Update,
+	Delete,
+}
+
+// endregion: --- Types
+
+// region:    --- EventBus
+
+#[derive(Clone)]
+pub(in crate::model) struct EventBus {
+	tx: broadcast::Sender<ModelEvent>,
+	/// The events waiting for the transaction commit (None when not in a transaction).
+	pending: Option<Arc<Mutex<PendingEvents>>>,
+}
+
+#[derive(Default)]
+struct PendingEvents {
+	events: Vec<ModelEvent>,
+	/// The `events` length at the start of each open transaction level.
+	level_starts: Vec<usize>,
+}
+
+impl EventBus {
+	pub(in crate::model) fn new() -> Self {
+		let (tx, _) = broadcast::channel(EVENTS_CHANNEL_CAPACITY);
+		EventBus { tx, pending: None }
+	}
+
+	/// Returns a new bus on the same channel, holding its events until `flush`.
+	pub(in crate::model) fn new_with_txn(&self) -> Self {
+		EventBus {
+			tx: self.tx.clone(),
+			pending: Some(Arc::default()),
+		}
+	}
+
+	pub(in crate::model) fn subscribe(&self) -> broadcast::Receiver<ModelEvent> {
+		self.tx.subscribe()
+	}
+
+	pub(in crate::model) fn emit(&self, event: ModelEvent) {
+		match &self.pending {
+			Some(pending) => pending.lock().unwrap().events.push(event),
+			// Note: Send only fails when there is no subscriber, which is fine.
+			None => {
+				let _ = self.tx.send(event);
+			}
+		}
+	}
+
+	pub(in crate::model) fn begin_level(&self) {
+		if let Some(pending) = &self.pending {
+			let mut pending = pending.lock().unwrap();
+			let start = pending.events.len();
+			pending.level_starts.push(start);
+		}
+	}
+
+	/// Keeps the events of the committed level, for the outer levels.
+	pub(in crate::model) fn commit_level(&self) {
+		if let Some(pending) = &self.pending {
+			pending.lock().unwrap().level_starts.pop();
+		}
+	}
+
+	/// Drops the events of the rolled back level.
+	pub(in crate::model) fn rollback_level(&self) {
+		if let Some(pending) = &self.pending {
+			let mut pending = pending.lock().unwrap();
+			if let Some(start) = pending.level_starts.pop() {
+				pending.events.truncate(start);
+			}
+		}
+	}
+
+	/// Sends the pending events (to be called once the transaction is committed).
+	pub(in crate::model) fn flush(&self) {
+		let Some(pending) = &self.pending else {
+			return;
+		};
+
+		let events = std::mem::take(&mut pending.lock().unwrap().events);
+		for event in events {
+			let _ = self.tx.send(event);
+		}
+	}
+}
+
+// endregion: --- EventBus
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_subscribe_events_create_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let mut events_rx = mm.subscribe_events();
+
+		// -- Exec
+		let project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_subscribe_events_create_delete_ok project",
+		)
+		.await?;
+		ProjectBmc::delete(&ctx, &mm, project_id).await?;
+
+		// -- Check
+		let event = events_rx.try_recv()?;
+		assert_eq!(event.entity, "project");
+		assert_eq!(event.id, project_id);
+		assert_eq!(event.kind, ModelEventKind::Create);
+		assert!(event.is_visible_to(&ctx));
+		let event = events_rx.try_recv()?;
+		assert_eq!(event.id, project_id);
+		assert_eq!(event.kind, ModelEventKind::Delete);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,536 @@
+//! Public holidays, computed from the bundled per-country rule files
+//! (`rules/*.json`), without any network access.
+//!
+//! Design:
+//!
+//! - A rule file lists the national holidays of a country, and the extra
+//!   holidays of its regions (e.g., "BY" for Bayern in "DE").
+//! - A holiday date is either fixed (`"date": "MM-DD"`), relative to the
+//!   Easter Sunday (`"easter": -2` for the Good Friday), or the nth weekday
+//!   of a month (`"weekday": {"month": 11, "weekday": "thursday", "nth": 4}`,
+//!   a `nth` of -1 being the last).
+//! - A holiday falling on a weekend can be `observed` on the nearest weekday
+//!   (Saturday to Friday, Sunday to Monday), or on the next free weekday
+//!   (e.g., the UK substitute days).
+//! - `HolidayBmc::import_holidays` creates the holidays of a year as
+//!   `holiday` absences of the user (see `model::absence`).
+
+use crate::ctx::Ctx;
+use crate::model::absence::{AbsenceBmc, AbsenceForCreate, AbsenceKind};
+use crate::model::modql_utils::iso_date;
+use crate::model::{Error, ModelManager, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use time::{Date, Duration, Month, Weekday};
+
+/// The bundled rule files (one per country).
+const RULE_FILES: &[&str] = &[
+	include_str!("rules/at.json"),
+	include_str!("rules/de.json"),
+	include_str!("rules/fr.json"),
+	include_str!("rules/gb.json"),
+	include_str!("rules/us.json"),
+];
+
+// region:    --- Holiday Types
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HolidayCalendar {
+	/// The ISO 3166-1 country code (e.g., "DE").
+	pub country: String,
+	pub name: String,
+	/// Ordered by code.
+	pub regions: Vec<HolidayRegion>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HolidayRegion {
+	/// The region code (e.g., "BY").
+	pub code: String,
+	pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct Holiday {
+	/// The (observed) date.
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub date: Date,
+	pub name: String,
+}
+
+// endregion: --- Holiday Types
+
+// region:    --- Rule Types
+
+#[derive(Deserialize)]
+struct CalendarRules {
+	country: String,
+	name: String,
+	holidays: Vec<HolidayRule>,
+	#[serde(default)]
+	regions: BTreeMap<String, RegionRules>,
+}
+
+#[derive(Deserialize)]
+struct RegionRules {
+	name: String,
+	holidays: Vec<HolidayRule>,
+}
+
+#[derive(Deserialize)]
+struct HolidayRule {
+	name: String,
+	#[serde(flatten)]
+	date: DateRule,
+	observed: Option<Observed>,
+	from_year: Option<i32>,
+	to_year: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DateRule {
+	/// A fixed "MM-DD" date.
+	Date(String),
+	/// The days after the Easter Sunday.
+	Easter(i64),
+	/// The `nth` `weekday` of the `month` (-1 for the last).
+	Weekday {
+		month: u8,
+		weekday: WeekdayRule,
+		nth: i8,
+	},
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum WeekdayRule {
+	Monday,
+	Tuesday,
+	Wednesday,
+	Thursday,
+	Friday,
+	Saturday,
+	Sunday,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Observed {
+	NearestWeekday,
+	NextWeekday,
+}
+
+// endregion: --- Rule Types
+
+// region:    --- Holidays
+
+/// Returns the bundled holiday calendars, by country.
+pub fn holiday_calendars() -> Result<Vec<HolidayCalendar>> {
+	let mut calendars = RULE_FILES
+		.iter()
+		.map(|content| {
+			let rules = parse_rules(content)?;
+			let regions = rules
+				.regions
+				.into_iter()
+				.map(|(code, region)| HolidayRegion {
+					code,
+					name: region.name,
+				})
+				.collect();
+
+			Ok(HolidayCalendar {
+				country: rules.country,
+				name: rules.name,
+				regions,
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+	calendars.sort_by(|a, b| a.country.cmp(&b.country));
+
+	Ok(calendars)
+}
+
+/// Returns the holidays of the `country` (and `region`) in the `year`,
+/// by date.
+///
+/// Note: An observed date can fall in the previous or next year
+///       (e.g., a Saturday January 1st observed on December 31st).
+pub fn holidays(
+	country: &str,
+	region: Option<&str>,
+	year: i32,
+) -> Result<Vec<Holiday>> {
+	let mut rules = calendar_rules(country)?;
+
+	let mut holiday_rules = std::mem::take(&mut rules.holidays);
+	if let Some(region) = region {
+		let region_rules = rules
+			.regions
+			.keys()
+			.find(|code| code.eq_ignore_ascii_case(region))
+			.cloned()
+			.and_then(|code| rules.regions.remove(&code))
+			.ok_or_else(|| Error::HolidayRegionNotFound {
+				country: rules.country.clone(),
+				region: region.to_string(),
+			})?;
+		holiday_rules.extend(region_rules.holidays);
+	}
+
+	Ok(compute_holidays(&holiday_rules, year))
+}
+
+fn calendar_rules(country: &str) -> Result<CalendarRules> {
+	for content in RULE_FILES {
+		let rules = parse_rules(content)?;
+		if rules.country.eq_ignore_ascii_case(country) {
+			return Ok(rules);
+		}
+	}
+
+	Err(Error::HolidayCalendarNotFound(country.to_string()))
+}
+
+fn parse_rules(content: &str) -> Result<CalendarRules> {
+	serde_json::from_str(content)
+		.map_err(|err| Error::HolidayRulesInvalid(err.to_string()))
+}
+
+fn compute_holidays(rules: &[HolidayRule], year: i32) -> Vec<Holiday> {
+	// -- The actual dates of the year rules
+	let mut dates: Vec<(&HolidayRule, Date)> = rules
+		.iter()
+		.filter(|rule| rule.from_year.is_none_or(|from| from <= year))
+		.filter(|rule| rule.to_year.is_none_or(|to| year <= to))
+		.filter_map(|rule| Some((rule, rule.date.date_in(year)?)))
+		.collect();
+	dates.sort_by_key(|(_, date)| *date);
+
+	// -- The observed dates (the substitutes skipping the taken days)
+	let mut taken: HashSet<Date> = dates
+		.iter()
+		.filter(|(rule, date)| rule.observed.is_none() || !is_weekend(*date))
+		.map(|(_, date)| *date)
+		.collect();
+	let mut holidays: Vec<Holiday> = dates
+		.into_iter()
+		.map(|(rule, date)| {
+			let date = match rule.observed {
+				Some(observed) if is_weekend(date) => observed.shift(date, &taken),
+				_ => date,
+			};
+			taken.insert(date);
+
+			Holiday {
+				date,
+				name: rule.name.clone(),
+			}
+		})
+		.collect();
+	holidays.sort_by_key(|holiday| holiday.date);
+
+	holidays
+}
+
+impl DateRule {
+	/// Returns the date of the rule in the `year`, if any
+	/// (e.g., none for a "02-29" in a non leap year).
+	fn date_in(&self, year: i32) -> Option<Date> {
+		match self {
+			DateRule::Date(month_day) => {
+				let (month, day) = month_day.split_once('-')?;
+				let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+				Date::from_calendar_date(year, month, day.parse().ok()?).ok()
+			}
+			DateRule::Easter(days) => {
+				Some(easter_sunday(year)? + Duration::days(*days))
+			}
+			DateRule::Weekday {
+				month,
+				weekday,
+				nth,
+			} => nth_weekday(
+				year,
+				Month::try_from(*month).ok()?,
+				weekday.into(),
+				*nth,
+			),
+		}
+	}
+}
+
+impl Observed {
+	fn shift(&self, date: Date, taken: &HashSet<Date>) -> Date {
+		match self {
+			Observed::NearestWeekday => match date.weekday() {
+				Weekday::Saturday => date - Duration::days(1),
+				Weekday::Sunday => date + Duration::days(1),
+				_ => date,
+			},
+			Observed::NextWeekday => {
+				let mut date = date + Duration::days(1);
+				while is_weekend(date) || taken.contains(&date) {
+					date += Duration::days(1);
+				}
+				date
+			}
+		}
+	}
+}
+
+impl From<&WeekdayRule> for Weekday {
+	fn from(weekday: &WeekdayRule) -> Self {
+		match weekday {
+			WeekdayRule::Monday => Weekday::Monday,
+			WeekdayRule::Tuesday => Weekday::Tuesday,
+			WeekdayRule::Wednesday => Weekday::Wednesday,
+			WeekdayRule::Thursday => Weekday::Thursday,
+			WeekdayRule::Friday => Weekday::Friday,
+			WeekdayRule::Saturday => Weekday::Saturday,
+			WeekdayRule::Sunday => Weekday::Sunday,
+		}
+	}
+}
+
+fn is_weekend(date: Date) -> bool {
+	matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday)
+}
+
+/// The Easter Sunday of the (gregorian) `year`
+/// (the "Anonymous Gregorian algorithm").
+fn easter_sunday(year: i32) -> Option<Date> {
+	let a = year % 19;
+	let b = year / 100;
+	let c = year % 100;
+	let d = b / 4;
+	let e = b % 4;
+	let f = (b + 8) / 25;
+	let g = (b - f + 1) / 3;
+	let h = (19 * a + b - d - g + 15) % 30;
+	let i = c / 4;
+	let k = c % 4;
+	let l = (32 + 2 * e + 2 * i - h - k) % 7;
+	let m = (a + 11 * h + 22 * l) / 451;
+	let month = (h + l - 7 * m + 114) / 31;
+	let day = (h + l - 7 * m + 114) % 31 + 1;
+
+	let month = Month::try_from(u8::try_from(month).ok()?).ok()?;
+	Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+}
+
+/// The `nth` (1 based) `weekday` of the `month`, or the last ones
+/// for a negative `nth` (e.g., -1 for the last).
+fn nth_weekday(year: i32, month: Month, weekday: Weekday, nth: i8) -> Option<Date> {
+	let weeks = Duration::weeks(i64::from(nth.unsigned_abs()) - 1);
+
+	let date = if nth > 0 {
+		let first = Date::from_calendar_date(year, month, 1).ok()?;
+		let days = (weekday.number_days_from_monday() + 7
+			- first.weekday().number_days_from_monday())
+			% 7;
+		first + Duration::days(days.into()) + weeks
+	} else if nth < 0 {
+		let last_day = month.length(year);
+		let last = Date::from_calendar_date(year, month, last_day).ok()?;
+		let days = (last.weekday().number_days_from_monday() + 7
+			- weekday.number_days_from_monday())
+			% 7;
+		last - Duration::days(days.into()) - weeks
+	} else {
+		return None;
+	};
+
+	(date.month() == month).then_some(date)
+}
+
+// endregion: --- Holidays
+
+// region:    --- HolidayBmc
+pub struct HolidayBmc;
+
+impl HolidayBmc {
+	/// Creates the holidays of the `year` as `holiday` absences of the ctx
+	/// user, skipping the dates with a `holiday` absence already (so a
+	/// re-import is a no-op), and returns the created absence ids.
+	pub async fn import_holidays(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		country: &str,
+		region: Option<&str>,
+		year: i32,
+	) -> Result<Vec<i64>> {
+		let holidays = holidays(country, region, year)?;
+		let (Some(first), Some(last)) = (holidays.first(), holidays.last()) else {
+			return Ok(Vec::new());
+		};
+
+		let existing: HashSet<Date> = AbsenceBmc::list_for_user(
+			ctx,
+			mm,
+			first.date,
+			last.date + Duration::days(1),
+		)
+		.await?
+		.into_iter()
+		.filter(|absence| absence.kind == AbsenceKind::Holiday.as_str())
+		.map(|absence| absence.date)
+		.collect();
+
+		let absences_c: Vec<AbsenceForCreate> = holidays
+			.into_iter()
+			.filter(|holiday| !existing.contains(&holiday.date))
+			.map(|holiday| AbsenceForCreate {
+				date: holiday.date,
+				kind: AbsenceKind::Holiday,
+				hours: None,
+				comment: holiday.name,
+			})
+			.collect();
+		if absences_c.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		AbsenceBmc::create_many(ctx, mm, absences_c).await
+	}
+}
+// endregion: --- HolidayBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+	use time::macros::date;
+
+	fn fx_dates(holidays: &[Holiday]) -> Vec<Date> {
+		holidays.iter().map(|holiday| holiday.date).collect()
+	}
+
+	#[test]
+	fn test_holiday_calendars_ok() -> Result<()> {
+		// -- Exec
+		let calendars = holiday_calendars()?;
+
+		// -- Check
+		let countries: Vec<&str> =
+			calendars.iter().map(|cal| cal.country.as_str()).collect();
+		assert_eq!(countries, vec!["AT", "DE", "FR", "GB", "US"]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_easter_sunday_ok() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(easter_sunday(2019), Some(date!(2019 - 04 - 21)));
+		assert_eq!(easter_sunday(2024), Some(date!(2024 - 03 - 31)));
+		assert_eq!(easter_sunday(2025), Some(date!(2025 - 04 - 20)));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_holidays_de_by_ok() -> Result<()> {
+		// -- Exec
+		let holidays = holidays("de", Some("by"), 2024)?;
+
+		// -- Check
+		assert_eq!(holidays.len(), 13);
+		let names: Vec<(Date, &str)> = holidays
+			.iter()
+			.map(|holiday| (holiday.date, holiday.name.as_str()))
+			.collect();
+		assert!(names.contains(&(date!(2024 - 03 - 29), "Karfreitag")));
+		assert!(names.contains(&(date!(2024 - 05 - 30), "Fronleichnam")));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_holidays_gb_eng_observed_ok() -> Result<()> {
+		// -- Exec
+		// (the 2021 Christmas is a Saturday, the Boxing Day a Sunday)
+		let holidays = holidays("GB", Some("ENG"), 2021)?;
+
+		// -- Check
+		assert_eq!(
+			fx_dates(&holidays),
+			vec![
+				date!(2021 - 01 - 01),
+				date!(2021 - 04 - 02),
+				date!(2021 - 04 - 05),
+				date!(2021 - 05 - 03),
+				date!(2021 - 05 - 31),
+				date!(2021 - 08 - 30),
+				date!(2021 - 12 - 27),
+				date!(2021 - 12 - 28),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_holidays_us_ok() -> Result<()> {
+		// -- Exec
+		let holidays = holidays("US", None, 2021)?;
+
+		// -- Check
+		let dates = fx_dates(&holidays);
+		// Juneteenth and Independence Day on weekends
+		assert!(dates.contains(&date!(2021 - 06 - 18)));
+		assert!(dates.contains(&date!(2021 - 07 - 05)));
+		// Thanksgiving, the 4th Thursday of November
+		assert!(dates.contains(&date!(2021 - 11 - 25)));
+		// Christmas on a Saturday
+		assert!(dates.contains(&date!(2021 - 12 - 24)));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_holidays_err_region_not_found() -> Result<()> {
+		// -- Exec
+		let res = holidays("DE", Some("XX"), 2024);
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::HolidayRegionNotFound { .. })),
+			"HolidayRegionNotFound not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_holidays_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+
+		// -- Exec
+		let ids = HolidayBmc::import_holidays(&ctx, &mm, "AT", None, 2001).await?;
+		let again = HolidayBmc::import_holidays(&ctx, &mm, "AT", None, 2001).await?;
+
+		// -- Check
+		assert_eq!(ids.len(), 13);
+		assert!(again.is_empty(), "re-import should be a no-op");
+		let absence = AbsenceBmc::get(&ctx, &mm, ids[0]).await?;
+		assert_eq!(absence.date, date!(2001 - 01 - 01));
+		assert_eq!(absence.kind, "holiday");
+		assert_eq!(absence.comment, "Neujahr");
+
+		// -- Clean
+		AbsenceBmc::delete_many(&ctx, &mm, &ids).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
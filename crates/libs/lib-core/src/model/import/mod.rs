@@ -0,0 +1,578 @@
+//! Import of the time entries of other trackers
+//! (Toggl csv, Clockify csv, and timewarrior json).
+//!
+//! Design:
+//!
+//! - Each format is parsed into `ImportRow`s, one per time entry, each being
+//!   an `ImportEntry` or the row error (e.g., invalid date), so a bad row does
+//!   not fail the whole file.
+//! - Each entry is mapped onto the user data:
+//!   - the client and project become a `Project` (e.g., "ACME / Website"),
+//!   - the task (or else the description) becomes a `Task` of this project,
+//!   - the entry becomes a `TaskTime` of this task.
+//! - The projects and tasks are matched by name, and a `TaskTime` with the
+//!   same task, start and stop time is a duplicate, so a re-import of the same
+//!   (or an overlapping) file only adds the new entries.
+//! - The import runs in one transaction. With `dry_run`, the transaction is
+//!   rolled back, and the report lists what would have been imported.
+//!   Each entry runs in a nested transaction (i.e., a savepoint), so an entry
+//!   failing in the model (e.g., `PeriodLocked`) is only rolled back itself,
+//!   and reported as a row error.
+
+// region:    --- Modules
+
+mod timewarrior;
+mod tracker_csv;
+
+use crate::ctx::Ctx;
+use crate::model::project::{ProjectBmc, ProjectFilter, ProjectForCreate};
+use crate::model::task::{TaskBmc, TaskFilter, TaskForCreate};
+use crate::model::tasktime::{TaskTimeBmc, TaskTimeFilter, TaskTimeForCreate};
+use crate::model::{ModelManager, Result};
+use lib_utils::time::{format_time, Rfc3339};
+use modql::filter::ListOptions;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use std::collections::{HashMap, HashSet};
+use time::{OffsetDateTime, UtcOffset};
+
+// endregion: --- Modules
+
+/// The project name of the entries without client and project.
+const NO_PROJECT_NAME: &str = "Imported";
+/// The task title of the entries without task and description.
+const NO_TASK_TITLE: &str = "(no description)";
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+	TogglCsv,
+	ClockifyCsv,
+	TimewarriorJson,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+	/// Rollback the import, and list the `ImportItem`s in the report.
+	pub dry_run: bool,
+	/// The offset of the local times of the csv formats.
+	pub utc_offset: UtcOffset,
+}
+
+impl Default for ImportOptions {
+	fn default() -> Self {
+		Self {
+			dry_run: false,
+			utc_offset: UtcOffset::UTC,
+		}
+	}
+}
+
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct ImportReport {
+	pub dry_run: bool,
+	/// The number of parsed rows (imported, duplicates, and errors).
+	pub rows: usize,
+	pub projects_created: usize,
+	pub tasks_created: usize,
+	pub tasktimes_created: usize,
+	pub duplicates: usize,
+	pub errors: Vec<ImportRowError>,
+	/// The entries as mapped (only for a dry run).
+	pub items: Vec<ImportItem>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ImportRowError {
+	/// The csv line (the header being 1), or the json array position (from 1).
+	pub row: usize,
+	pub cause: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportItemStatus {
+	Created,
+	Duplicate,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ImportItem {
+	pub row: usize,
+	pub project: String,
+	pub task: String,
+	pub comment: String,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+	pub status: ImportItemStatus,
+}
+
+/// A parsed row of an import file.
+pub(in crate::model) struct ImportRow {
+	pub row: usize,
+	pub entry: core::result::Result<ImportEntry, String>,
+}
+
+/// A time entry, as exported by the other trackers.
+pub(in crate::model) struct ImportEntry {
+	pub client: Option<String>,
+	pub project: Option<String>,
+	pub task: Option<String>,
+	pub description: String,
+	pub start_time: OffsetDateTime,
+	pub stop_time: OffsetDateTime,
+}
+
+impl ImportEntry {
+	fn project_name(&self) -> String {
+		match (&self.client, &self.project) {
+			(Some(client), Some(project)) => format!("{client} / {project}"),
+			(None, Some(name)) | (Some(name), None) => name.to_string(),
+			(None, None) => NO_PROJECT_NAME.to_string(),
+		}
+	}
+
+	/// Returns the task title and the tasktime comment.
+	fn task_title_and_comment(&self) -> (String, String) {
+		match &self.task {
+			Some(task) => (task.to_string(), self.description.to_string()),
+			None if !self.description.is_empty() => {
+				(self.description.to_string(), String::new())
+			}
+			None => (NO_TASK_TITLE.to_string(), String::new()),
+		}
+	}
+}
+
+// endregion: --- Types
+
+// region:    --- ImportBmc
+
+pub struct ImportBmc;
+
+impl ImportBmc {
+	/// Imports the `content` of an export file of another tracker.
+	///
+	/// Fails with `ImportFileInvalid` when the file cannot be read at all
+	/// (e.g., missing csv column), the row errors being in the report.
+	pub async fn import(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		format: ImportFormat,
+		content: &str,
+		options: ImportOptions,
+	) -> Result<ImportReport> {
+		let rows = match format {
+			ImportFormat::TogglCsv => {
+				tracker_csv::parse(content, &tracker_csv::TOGGL, options.utc_offset)?
+			}
+			ImportFormat::ClockifyCsv => tracker_csv::parse(
+				content,
+				&tracker_csv::CLOCKIFY,
+				options.utc_offset,
+			)?,
+			ImportFormat::TimewarriorJson => timewarrior::parse(content)?,
+		};
+
+		// -- Start the transaction
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let mut importer = Importer {
+				report: ImportReport {
+					dry_run: options.dry_run,
+					rows: rows.len(),
+					..Default::default()
+				},
+				..Default::default()
+			};
+			for ImportRow { row, entry } in rows {
+				match entry {
+					Ok(entry) => importer.import_row(ctx, &mm, row, entry).await?,
+					Err(cause) => {
+						importer.report.errors.push(ImportRowError { row, cause })
+					}
+				}
+			}
+
+			Ok(importer.report)
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error, or for a dry run)
+		match res {
+			Ok(report) if options.dry_run => {
+				mm.rollback_txn().await?;
+				Ok(report)
+			}
+			res => mm.end_txn(res).await,
+		}
+	}
+}
+
+#[derive(Default)]
+struct Importer {
+	report: ImportReport,
+	/// project name -> project id
+	projects: HashMap<String, i64>,
+	/// (project id, task title) -> task id
+	tasks: HashMap<(i64, String), i64>,
+	/// (task id, start, stop) of the entries of this import
+	tasktimes: HashSet<(i64, OffsetDateTime, OffsetDateTime)>,
+}
+
+impl Importer {
+	/// Imports the entry in a nested transaction, reporting its error
+	/// (after the rollback of its writes) as the `ImportRowError` of the row.
+	async fn import_row(
+		&mut self,
+		ctx: &Ctx,
+		mm: &ModelManager,
+		row: usize,
+		entry: ImportEntry,
+	) -> Result<()> {
+		// (restored on error, as the projects and tasks created are rolled back)
+		let projects = self.projects.clone();
+		let tasks = self.tasks.clone();
+		let created = (self.report.projects_created, self.report.tasks_created);
+
+		mm.begin_txn().await?;
+		let res = self.import_entry(ctx, mm, row, entry).await;
+		if let Err(err) = mm.end_txn(res).await {
+			self.projects = projects;
+			self.tasks = tasks;
+			(self.report.projects_created, self.report.tasks_created) = created;
+			self.report.errors.push(ImportRowError {
+				row,
+				cause: err.to_string(),
+			});
+		}
+
+		Ok(())
+	}
+
+	async fn import_entry(
+		&mut self,
+		ctx: &Ctx,
+		mm: &ModelManager,
+		row: usize,
+		entry: ImportEntry,
+	) -> Result<()> {
+		let ImportEntry {
+			start_time,
+			stop_time,
+			..
+		} = entry;
+		if stop_time < start_time {
+			self.report.errors.push(ImportRowError {
+				row,
+				cause: "end is before start".to_string(),
+			});
+			return Ok(());
+		}
+
+		let project = entry.project_name();
+		let (task, comment) = entry.task_title_and_comment();
+		let project_id = self.project_id(ctx, mm, &project).await?;
+		let task_id = self.task_id(ctx, mm, project_id, &task).await?;
+
+		// -- Dedupe, then create the tasktime
+		let status = if self.tasktime_exists(ctx, mm, task_id, &entry).await? {
+			self.report.duplicates += 1;
+			ImportItemStatus::Duplicate
+		} else {
+			TaskTimeBmc::create(
+				ctx,
+				mm,
+				TaskTimeForCreate {
+					task_id,
+					comment: comment.clone(),
+					start_time: Some(start_time),
+					stop_time: Some(stop_time),
+					billable: None,
+				},
+			)
+			.await?;
+			self.tasktimes.insert((task_id, start_time, stop_time));
+			self.report.tasktimes_created += 1;
+			ImportItemStatus::Created
+		};
+
+		if self.report.dry_run {
+			self.report.items.push(ImportItem {
+				row,
+				project,
+				task,
+				comment,
+				start_time,
+				stop_time,
+				status,
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Returns the id of the ctx user project named `name`, creating it if needed.
+	async fn project_id(
+		&mut self,
+		ctx: &Ctx,
+		mm: &ModelManager,
+		name: &str,
+	) -> Result<i64> {
+		if let Some(id) = self.projects.get(name) {
+			return Ok(*id);
+		}
+
+		let filter: ProjectFilter = serde_json::from_value(json!({
+			"name": name,
+			"cid": ctx.user_id(),
+		}))?;
+		let existing =
+			ProjectBmc::list(ctx, mm, Some(vec![filter]), Some(first_only()))
+				.await?;
+		let id = match existing.first() {
+			Some(project) => project.id,
+			None => {
+				self.report.projects_created += 1;
+				let project_c = ProjectForCreate {
+					name: name.to_string(),
+					client_id: None,
+				};
+				ProjectBmc::create(ctx, mm, project_c).await?
+			}
+		};
+
+		self.projects.insert(name.to_string(), id);
+		Ok(id)
+	}
+
+	/// Returns the id of the task titled `title` of the project,
+	/// creating it if needed.
+	async fn task_id(
+		&mut self,
+		ctx: &Ctx,
+		mm: &ModelManager,
+		project_id: i64,
+		title: &str,
+	) -> Result<i64> {
+		let key = (project_id, title.to_string());
+		if let Some(id) = self.tasks.get(&key) {
+			return Ok(*id);
+		}
+
+		let filter: TaskFilter = serde_json::from_value(json!({
+			"project_id": project_id,
+			"title": title,
+		}))?;
+		let existing =
+			TaskBmc::list(ctx, mm, Some(vec![filter]), Some(first_only())).await?;
+		let id = match existing.first() {
+			Some(task) => task.id,
+			None => {
+				self.report.tasks_created += 1;
+				let task_c = TaskForCreate {
+					title: title.to_string(),
+					project_id,
+				};
+				TaskBmc::create(ctx, mm, task_c).await?
+			}
+		};
+
+		self.tasks.insert(key, id);
+		Ok(id)
+	}
+
+	async fn tasktime_exists(
+		&self,
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		entry: &ImportEntry,
+	) -> Result<bool> {
+		let key = (task_id, entry.start_time, entry.stop_time);
+		if self.tasktimes.contains(&key) {
+			return Ok(true);
+		}
+
+		let filter: TaskTimeFilter = serde_json::from_value(json!({
+			"task_id": task_id,
+			"start_time": {"$eq": format_time(entry.start_time)},
+			"stop_time": {"$eq": format_time(entry.stop_time)},
+		}))?;
+		let existing =
+			TaskTimeBmc::list(ctx, mm, Some(vec![filter]), Some(first_only()))
+				.await?;
+
+		Ok(!existing.is_empty())
+	}
+}
+
+fn first_only() -> ListOptions {
+	ListOptions {
+		limit: Some(1),
+		offset: None,
+		order_bys: Some("id".into()),
+	}
+}
+
+// endregion: --- ImportBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::period_lock::{PeriodLockBmc, PeriodLockForCreate};
+	use crate::model::project::Project;
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+	use time::macros::datetime;
+
+	const FX_TOGGL_CSV: &str = "\
+Client,Project,Task,Description,Start date,Start time,End date,End time
+test_import_ok,Website,,Fix header,2024-03-01,09:00:00,2024-03-01,10:30:00
+test_import_ok,Website,,Fix header,2024-03-01,13:00:00,2024-03-01,14:00:00
+test_import_ok,Website,,Deploy,2024-03-01,15:00:00,2024-03-01,14:00:00
+";
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_dry_run_then_reimport_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_name = "test_import_ok / Website";
+
+		// -- Exec
+		let dry_report = ImportBmc::import(
+			&ctx,
+			&mm,
+			ImportFormat::TogglCsv,
+			FX_TOGGL_CSV,
+			ImportOptions {
+				dry_run: true,
+				..Default::default()
+			},
+		)
+		.await?;
+		let report = ImportBmc::import(
+			&ctx,
+			&mm,
+			ImportFormat::TogglCsv,
+			FX_TOGGL_CSV,
+			ImportOptions::default(),
+		)
+		.await?;
+		let re_report = ImportBmc::import(
+			&ctx,
+			&mm,
+			ImportFormat::TogglCsv,
+			FX_TOGGL_CSV,
+			ImportOptions::default(),
+		)
+		.await?;
+
+		// -- Check
+		// dry run
+		assert_eq!(dry_report.rows, 3);
+		assert_eq!(dry_report.tasktimes_created, 2);
+		assert_eq!(dry_report.items.len(), 2);
+		assert_eq!(dry_report.errors.len(), 1);
+		assert_eq!(dry_report.errors[0].row, 4);
+		// import
+		assert_eq!(report.projects_created, 1);
+		assert_eq!(report.tasks_created, 1);
+		assert_eq!(report.tasktimes_created, 2);
+		assert!(report.items.is_empty());
+		// re-import
+		assert_eq!(re_report.projects_created, 0);
+		assert_eq!(re_report.tasktimes_created, 0);
+		assert_eq!(re_report.duplicates, 2);
+		// only one project (the dry run being rolled back)
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"name": fx_project_name}))?;
+		let projects: Vec<Project> =
+			ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(projects.len(), 1);
+
+		// -- Clean
+		let project = projects.first().context("Should have the project")?;
+		ProjectBmc::delete(&ctx, &mm, project.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_import_row_err_period_locked() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_csv = "\
+Client,Project,Task,Description,Start date,Start time,End date,End time
+test_import_locked,Locked,,Fix header,2034-03-01,09:00:00,2034-03-01,10:30:00
+test_import_locked,Open,,Fix header,2034-04-01,09:00:00,2034-04-01,10:30:00
+";
+		let fx_lock_id = PeriodLockBmc::create(
+			&ctx,
+			&mm,
+			PeriodLockForCreate {
+				user_id: None,
+				all_users: false,
+				start_time: datetime!(2034-03-01 00:00 UTC),
+				stop_time: datetime!(2034-04-01 00:00 UTC),
+				comment: "March submitted".to_string(),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let report = ImportBmc::import(
+			&ctx,
+			&mm,
+			ImportFormat::TogglCsv,
+			fx_csv,
+			ImportOptions::default(),
+		)
+		.await?;
+
+		// -- Check
+		assert_eq!(report.rows, 2);
+		assert_eq!(report.projects_created, 1);
+		assert_eq!(report.tasks_created, 1);
+		assert_eq!(report.tasktimes_created, 1);
+		assert_eq!(report.errors.len(), 1);
+		assert_eq!(report.errors[0].row, 2);
+		assert!(
+			report.errors[0].cause.contains("PeriodLocked"),
+			"cause: {}",
+			report.errors[0].cause
+		);
+		// (the project of the locked row is rolled back)
+		let filter: ProjectFilter = serde_json::from_value(
+			json!({"name": {"$startsWith": "test_import_locked / "}}),
+		)?;
+		let projects: Vec<Project> =
+			ProjectBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, ["test_import_locked / Open"]);
+
+		// -- Clean
+		PeriodLockBmc::unlock(&ctx, &mm, fx_lock_id, "clean".to_string()).await?;
+		ProjectBmc::delete(&ctx, &mm, projects[0].id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,112 @@
+//! The json of `timew export`, an array of intervals
+//! (e.g., `{"id": 1, "start": "20240301T080000Z", "end": "20240301T093000Z",
+//! "tags": ["Website", "Fix header"], "annotation": "..."}`).
+//!
+//! - The first tag is the project, and the other tags the task
+//!   (or else, the annotation).
+//! - An interval without `end` (i.e., still running) is a row error.
+
+use crate::model::import::{ImportEntry, ImportRow};
+use crate::model::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+const TIMEW_FORMAT: &[FormatItem<'static>] =
+	format_description!("[year][month][day]T[hour][minute][second]Z");
+
+#[derive(Deserialize)]
+struct TimewInterval {
+	start: String,
+	end: Option<String>,
+	#[serde(default)]
+	tags: Vec<String>,
+	annotation: Option<String>,
+}
+
+pub(super) fn parse(content: &str) -> Result<Vec<ImportRow>> {
+	let intervals: Vec<Value> = serde_json::from_str(content)
+		.map_err(|ex| Error::ImportFileInvalid(format!("timewarrior json: {ex}")))?;
+
+	let rows = intervals
+		.into_iter()
+		.enumerate()
+		.map(|(idx, interval)| ImportRow {
+			row: idx + 1,
+			entry: parse_interval(interval),
+		})
+		.collect();
+
+	Ok(rows)
+}
+
+fn parse_interval(interval: Value) -> core::result::Result<ImportEntry, String> {
+	let TimewInterval {
+		start,
+		end,
+		mut tags,
+		annotation,
+	} = serde_json::from_value(interval).map_err(|ex| ex.to_string())?;
+
+	let end = end.ok_or("interval still running (no end)")?;
+	let annotation = annotation.unwrap_or_default();
+	let project = (!tags.is_empty()).then(|| tags.remove(0));
+	let task = (!tags.is_empty()).then(|| tags.join(" "));
+
+	Ok(ImportEntry {
+		client: None,
+		project,
+		task,
+		description: annotation,
+		start_time: parse_time(&start)?,
+		stop_time: parse_time(&end)?,
+	})
+}
+
+fn parse_time(moment: &str) -> core::result::Result<OffsetDateTime, String> {
+	PrimitiveDateTime::parse(moment, TIMEW_FORMAT)
+		.map(PrimitiveDateTime::assume_utc)
+		.map_err(|_| format!("invalid time '{moment}'"))
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::datetime;
+
+	#[test]
+	fn test_parse_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = r#"[
+			{"id": 2, "start": "20240301T080000Z", "end": "20240301T093000Z",
+			 "tags": ["Website", "Fix", "header"], "annotation": "css only"},
+			{"id": 1, "start": "20240301T100000Z", "tags": ["Website"]}
+		]"#;
+
+		// -- Exec
+		let rows = parse(fx_content)?;
+
+		// -- Check
+		assert_eq!(rows.len(), 2);
+		let entry = rows[0]
+			.entry
+			.as_ref()
+			.map_err(|ex| anyhow::anyhow!("{ex}"))?;
+		assert_eq!(entry.project.as_deref(), Some("Website"));
+		assert_eq!(entry.task.as_deref(), Some("Fix header"));
+		assert_eq!(entry.description, "css only");
+		assert_eq!(entry.start_time, datetime!(2024-03-01 08:00 UTC));
+		assert_eq!(entry.stop_time, datetime!(2024-03-01 09:30 UTC));
+		assert!(
+			rows[1].entry.is_err(),
+			"running interval should be a row error"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
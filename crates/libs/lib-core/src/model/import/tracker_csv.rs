@@ -0,0 +1,243 @@
+//! The csv exports of Toggl ("Detailed report") and Clockify ("Detailed report").
+//!
+//! Both have one time entry per row, with (at least) the columns
+//! "Project", "Description", "Start date", "Start time", "End date",
+//! and "End time" (matched case insensitively), and optionally
+//! "Client" and "Task".
+//!
+//! The dates and times are local (without offset), so they are read
+//! in the `ImportOptions::utc_offset`.
+
+use crate::model::import::{ImportEntry, ImportRow};
+use crate::model::{Error, Result};
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{Date, PrimitiveDateTime, Time, UtcOffset};
+
+/// The date and time formats of a tracker csv, tried in order.
+pub(super) struct CsvLayout {
+	pub name: &'static str,
+	pub date_formats: &'static [&'static [FormatItem<'static>]],
+	pub time_formats: &'static [&'static [FormatItem<'static>]],
+}
+
+pub(super) const TOGGL: CsvLayout = CsvLayout {
+	name: "toggl csv",
+	date_formats: &[format_description!("[year]-[month]-[day]")],
+	time_formats: &[
+		format_description!("[hour]:[minute]:[second]"),
+		format_description!("[hour]:[minute]"),
+	],
+};
+
+/// Note: Clockify formats the dates with the workspace settings,
+///       so "MM/DD/YYYY" (the default) is tried before "DD/MM/YYYY".
+pub(super) const CLOCKIFY: CsvLayout = CsvLayout {
+	name: "clockify csv",
+	date_formats: &[
+		format_description!("[month]/[day]/[year]"),
+		format_description!("[day]/[month]/[year]"),
+		format_description!("[day].[month].[year]"),
+		format_description!("[year]-[month]-[day]"),
+	],
+	time_formats: &[
+		format_description!("[hour repr:12]:[minute]:[second] [period]"),
+		format_description!("[hour repr:12]:[minute] [period]"),
+		format_description!("[hour]:[minute]:[second]"),
+		format_description!("[hour]:[minute]"),
+	],
+};
+
+struct Columns {
+	client: Option<usize>,
+	project: usize,
+	task: Option<usize>,
+	description: usize,
+	start_date: usize,
+	start_time: usize,
+	end_date: usize,
+	end_time: usize,
+}
+
+pub(super) fn parse(
+	content: &str,
+	layout: &CsvLayout,
+	offset: UtcOffset,
+) -> Result<Vec<ImportRow>> {
+	let file_invalid = |cause: String| {
+		Error::ImportFileInvalid(format!("{}: {cause}", layout.name))
+	};
+
+	let mut reader = csv::ReaderBuilder::new()
+		.flexible(true)
+		.from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+	// -- Find the columns
+	let headers = reader
+		.headers()
+		.map_err(|ex| file_invalid(ex.to_string()))?
+		.clone();
+	let find = |name: &str| {
+		headers
+			.iter()
+			.position(|header| header.trim().eq_ignore_ascii_case(name))
+	};
+	let require = |name: &str| {
+		find(name).ok_or_else(|| file_invalid(format!("no '{name}' column")))
+	};
+	let columns = Columns {
+		client: find("client"),
+		project: require("project")?,
+		task: find("task"),
+		description: require("description")?,
+		start_date: require("start date")?,
+		start_time: require("start time")?,
+		end_date: require("end date")?,
+		end_time: require("end time")?,
+	};
+
+	// -- Parse the rows
+	let mut rows = Vec::new();
+	for record in reader.records() {
+		let record = record.map_err(|ex| file_invalid(ex.to_string()))?;
+		// (the header being row 1, as in a spreadsheet)
+		let row = record
+			.position()
+			.map(|pos| pos.line() as usize)
+			.unwrap_or(0);
+
+		let cell = |idx: usize| record.get(idx).unwrap_or_default().trim();
+		let text = |idx: Option<usize>| {
+			idx.map(cell)
+				.filter(|value| !value.is_empty())
+				.map(str::to_string)
+		};
+
+		let start_time = parse_date_time(
+			layout,
+			cell(columns.start_date),
+			cell(columns.start_time),
+			offset,
+		);
+		let stop_time = parse_date_time(
+			layout,
+			cell(columns.end_date),
+			cell(columns.end_time),
+			offset,
+		);
+		let entry = start_time.and_then(|start_time| {
+			Ok(ImportEntry {
+				client: text(columns.client),
+				project: text(Some(columns.project)),
+				task: text(columns.task),
+				description: cell(columns.description).to_string(),
+				start_time,
+				stop_time: stop_time?,
+			})
+		});
+
+		rows.push(ImportRow { row, entry });
+	}
+
+	Ok(rows)
+}
+
+fn parse_date_time(
+	layout: &CsvLayout,
+	date: &str,
+	time: &str,
+	offset: UtcOffset,
+) -> core::result::Result<time::OffsetDateTime, String> {
+	let date = layout
+		.date_formats
+		.iter()
+		.find_map(|format| Date::parse(date, format).ok())
+		.ok_or_else(|| format!("invalid date '{date}'"))?;
+	let time = layout
+		.time_formats
+		.iter()
+		.find_map(|format| Time::parse(time, format).ok())
+		.ok_or_else(|| format!("invalid time '{time}'"))?;
+
+	Ok(PrimitiveDateTime::new(date, time).assume_offset(offset))
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::datetime;
+
+	#[test]
+	fn test_parse_toggl_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = "\
+User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration
+Jen,jen@example.com,ACME,Website,,Fix header,Yes,2024-03-01,09:00:00,2024-03-01,10:30:00,01:30:00
+Jen,jen@example.com,,,,Reading,No,2024-03-01,bad,2024-03-01,12:00:00,01:00:00
+";
+
+		// -- Exec
+		let rows = parse(fx_content, &TOGGL, UtcOffset::from_hms(1, 0, 0)?)?;
+
+		// -- Check
+		assert_eq!(rows.len(), 2);
+		let entry = rows[0]
+			.entry
+			.as_ref()
+			.map_err(|ex| anyhow::anyhow!("{ex}"))?;
+		assert_eq!(rows[0].row, 2);
+		assert_eq!(entry.client.as_deref(), Some("ACME"));
+		assert_eq!(entry.project.as_deref(), Some("Website"));
+		assert_eq!(entry.task, None);
+		assert_eq!(entry.start_time, datetime!(2024-03-01 08:00 UTC));
+		assert_eq!(entry.stop_time, datetime!(2024-03-01 09:30 UTC));
+		assert_eq!(rows[1].row, 3);
+		assert_eq!(
+			rows[1].entry.as_ref().err().map(String::as_str),
+			Some("invalid time 'bad'")
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_clockify_12h_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = "\
+Project,Client,Description,Task,User,Start Date,Start Time,End Date,End Time
+Website,ACME,Fix footer,Frontend,Jen,03/01/2024,11:30:00 PM,03/02/2024,12:15:00 AM
+";
+
+		// -- Exec
+		let rows = parse(fx_content, &CLOCKIFY, UtcOffset::UTC)?;
+
+		// -- Check
+		let entry = rows[0]
+			.entry
+			.as_ref()
+			.map_err(|ex| anyhow::anyhow!("{ex}"))?;
+		assert_eq!(entry.task.as_deref(), Some("Frontend"));
+		assert_eq!(entry.start_time, datetime!(2024-03-01 23:30 UTC));
+		assert_eq!(entry.stop_time, datetime!(2024-03-02 00:15 UTC));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_err_missing_column() -> Result<()> {
+		// -- Exec
+		let res =
+			parse("Project,Description\nWebsite,Fix\n", &TOGGL, UtcOffset::UTC);
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::ImportFileInvalid(_))),
+			"ImportFileInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,893 @@
+//! Invoices of the billable tasktimes of a client, for a period.
+//!
+//! Design:
+//!
+//! - `InvoiceBmc::generate` creates a `draft` invoice of the `billable`
+//!   tasktimes on the client projects starting in the period, with one
+//!   `InvoiceLine` per tasktime, priced at its `Rate` (see `rate::rate_for`).
+//!   The tasktimes already in an issued invoice are skipped.
+//! - The line amount is the rounded duration (per the biller rounding
+//!   policies, see `model::rounding`) times the hourly rate, rounded
+//!   to the nearest cent (half up), and the invoice total is the sum
+//!   of the line amounts.
+//! - `InvoiceBmc::issue` numbers the invoice (sequentially per user,
+//!   from 1), after which it is locked, i.e., can no longer be updated
+//!   nor deleted (see `InvoiceBmc` hooks).
+//!   It locks the invoice client row (`SELECT ... FOR UPDATE`), so the issues
+//!   of a client run one at a time, and checks there that none of the invoice
+//!   tasktimes is already in an issued invoice.
+//! - The lines are a snapshot of the tasktimes (i.e., editing a tasktime
+//!   does not change a draft invoice), and are only written by `generate`.
+//!   A tasktime in an issued invoice can no longer be updated nor deleted
+//!   (see `InvoiceBmc::check_tasktime_not_invoiced`, called by the
+//!   `TaskTimeBmc` hooks).
+//!
+//! Note: The rendering (HTML, CSV) is in `export::invoice`.
+
+use crate::ctx::Ctx;
+use crate::model::archive::{list_by_parents, IN_IDS_MAX};
+use crate::model::base::{self, DbBmc};
+use crate::model::client::ClientBmc;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::{date_to_sea_value, iso_date, time_to_sea_value};
+use crate::model::project::{Project, ProjectBmc, ProjectFilter};
+use crate::model::rate::{rate_for, Rate, RateBmc};
+use crate::model::rounding::{RoundingPolicyBmc, Roundings};
+use crate::model::task::{Task, TaskBmc, TaskFilter};
+use crate::model::tasktime::{TaskTime, TaskTimeBmc, TaskTimeFilter};
+use crate::model::{Error, ModelManager};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use modql::field::Fields;
+use modql::filter::{ListOptions, OpValsInt64, OpValsString};
+use schemars::JsonSchema;
+use sea_query::{Expr, Iden, PostgresQueryBuilder, Query, SelectStatement};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+use time::{Date, Duration, UtcOffset};
+
+// region:    --- Invoice Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceStatus {
+	Draft,
+	Issued,
+}
+
+impl InvoiceStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			InvoiceStatus::Draft => "draft",
+			InvoiceStatus::Issued => "issued",
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Invoice {
+	pub id: i64,
+	pub client_id: i64,
+
+	/// The sequential number, once issued.
+	pub number: Option<i64>,
+	/// The `InvoiceStatus` (e.g., "draft").
+	pub status: String,
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub period_start: Date,
+	/// Inclusive.
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub period_end: Date,
+	/// The client currency, at the generation.
+	pub currency: String,
+	pub total_cents: i64,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub issued_at: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl Invoice {
+	pub fn is_issued(&self) -> bool {
+		self.status == InvoiceStatus::Issued.as_str()
+	}
+}
+
+#[derive(Fields)]
+struct InvoiceForCreate {
+	client_id: i64,
+	status: String,
+	period_start: Date,
+	period_end: Date,
+	currency: String,
+	total_cents: i64,
+}
+
+#[derive(Fields)]
+struct InvoiceForIssue {
+	number: i64,
+	status: String,
+	issued_at: OffsetDateTime,
+}
+
+generate_filter!(
+	InvoiceFilter {
+		client_id: OpValsInt64,
+		number: OpValsInt64,
+		status: OpValsString,
+		currency: OpValsString,
+		total_cents: OpValsInt64,
+	},
+	Times: [issued_at],
+	Dates: [period_start, period_end],
+);
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct InvoiceLine {
+	pub id: i64,
+	pub invoice_id: i64,
+	/// `None` once the tasktime is deleted.
+	pub tasktime_id: Option<i64>,
+	pub project_id: i64,
+	pub task_id: i64,
+	/// The user of the tasktime.
+	pub user_id: i64,
+
+	/// The tasktime start date (in the generation utc offset).
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub date: Date,
+	/// The task title, and the tasktime comment (if any).
+	pub description: String,
+	pub duration_sec: i64,
+	/// The billed duration (see `model::rounding`).
+	pub rounded_sec: i64,
+	pub hourly_rate_cents: i64,
+	pub amount_cents: i64,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct InvoiceLineForCreate {
+	invoice_id: i64,
+	tasktime_id: Option<i64>,
+	project_id: i64,
+	task_id: i64,
+	user_id: i64,
+	date: Date,
+	description: String,
+	duration_sec: i64,
+	rounded_sec: i64,
+	hourly_rate_cents: i64,
+	amount_cents: i64,
+}
+
+generate_filter!(
+	InvoiceLineFilter {
+		invoice_id: OpValsInt64,
+		tasktime_id: OpValsInt64,
+		project_id: OpValsInt64,
+		task_id: OpValsInt64,
+		user_id: OpValsInt64,
+	},
+	Dates: [date],
+);
+
+// endregion: --- Invoice Types
+
+// region:    --- InvoiceBmc
+pub struct InvoiceBmc;
+
+/// Note: An issued invoice is locked, i.e., its update (including issuing it
+///       again) and delete fail with `Error::InvoiceLocked`.
+#[async_trait]
+impl DbBmc for InvoiceBmc {
+	const TABLE: &'static str = "invoice";
+
+	async fn before_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		_fields: &mut Fields,
+	) -> crate::model::Result<()> {
+		check_not_issued(&Self::get(ctx, mm, id).await?)
+	}
+
+	async fn before_delete(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_not_issued(&Self::get(ctx, mm, id).await?)
+	}
+}
+
+generate_common_bmc_fns!(
+	Bmc: InvoiceBmc,
+	Entity: Invoice,
+	ForCreate: InvoiceForCreate,
+	ForUpdate: InvoiceForIssue,
+	Filter: InvoiceFilter,
+	Fns: [get, list, list_stream, delete],
+);
+
+impl InvoiceBmc {
+	/// Creates the `draft` invoice of the billable tasktimes of `client_id`
+	/// starting in `[period_start, period_end]` (dates in `offset`),
+	/// and returns its id.
+	///
+	/// Fails with `Error::InvoiceRateMissing` if a tasktime has no rate.
+	pub async fn generate(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		client_id: i64,
+		period_start: Date,
+		period_end: Date,
+		offset: UtcOffset,
+	) -> crate::model::Result<i64> {
+		if period_end < period_start {
+			return Err(Error::InvoicePeriodInvalid {
+				period_start: iso_date::format(period_start),
+				period_end: iso_date::format(period_end),
+			});
+		}
+		let client = ClientBmc::get(ctx, mm, client_id).await?;
+
+		// -- Load the billable tasktimes of the client projects
+		let filter: ProjectFilter =
+			serde_json::from_value(json!({"client_id": client_id}))?;
+		let projects: Vec<Project> =
+			ProjectBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let project_ids: Vec<i64> = projects.iter().map(|p| p.id).collect();
+		let tasks: Vec<Task> =
+			list_by_parents(&project_ids, "project_id", |filter: TaskFilter| {
+				TaskBmc::list_stream(ctx, mm, Some(vec![filter]), None)
+			})
+			.await?;
+		let tasks: HashMap<i64, Task> =
+			tasks.into_iter().map(|task| (task.id, task)).collect();
+
+		let from = period_start.midnight().assume_offset(offset);
+		let to = (period_end + Duration::days(1))
+			.midnight()
+			.assume_offset(offset);
+		let task_ids: Vec<i64> = tasks.keys().copied().collect();
+		let mut tasktimes: Vec<TaskTime> = Vec::new();
+		for ids in task_ids.chunks(IN_IDS_MAX) {
+			let filter: TaskTimeFilter = serde_json::from_value(json!({
+				"task_id": {"$in": ids},
+				"billable": true,
+				"start_time": {"$gte": format_time(from), "$lt": format_time(to)},
+			}))?;
+			let mut chunk: Vec<TaskTime> =
+				TaskTimeBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+					.try_collect()
+					.await?;
+			tasktimes.append(&mut chunk);
+		}
+		tasktimes.sort_by_key(|tt| (tt.start_time, tt.id));
+
+		// -- Skip the already invoiced ones
+		let invoiced = Self::issued_tasktime_ids(mm, client_id).await?;
+		tasktimes.retain(|tt| !invoiced.contains(&tt.id));
+
+		// -- Price them
+		let rates = RateBmc::list_for_user(ctx, mm).await?;
+		let roundings = RoundingPolicyBmc::roundings_for_user(ctx, mm).await?;
+		let lines = tasktimes
+			.iter()
+			.map(|tt| line_for(tt, &tasks[&tt.task_id], &rates, &roundings, offset))
+			.collect::<crate::model::Result<Vec<_>>>()?;
+		let total_cents = lines.iter().map(|line| line.amount_cents).sum();
+
+		// -- Create the invoice and its lines
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let invoice_c = InvoiceForCreate {
+				client_id,
+				status: InvoiceStatus::Draft.as_str().to_string(),
+				period_start,
+				period_end,
+				currency: client.currency,
+				total_cents,
+			};
+			let id = base::create::<Self, _>(ctx, &mm, invoice_c).await?;
+			let lines_c: Vec<InvoiceLineForCreate> = lines
+				.into_iter()
+				.map(|line| InvoiceLineForCreate {
+					invoice_id: id,
+					..line
+				})
+				.collect();
+			if !lines_c.is_empty() {
+				base::create_many::<InvoiceLineBmc, _>(ctx, &mm, lines_c).await?;
+			}
+
+			Ok(id)
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Issues the draft invoice, with the next number of the ctx user,
+	/// which locks it.
+	///
+	/// Fails with `Error::InvoiceTaskTimeInvoiced` if one of its tasktimes
+	/// was issued in another invoice since its generation.
+	pub async fn issue(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			// -- Lock the invoice, then its client (serializes the client issues)
+			base::lock_for_update::<Self>(&mm, &[id]).await?;
+			let invoice = Self::get(ctx, &mm, id).await?;
+			check_not_issued(&invoice)?;
+			base::lock_for_update::<ClientBmc>(&mm, &[invoice.client_id]).await?;
+
+			// -- Check the tasktimes
+			//    (locked, in id order, see `check_tasktime_not_invoiced`)
+			let mut query = lines_query();
+			query.and_where(
+				Expr::col((InvoiceLineIden::Table, InvoiceLineIden::InvoiceId))
+					.eq(id),
+			);
+			let mut tasktime_ids: Vec<i64> =
+				fetch_tasktime_ids(&mm, query).await?.into_iter().collect();
+			tasktime_ids.sort_unstable();
+			base::lock_for_update::<TaskTimeBmc>(&mm, &tasktime_ids).await?;
+			let invoiced = Self::issued_tasktime_ids(&mm, invoice.client_id).await?;
+			if let Some(tasktime_id) = tasktime_ids
+				.into_iter()
+				.find(|tasktime_id| invoiced.contains(tasktime_id))
+			{
+				return Err(Error::InvoiceTaskTimeInvoiced { tasktime_id });
+			}
+
+			// -- Next number (the unique (cid, number) guards the races)
+			let filter: InvoiceFilter = serde_json::from_value(json!({
+				"cid": ctx.user_id(),
+				"number": {"$gt": 0}, // the issued ones
+			}))?;
+			let list_options = ListOptions {
+				limit: Some(1),
+				order_bys: Some("!number".into()),
+				..Default::default()
+			};
+			let last = Self::list(ctx, &mm, Some(vec![filter]), Some(list_options))
+				.await?
+				.into_iter()
+				.next();
+			let number = last.and_then(|invoice| invoice.number).unwrap_or(0) + 1;
+
+			let invoice_u = InvoiceForIssue {
+				number,
+				status: InvoiceStatus::Issued.as_str().to_string(),
+				issued_at: now_utc(),
+			};
+			base::update::<Self, _>(ctx, &mm, id, invoice_u).await?;
+
+			Ok(())
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Fails with `Error::InvoiceTaskTimeInvoiced` if the tasktime is in
+	/// an issued invoice (i.e., can no longer change).
+	///
+	/// Note: Locks the tasktime row first, so it waits for a concurrent
+	///       `issue` of an invoice with this tasktime (see `issue`).
+	pub(in crate::model) async fn check_tasktime_not_invoiced(
+		mm: &ModelManager,
+		tasktime_id: i64,
+	) -> crate::model::Result<()> {
+		base::lock_for_update::<TaskTimeBmc>(mm, &[tasktime_id]).await?;
+
+		let mut query = issued_lines_query();
+		query.and_where(
+			Expr::col((InvoiceLineIden::Table, InvoiceLineIden::TasktimeId))
+				.eq(tasktime_id),
+		);
+		if !fetch_tasktime_ids(mm, query).await?.is_empty() {
+			return Err(Error::InvoiceTaskTimeInvoiced { tasktime_id });
+		}
+
+		Ok(())
+	}
+
+	/// Returns the ids of the tasktimes in the issued invoices of `client_id`.
+	async fn issued_tasktime_ids(
+		mm: &ModelManager,
+		client_id: i64,
+	) -> crate::model::Result<HashSet<i64>> {
+		let mut query = issued_lines_query();
+		query.and_where(
+			Expr::col((InvoiceIden::Table, InvoiceIden::ClientId)).eq(client_id),
+		);
+
+		fetch_tasktime_ids(mm, query).await
+	}
+}
+
+#[derive(Iden)]
+enum InvoiceIden {
+	#[iden = "invoice"]
+	Table,
+	Id,
+	ClientId,
+	Status,
+}
+
+#[derive(Iden)]
+enum InvoiceLineIden {
+	#[iden = "invoice_line"]
+	Table,
+	InvoiceId,
+	TasktimeId,
+}
+
+/// Returns the query of the tasktime ids of the invoice lines
+/// (to be narrowed with `and_where`).
+fn lines_query() -> SelectStatement {
+	let mut query = Query::select();
+	query
+		.column((InvoiceLineIden::Table, InvoiceLineIden::TasktimeId))
+		.from(InvoiceLineIden::Table)
+		.and_where(
+			Expr::col((InvoiceLineIden::Table, InvoiceLineIden::TasktimeId))
+				.is_not_null(),
+		);
+	query
+}
+
+/// Returns the `lines_query` of the issued invoices.
+fn issued_lines_query() -> SelectStatement {
+	let mut query = lines_query();
+	query
+		.inner_join(
+			InvoiceIden::Table,
+			Expr::col((InvoiceIden::Table, InvoiceIden::Id))
+				.equals((InvoiceLineIden::Table, InvoiceLineIden::InvoiceId)),
+		)
+		.and_where(
+			Expr::col((InvoiceIden::Table, InvoiceIden::Status))
+				.eq(InvoiceStatus::Issued.as_str()),
+		);
+	query
+}
+
+/// Executes a `lines_query` on the `mm` db (or transaction).
+async fn fetch_tasktime_ids(
+	mm: &ModelManager,
+	query: SelectStatement,
+) -> crate::model::Result<HashSet<i64>> {
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+	Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+fn check_not_issued(invoice: &Invoice) -> crate::model::Result<()> {
+	if invoice.is_issued() {
+		return Err(Error::InvoiceLocked { id: invoice.id });
+	}
+
+	Ok(())
+}
+
+/// Returns the (not yet attached) invoice line of the tasktime.
+fn line_for(
+	tasktime: &TaskTime,
+	task: &Task,
+	rates: &[Rate],
+	roundings: &Roundings,
+	offset: UtcOffset,
+) -> crate::model::Result<InvoiceLineForCreate> {
+	let date = tasktime.start_time.to_offset(offset).date();
+	let rate = rate_for(rates, task.project_id, task.id, tasktime.cid, date).ok_or(
+		Error::InvoiceRateMissing {
+			tasktime_id: tasktime.id,
+		},
+	)?;
+	let duration_sec = (tasktime.stop_time - tasktime.start_time).whole_seconds();
+	let rounded_sec = roundings.round(Some(task.project_id), duration_sec);
+	let description = if tasktime.comment.is_empty() {
+		task.title.clone()
+	} else {
+		format!("{}: {}", task.title, tasktime.comment)
+	};
+
+	Ok(InvoiceLineForCreate {
+		invoice_id: 0,
+		tasktime_id: Some(tasktime.id),
+		project_id: task.project_id,
+		task_id: task.id,
+		user_id: tasktime.cid,
+		date,
+		description,
+		duration_sec,
+		rounded_sec,
+		hourly_rate_cents: rate.hourly_rate_cents,
+		amount_cents: amount_cents(rounded_sec, rate.hourly_rate_cents),
+	})
+}
+
+/// Returns the amount of `duration_sec` at `hourly_rate_cents`,
+/// rounded to the nearest cent (half up).
+pub fn amount_cents(duration_sec: i64, hourly_rate_cents: i64) -> i64 {
+	(duration_sec * hourly_rate_cents + 1800).div_euclid(3600)
+}
+// endregion: --- InvoiceBmc
+
+// region:    --- InvoiceLineBmc
+pub struct InvoiceLineBmc;
+
+impl DbBmc for InvoiceLineBmc {
+	const TABLE: &'static str = "invoice_line";
+}
+
+impl InvoiceLineBmc {
+	/// Returns the lines of the invoice, by date (and tasktime start).
+	pub async fn list_for_invoice(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		invoice_id: i64,
+	) -> crate::model::Result<Vec<InvoiceLine>> {
+		let filter: InvoiceLineFilter =
+			serde_json::from_value(json!({"invoice_id": invoice_id}))?;
+		let list_options = ListOptions {
+			order_bys: Some("id".into()),
+			..Default::default()
+		};
+
+		Self::list_stream(ctx, mm, Some(vec![filter]), Some(list_options))?
+			.try_collect()
+			.await
+	}
+
+	fn list_stream(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<InvoiceLineFilter>>,
+		list_options: Option<ListOptions>,
+	) -> crate::model::Result<crate::model::EntityStream<InvoiceLine>> {
+		base::list_stream::<Self, _, _>(ctx, mm, filter, list_options)
+	}
+}
+// endregion: --- InvoiceLineBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::client::ClientForCreate;
+	use crate::model::project::ProjectForUpdate;
+	use crate::model::rate::RateForCreate;
+	use crate::model::rounding::{RoundingMode, RoundingPolicyForCreate};
+	use crate::model::tasktime::{TaskTimeForCreate, TaskTimeForUpdate};
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+	use time::macros::{date, datetime};
+
+	#[test]
+	fn test_amount_cents_half_up() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(amount_cents(3600, 10_000), 10_000);
+		assert_eq!(amount_cents(90 * 60, 10_001), 15_002); // 15001.5
+		assert_eq!(amount_cents(1, 10_000), 3); // 2.78
+		assert_eq!(amount_cents(0, 10_000), 0);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_generate_and_issue_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_client_id = ClientBmc::create(
+			&ctx,
+			&mm,
+			ClientForCreate {
+				name: "test_generate_and_issue_ok client".to_string(),
+				address: None,
+				currency: Some("USD".to_string()),
+			},
+		)
+		.await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_generate_and_issue_ok project",
+		)
+		.await?;
+		ProjectBmc::update(
+			&ctx,
+			&mm,
+			fx_project_id,
+			ProjectForUpdate {
+				name: None,
+				owner_id: None,
+				client_id: Some(fx_client_id),
+			},
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_generate_and_issue_ok task",
+		)
+		.await?;
+		RateBmc::create(
+			&ctx,
+			&mm,
+			RateForCreate {
+				project_id: Some(fx_project_id),
+				task_id: None,
+				user_id: None,
+				hourly_rate_cents: 12_000,
+				valid_from: date!(2024 - 01 - 01),
+			},
+		)
+		.await?;
+		// (the project only, deleted with it)
+		RoundingPolicyBmc::create(
+			&ctx,
+			&mm,
+			RoundingPolicyForCreate {
+				project_id: Some(fx_project_id),
+				mode: RoundingMode::Up,
+				increment_sec: 3600,
+				min_sec: 0,
+			},
+		)
+		.await?;
+		let fx_start = datetime!(2024-03-04 09:00 UTC);
+		// (billable, start offset hours, duration minutes)
+		let fx_tasktimes = [(true, 0, 90), (false, 2, 60), (true, 24 * 40, 60)];
+		for (billable, start_hours, duration_min) in fx_tasktimes {
+			let start_time = fx_start + Duration::hours(start_hours);
+			TaskTimeBmc::create(
+				&ctx,
+				&mm,
+				TaskTimeForCreate {
+					task_id: fx_task_id,
+					comment: "test_generate_and_issue_ok".to_string(),
+					start_time: Some(start_time),
+					stop_time: Some(start_time + Duration::minutes(duration_min)),
+					billable: Some(billable),
+				},
+			)
+			.await?;
+		}
+
+		// -- Exec
+		let id = InvoiceBmc::generate(
+			&ctx,
+			&mm,
+			fx_client_id,
+			date!(2024 - 03 - 01),
+			date!(2024 - 03 - 31),
+			UtcOffset::UTC,
+		)
+		.await?;
+		// (a draft of the same tasktimes, issued after)
+		let dup_id = InvoiceBmc::generate(
+			&ctx,
+			&mm,
+			fx_client_id,
+			date!(2024 - 03 - 01),
+			date!(2024 - 03 - 31),
+			UtcOffset::UTC,
+		)
+		.await?;
+		InvoiceBmc::issue(&ctx, &mm, id).await?;
+		let res_dup = InvoiceBmc::issue(&ctx, &mm, dup_id).await;
+
+		// -- Check
+		let invoice = InvoiceBmc::get(&ctx, &mm, id).await?;
+		assert!(invoice.is_issued());
+		assert!(invoice.number.is_some());
+		assert_eq!(invoice.currency, "USD");
+		// (1h30 rounded up to 2h)
+		assert_eq!(invoice.total_cents, 24_000);
+		let lines = InvoiceLineBmc::list_for_invoice(&ctx, &mm, id).await?;
+		assert_eq!(lines.len(), 1);
+		assert_eq!(lines[0].duration_sec, 90 * 60);
+		assert_eq!(lines[0].rounded_sec, 2 * 3600);
+		assert_eq!(lines[0].date, date!(2024 - 03 - 04));
+		let fx_tasktime_id =
+			lines[0].tasktime_id.context("Should have a tasktime")?;
+		assert!(
+			matches!(
+				res_dup,
+				Err(Error::InvoiceTaskTimeInvoiced { tasktime_id })
+					if tasktime_id == fx_tasktime_id
+			),
+			"InvoiceTaskTimeInvoiced not matching"
+		);
+		assert!(!InvoiceBmc::get(&ctx, &mm, dup_id).await?.is_issued());
+
+		// -- Check - Locked
+		let res = InvoiceBmc::delete(&ctx, &mm, id).await;
+		assert!(
+			matches!(res, Err(Error::InvoiceLocked { .. })),
+			"InvoiceLocked not matching"
+		);
+		let res = InvoiceBmc::issue(&ctx, &mm, id).await;
+		assert!(
+			matches!(res, Err(Error::InvoiceLocked { .. })),
+			"InvoiceLocked not matching"
+		);
+		let res = TaskTimeBmc::update(
+			&ctx,
+			&mm,
+			fx_tasktime_id,
+			TaskTimeForUpdate {
+				comment: Some("changed".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
+		assert!(
+			matches!(res, Err(Error::InvoiceTaskTimeInvoiced { .. })),
+			"InvoiceTaskTimeInvoiced not matching"
+		);
+		let res = TaskTimeBmc::delete(&ctx, &mm, fx_tasktime_id).await;
+		assert!(
+			matches!(res, Err(Error::InvoiceTaskTimeInvoiced { .. })),
+			"InvoiceTaskTimeInvoiced not matching"
+		);
+
+		// -- Check - Next invoice, without the invoiced tasktime
+		let next_id = InvoiceBmc::generate(
+			&ctx,
+			&mm,
+			fx_client_id,
+			date!(2024 - 03 - 01),
+			date!(2024 - 04 - 30),
+			UtcOffset::UTC,
+		)
+		.await?;
+		InvoiceBmc::issue(&ctx, &mm, next_id).await?;
+		let next = InvoiceBmc::get(&ctx, &mm, next_id).await?;
+		assert_eq!(next.number, invoice.number.map(|number| number + 1));
+		assert_eq!(next.total_cents, 12_000);
+
+		// -- Clean
+		//    (the issued invoices are locked, hence kept, with the client,
+		//     and the project of their tasktimes)
+		InvoiceBmc::delete(&ctx, &mm, dup_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_generate_err_rate_missing() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_client_id = ClientBmc::create(
+			&ctx,
+			&mm,
+			ClientForCreate {
+				name: "test_generate_err_rate_missing client".to_string(),
+				address: None,
+				currency: None,
+			},
+		)
+		.await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_generate_err_rate_missing project",
+		)
+		.await?;
+		ProjectBmc::update(
+			&ctx,
+			&mm,
+			fx_project_id,
+			ProjectForUpdate {
+				name: None,
+				owner_id: None,
+				client_id: Some(fx_client_id),
+			},
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_generate_err_rate_missing task",
+		)
+		.await?;
+		let fx_start = datetime!(2010-03-04 09:00 UTC);
+		let fx_tasktime_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_generate_err_rate_missing".to_string(),
+				start_time: Some(fx_start),
+				stop_time: Some(fx_start + Duration::hours(1)),
+				billable: None,
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let res = InvoiceBmc::generate(
+			&ctx,
+			&mm,
+			fx_client_id,
+			date!(2010 - 03 - 01),
+			date!(2010 - 03 - 31),
+			UtcOffset::UTC,
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::InvoiceRateMissing { tasktime_id }) if tasktime_id == fx_tasktime_id
+			),
+			"InvoiceRateMissing not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ClientBmc::delete(&ctx, &mm, fx_client_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
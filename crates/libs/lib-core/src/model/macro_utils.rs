@@ -0,0 +1,309 @@
+//! Declarative macros removing the per-entity Bmc boilerplate.
+//!
+//! - `generate_common_bmc_fns!` generates the `DbBmc` impl and the CRUD (and bulk)
+//!   functions of a Bmc, all delegating to `model::base`.
+//! - `generate_filter!` generates the modql filter struct of an entity, with the
+//!   common `id` and timestamp fields added.
+//!
+//! e.g.,
+//!
+//! ```ignore
+//! pub struct TaskBmc;
+//!
+//! generate_common_bmc_fns!(
+//!     Bmc: TaskBmc,
+//!     Table: "task",
+//!     Entity: Task,
+//!     ForCreate: TaskForCreate,
+//!     ForUpdate: TaskForUpdate,
+//!     Filter: TaskFilter,
+//! );
+//! ```
+//!
+//! - `Table` is optional. When omitted, the `DbBmc` impl must be written by hand.
+//! - `Fns` is optional and defaults to all the common functions.
+//!   To override a single operation, list the ones to generate, and implement
+//!   the missing one(s) in a regular `impl TaskBmc { ... }` block.
+//!
+//! ```ignore
+//! generate_common_bmc_fns!(
+//!     Bmc: ProjectBmc,
+//!     Table: "project",
+//!     Entity: Project,
+//!     ForCreate: ProjectForCreate,
+//!     ForUpdate: ProjectForUpdate,
+//!     Filter: ProjectFilter,
+//!     Fns: [
+//!         get, list, list_stream, update, delete,
+//!         get_many, update_many, delete_many,
+//!     ],
+//! );
+//! ```
+
+macro_rules! generate_common_bmc_fns {
+	// -- Without Fns, generate all common functions.
+	(
+		Bmc: $bmc:ident,
+		$(Table: $table:literal,)?
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Filter: $filter:ty $(,)?
+	) => {
+		$crate::model::macro_utils::generate_common_bmc_fns!(
+			Bmc: $bmc,
+			$(Table: $table,)?
+			Entity: $entity,
+			ForCreate: $for_create,
+			ForUpdate: $for_update,
+			Filter: $filter,
+			Fns: [
+				create, get, list, list_stream, update, delete,
+				create_many, get_many, update_many, delete_many
+			],
+		);
+	};
+
+	// -- With the explicit list of functions to generate.
+	(
+		Bmc: $bmc:ident,
+		$(Table: $table:literal,)?
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Filter: $filter:ty,
+		Fns: [$($fn_name:ident),* $(,)?] $(,)?
+	) => {
+		$(
+			impl $crate::model::base::DbBmc for $bmc {
+				const TABLE: &'static str = $table;
+			}
+		)?
+
+		$(
+			$crate::model::macro_utils::generate_common_bmc_fns!(
+				@fn $fn_name, $bmc, $entity, $for_create, $for_update, $filter
+			);
+		)*
+	};
+
+	// -- Single function generators.
+	(@fn create, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn create(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				entity_c: $for_create,
+			) -> $crate::model::Result<i64> {
+				$crate::model::base::create::<Self, _>(ctx, mm, entity_c).await
+			}
+		}
+	};
+
+	(@fn get, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn get(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				id: i64,
+			) -> $crate::model::Result<$entity> {
+				$crate::model::base::get::<Self, _>(ctx, mm, id).await
+			}
+		}
+	};
+
+	(@fn list, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn list(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				filter: Option<Vec<$filter>>,
+				list_options: Option<modql::filter::ListOptions>,
+			) -> $crate::model::Result<Vec<$entity>> {
+				$crate::model::base::list::<Self, _, _>(ctx, mm, filter, list_options)
+					.await
+			}
+		}
+	};
+
+	(@fn list_stream, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub fn list_stream(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				filter: Option<Vec<$filter>>,
+				list_options: Option<modql::filter::ListOptions>,
+			) -> $crate::model::Result<$crate::model::EntityStream<$entity>> {
+				$crate::model::base::list_stream::<Self, _, _>(
+					ctx,
+					mm,
+					filter,
+					list_options,
+				)
+			}
+		}
+	};
+
+	(@fn update, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn update(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				id: i64,
+				entity_u: $for_update,
+			) -> $crate::model::Result<()> {
+				$crate::model::base::update::<Self, _>(ctx, mm, id, entity_u).await
+			}
+		}
+	};
+
+	(@fn delete, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn delete(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				id: i64,
+			) -> $crate::model::Result<()> {
+				$crate::model::base::delete::<Self>(ctx, mm, id).await
+			}
+		}
+	};
+
+	(@fn create_many, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn create_many(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				entities_c: Vec<$for_create>,
+			) -> $crate::model::Result<Vec<i64>> {
+				$crate::model::base::create_many::<Self, _>(ctx, mm, entities_c).await
+			}
+		}
+	};
+
+	(@fn get_many, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn get_many(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				ids: &[i64],
+			) -> $crate::model::Result<Vec<$entity>> {
+				$crate::model::base::get_many::<Self, _>(ctx, mm, ids).await
+			}
+		}
+	};
+
+	(@fn update_many, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn update_many(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				entities_u: Vec<(i64, $for_update)>,
+			) -> $crate::model::Result<()> {
+				$crate::model::base::update_many::<Self, _>(ctx, mm, entities_u).await
+			}
+		}
+	};
+
+	(@fn delete_many, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty) => {
+		impl $bmc {
+			pub async fn delete_many(
+				ctx: &$crate::ctx::Ctx,
+				mm: &$crate::model::ModelManager,
+				ids: &[i64],
+			) -> $crate::model::Result<()> {
+				$crate::model::base::delete_many::<Self>(ctx, mm, ids).await
+			}
+		}
+	};
+}
+pub(crate) use generate_common_bmc_fns;
+
+/// Generates the modql filter struct for an entity.
+///
+/// The `id`, `cid`, `ctime`, `mid`, `mtime` filter nodes are always added.
+/// `Times` lists the extra `OffsetDateTime` properties (filtered as Rfc3339
+/// strings), and `Dates` the `Date` ones (filtered as "YYYY-MM-DD" strings).
+///
+/// The `schemars::JsonSchema` implementation, describing the modql operators
+/// of each property, is generated as well (see `modql_utils::OpValsSchema`).
+///
+/// ```ignore
+/// generate_filter!(
+///     TaskTimeFilter {
+///         task_id: OpValsInt64,
+///         comment: OpValsString,
+///     },
+///     Times: [start_time, stop_time],
+/// );
+/// ```
+///
+/// Note: The calling module must have `time_to_sea_value` in scope
+///       (i.e., `use crate::model::modql_utils::time_to_sea_value;`),
+///       and `date_to_sea_value` when `Dates` are given.
+macro_rules! generate_filter {
+	(
+		$filter:ident {
+			$($field:ident: $op_vals:ty),* $(,)?
+		}
+		$(, Times: [$($time_field:ident),* $(,)?])?
+		$(, Dates: [$($date_field:ident),* $(,)?])?
+		$(,)?
+	) => {
+		#[derive(modql::filter::FilterNodes, serde::Deserialize, Default, Debug)]
+		pub struct $filter {
+			id: Option<modql::filter::OpValsInt64>,
+			$($field: Option<$op_vals>,)*
+			$($(
+				#[modql(to_sea_value_fn = "time_to_sea_value")]
+				$time_field: Option<modql::filter::OpValsValue>,
+			)*)?
+			$($(
+				#[modql(to_sea_value_fn = "date_to_sea_value")]
+				$date_field: Option<modql::filter::OpValsValue>,
+			)*)?
+
+			cid: Option<modql::filter::OpValsInt64>,
+			#[modql(to_sea_value_fn = "time_to_sea_value")]
+			ctime: Option<modql::filter::OpValsValue>,
+			mid: Option<modql::filter::OpValsInt64>,
+			#[modql(to_sea_value_fn = "time_to_sea_value")]
+			mtime: Option<modql::filter::OpValsValue>,
+		}
+
+		impl schemars::JsonSchema for $filter {
+			fn schema_name() -> String {
+				stringify!($filter).to_string()
+			}
+
+			fn json_schema(
+				_gen: &mut schemars::gen::SchemaGenerator,
+			) -> schemars::schema::Schema {
+				#[allow(unused_imports)]
+				use $crate::model::modql_utils::{
+					date_op_vals_schema, filter_schema, OpValsSchema,
+				};
+				use modql::filter::{OpValsInt64, OpValsValue};
+
+				filter_schema(
+					stringify!($filter),
+					vec![
+						("id", OpValsInt64::op_vals_schema()),
+						$((stringify!($field), <$op_vals>::op_vals_schema()),)*
+						$($(
+							(stringify!($time_field), OpValsValue::op_vals_schema()),
+						)*)?
+						$($(
+							(stringify!($date_field), date_op_vals_schema()),
+						)*)?
+						("cid", OpValsInt64::op_vals_schema()),
+						("ctime", OpValsValue::op_vals_schema()),
+						("mid", OpValsInt64::op_vals_schema()),
+						("mtime", OpValsValue::op_vals_schema()),
+					],
+				)
+			}
+		}
+	};
+}
+pub(crate) use generate_filter;
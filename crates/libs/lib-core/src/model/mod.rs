@@ -19,39 +19,140 @@
 
 // region:    --- Modules
 
+pub mod absence;
+pub mod anomaly;
+pub mod archive;
 mod base;
+pub mod client;
 mod error;
+pub mod event;
+pub mod holiday;
+pub mod import;
+pub mod invoice;
+mod macro_utils;
 pub mod modql_utils;
+pub mod period_lock;
+pub mod place;
 pub mod project;
+pub mod rate;
+pub mod rounding;
 mod store;
+pub mod sync;
 pub mod task;
 pub mod taskprogress;
 pub mod tasktime;
 pub mod timerecord;
+pub mod timesheet;
+pub mod travel;
 pub mod user;
+pub mod work_schedule;
 
+pub use self::base::EntityStream;
 pub use self::error::{Error, Result};
 
-use crate::model::store::{new_db_pool, Db};
+use crate::model::event::{EventBus, ModelEvent};
+use crate::model::store::{new_db_pool, Dbx};
+use tokio::sync::broadcast;
 
 // endregion: --- Modules
 
 #[derive(Clone)]
 pub struct ModelManager {
-	db: Db,
+	dbx: Dbx,
+	events: EventBus,
 }
 
 impl ModelManager {
 	/// Constructor
 	pub async fn new() -> Result<Self> {
-		let db = new_db_pool().await?;
+		let db_pool = new_db_pool().await?;
+		let dbx = Dbx::new(db_pool, false);
 
-		Ok(ModelManager { db })
+		Ok(ModelManager {
+			dbx,
+			events: EventBus::new(),
+		})
 	}
 
-	/// Returns the sqlx db pool reference.
+	/// Returns a `ModelManager` executing on a shared transaction
+	/// (see `store::dbx`), sharing the same db pool and event channel.
+	///
+	/// If `self` is already in transaction mode, returns a clone of it,
+	/// so that nested Bmc calls join the open transaction.
+	pub fn new_with_txn(&self) -> ModelManager {
+		if self.dbx.with_txn() {
+			return self.clone();
+		}
+
+		ModelManager {
+			dbx: Dbx::new(self.dbx.db().clone(), true),
+			events: self.events.new_with_txn(),
+		}
+	}
+
+	/// Subscribes to the `ModelEvent`s of the committed writes (see `model::event`).
+	pub fn subscribe_events(&self) -> broadcast::Receiver<ModelEvent> {
+		self.events.subscribe()
+	}
+
+	/// Returns the db executor reference.
+	/// (Only for the model layer)
+	pub(in crate::model) fn dbx(&self) -> &Dbx {
+		&self.dbx
+	}
+
+	/// Emits the event, or holds it until the commit when in a transaction.
+	/// (Only for the model layer)
+	pub(in crate::model) fn emit_event(&self, event: ModelEvent) {
+		self.events.emit(event);
+	}
+
+	/// Begins a transaction level (nested if one is already open).
+	/// (Only for the model layer)
+	pub(in crate::model) async fn begin_txn(&self) -> Result<()> {
+		self.dbx.begin_txn().await?;
+		self.events.begin_level();
+
+		Ok(())
+	}
+
+	/// Commits the transaction level, and sends the pending events
+	/// once the outermost transaction is committed.
+	/// (Only for the model layer)
+	pub(in crate::model) async fn commit_txn(&self) -> Result<()> {
+		self.dbx.commit_txn().await?;
+		self.events.commit_level();
+
+		if !self.dbx.is_txn_open().await {
+			self.events.flush();
+		}
+
+		Ok(())
+	}
+
+	/// Rolls back the transaction level, dropping its pending events.
+	/// (Only for the model layer)
+	pub(in crate::model) async fn rollback_txn(&self) -> Result<()> {
+		self.events.rollback_level();
+		self.dbx.rollback_txn().await?;
+
+		Ok(())
+	}
+
+	/// Ends the transaction level opened by `begin_txn`, i.e., commits it
+	/// if `res` is ok, and otherwise rolls it back and returns the error.
 	/// (Only for the model layer)
-	pub(in crate::model) fn db(&self) -> &Db {
-		&self.db
+	pub(in crate::model) async fn end_txn<T>(&self, res: Result<T>) -> Result<T> {
+		match res {
+			Ok(value) => {
+				self.commit_txn().await?;
+				Ok(value)
+			}
+			Err(err) => {
+				// Note: The operation error prevails over a rollback one.
+				let _ = self.rollback_txn().await;
+				Err(err)
+			}
+		}
 	}
 }
@@ -1,3 +1,8 @@
+use modql::filter::{
+	OpValsBool, OpValsFloat64, OpValsInt32, OpValsInt64, OpValsString, OpValsValue,
+};
+use schemars::schema::{Schema, SchemaObject};
+use serde_json::{json, Map, Value};
 use time::serde::rfc3339;
 
 pub fn time_to_sea_value(
@@ -5,3 +10,201 @@ pub fn time_to_sea_value(
 ) -> modql::filter::SeaResult<sea_query::Value> {
 	Ok(rfc3339::deserialize(json_value)?.into())
 }
+
+pub fn date_to_sea_value(
+	json_value: serde_json::Value,
+) -> modql::filter::SeaResult<sea_query::Value> {
+	Ok(iso_date::deserialize(json_value)?.into())
+}
+
+// region:    --- Date Serde
+
+/// Serde of the `Date` properties as "YYYY-MM-DD" strings
+/// (e.g., `#[serde(with = "iso_date")]`, or `"iso_date::option"`).
+pub mod iso_date {
+	use serde::de::Error as _;
+	use serde::{Deserialize, Deserializer, Serializer};
+	use time::format_description::FormatItem;
+	use time::macros::format_description;
+	use time::Date;
+
+	const DATE_FORMAT: &[FormatItem<'static>] =
+		format_description!("[year]-[month]-[day]");
+
+	/// Returns the "YYYY-MM-DD" of the `date` (e.g., for the filters).
+	pub fn format(date: Date) -> String {
+		// Note: Cannot fail, since the format has only date components.
+		date.format(DATE_FORMAT).unwrap_or_default()
+	}
+
+	pub fn serialize<S: Serializer>(
+		date: &Date,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format(*date))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Date, D::Error> {
+		let date = String::deserialize(deserializer)?;
+		Date::parse(&date, DATE_FORMAT).map_err(|_| {
+			D::Error::custom(format!("invalid date '{date}' (expected YYYY-MM-DD)"))
+		})
+	}
+
+	pub mod option {
+		use serde::{Deserialize, Deserializer, Serializer};
+		use time::Date;
+
+		pub fn serialize<S: Serializer>(
+			date: &Option<Date>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			match date {
+				Some(date) => super::serialize(date, serializer),
+				None => serializer.serialize_none(),
+			}
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<Date>, D::Error> {
+			#[derive(Deserialize)]
+			struct Wrapper(#[serde(with = "super")] Date);
+
+			let date = Option::<Wrapper>::deserialize(deserializer)?;
+			Ok(date.map(|Wrapper(date)| date))
+		}
+	}
+}
+
+// endregion: --- Date Serde
+
+// region:    --- Filter Schemas
+
+const OPS_EQ: &[&str] = &["$eq", "$not"];
+const OPS_ORD: &[&str] = &["$eq", "$not", "$lt", "$lte", "$gt", "$gte"];
+const OPS_STRING: &[&str] = &[
+	"$eq",
+	"$not",
+	"$lt",
+	"$lte",
+	"$gt",
+	"$gte",
+	"$contains",
+	"$notContains",
+	"$startsWith",
+	"$notStartsWith",
+	"$endsWith",
+	"$notEndsWith",
+];
+const OPS_LIST: &[&str] = &["$in", "$notIn"];
+const OPS_STRING_LIST: &[&str] = &[
+	"$in",
+	"$notIn",
+	"$containsAny",
+	"$containsAll",
+	"$startsWithAny",
+	"$endsWithAny",
+];
+
+/// The JSON Schema of a modql `OpVals...` filter property,
+/// which is either the value (for `$eq`), or an object of operators.
+/// (used by the `generate_filter!` `JsonSchema` implementation)
+pub trait OpValsSchema {
+	fn op_vals_schema() -> Schema;
+}
+
+impl OpValsSchema for OpValsString {
+	fn op_vals_schema() -> Schema {
+		op_vals_schema(json!({"type": "string"}), OPS_STRING, OPS_STRING_LIST)
+	}
+}
+
+impl OpValsSchema for OpValsInt64 {
+	fn op_vals_schema() -> Schema {
+		op_vals_schema(json!({"type": "integer"}), OPS_ORD, OPS_LIST)
+	}
+}
+
+impl OpValsSchema for OpValsInt32 {
+	fn op_vals_schema() -> Schema {
+		op_vals_schema(json!({"type": "integer"}), OPS_ORD, OPS_LIST)
+	}
+}
+
+impl OpValsSchema for OpValsFloat64 {
+	fn op_vals_schema() -> Schema {
+		op_vals_schema(json!({"type": "number"}), OPS_ORD, OPS_LIST)
+	}
+}
+
+impl OpValsSchema for OpValsBool {
+	fn op_vals_schema() -> Schema {
+		op_vals_schema(json!({"type": "boolean"}), OPS_EQ, &[])
+	}
+}
+
+/// Note: In this model, `OpValsValue` is only used for the time properties
+///       (see `time_to_sea_value`), and the date ones (see `date_op_vals_schema`).
+impl OpValsSchema for OpValsValue {
+	fn op_vals_schema() -> Schema {
+		let value_schema = json!({"type": "string", "format": "date-time"});
+		op_vals_schema(value_schema, OPS_ORD, OPS_LIST)
+	}
+}
+
+/// The schema of a `Date` property filter (see `date_to_sea_value`).
+pub fn date_op_vals_schema() -> Schema {
+	let value_schema = json!({"type": "string", "format": "date"});
+	op_vals_schema(value_schema, OPS_ORD, OPS_LIST)
+}
+
+fn op_vals_schema(value_schema: Value, ops: &[&str], list_ops: &[&str]) -> Schema {
+	let mut op_properties = Map::new();
+	for op in ops {
+		op_properties.insert(op.to_string(), value_schema.clone());
+	}
+	for op in list_ops {
+		let list_schema = json!({"type": "array", "items": value_schema});
+		op_properties.insert(op.to_string(), list_schema);
+	}
+	op_properties.insert("$null".to_string(), json!({"type": "boolean"}));
+
+	let schema = json!({
+		"anyOf": [
+			value_schema,
+			{
+				"type": "object",
+				"properties": op_properties,
+				"additionalProperties": false,
+			}
+		]
+	});
+
+	// Note: Cannot fail, since the json above is a valid schema.
+	Schema::Object(
+		serde_json::from_value::<SchemaObject>(schema).unwrap_or_default(),
+	)
+}
+
+/// The JSON Schema object of a filter, from its `(property_name, op_vals_schema)`.
+pub fn filter_schema(name: &str, properties: Vec<(&str, Schema)>) -> Schema {
+	let properties: Map<String, Value> = properties
+		.into_iter()
+		.map(|(prop, schema)| (prop.to_string(), json!(schema)))
+		.collect();
+
+	let schema = json!({
+		"title": name,
+		"type": "object",
+		"properties": properties,
+	});
+
+	Schema::Object(
+		serde_json::from_value::<SchemaObject>(schema).unwrap_or_default(),
+	)
+}
+
+// endregion: --- Filter Schemas
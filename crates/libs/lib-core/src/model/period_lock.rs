@@ -0,0 +1,547 @@
+//! Period locks, i.e., the closed periods (e.g., a submitted month) in which
+//! the time entries (`TaskTime`s and `TimeRecord`s) can no longer change.
+//!
+//! Design:
+//!
+//! - A lock covers the `[start_time, stop_time)` of a user, or of all the
+//!   users (`user_id: None`).
+//! - Creating, updating or deleting an entry overlapping an active lock of
+//!   its user (i.e., its `cid`) fails with `Error::PeriodLocked`
+//!   (see `PeriodLockBmc::check_unlocked`, called by the entity hooks).
+//!   An update is checked for both the previous and the new times.
+//!   Deleting a task or a project deletes its entries through `TaskTimeBmc`
+//!   (see `base::delete_children`), so it fails the same way.
+//! - A user locks their own periods, and an admin (see `UserBmc::is_admin`)
+//!   the ones of any user, or of all the users.
+//! - Only an admin unlocks, recording who, when, and why on the lock.
+//!   The locks are never deleted, and are hence the audit trail.
+
+use crate::ctx::Ctx;
+use crate::model::base;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::user::UserBmc;
+use crate::model::{Error, ModelManager};
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use modql::field::Fields;
+use modql::filter::{OpValsInt64, OpValsString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- PeriodLock Types
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct PeriodLock {
+	pub id: i64,
+	/// `None` for all the users.
+	pub user_id: Option<i64>,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+	pub comment: String,
+
+	// -- Unlock
+	//    (the admin user_id, time, and reason)
+	pub unlocked_by: Option<i64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub unlocked_at: Option<OffsetDateTime>,
+	pub unlock_reason: Option<String>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl PeriodLock {
+	pub fn is_active(&self) -> bool {
+		self.unlocked_at.is_none()
+	}
+
+	/// Returns whether the `[start_time, stop_time]` of an entry overlaps
+	/// the lock (an instant entry too, if at or after the lock start).
+	pub fn overlaps(
+		&self,
+		start_time: OffsetDateTime,
+		stop_time: OffsetDateTime,
+	) -> bool {
+		start_time < self.stop_time
+			&& (self.start_time < stop_time || self.start_time <= start_time)
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct PeriodLockForCreate {
+	/// The locked user, defaults to the ctx user
+	/// (another user being for admins only).
+	#[serde(default)]
+	pub user_id: Option<i64>,
+	/// Locks all the users (admins only).
+	#[serde(default)]
+	pub all_users: bool,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+	#[serde(default)]
+	pub comment: String,
+}
+
+/// The `PeriodLockForCreate` with the user resolved.
+#[derive(Fields)]
+struct PeriodLockForCreateInner {
+	user_id: Option<i64>,
+	start_time: OffsetDateTime,
+	stop_time: OffsetDateTime,
+	comment: String,
+}
+
+#[derive(Fields)]
+struct PeriodLockForUnlock {
+	unlocked_by: i64,
+	unlocked_at: OffsetDateTime,
+	unlock_reason: String,
+}
+
+generate_filter!(
+	PeriodLockFilter {
+		user_id: OpValsInt64,
+		comment: OpValsString,
+		unlocked_by: OpValsInt64,
+	},
+	Times: [start_time, stop_time, unlocked_at],
+);
+// endregion: --- PeriodLock Types
+
+// region:    --- PeriodLockBmc
+pub struct PeriodLockBmc;
+
+generate_common_bmc_fns!(
+	Bmc: PeriodLockBmc,
+	Table: "period_lock",
+	Entity: PeriodLock,
+	ForCreate: PeriodLockForCreate,
+	ForUpdate: PeriodLockForUnlock,
+	Filter: PeriodLockFilter,
+	Fns: [get, list, list_stream],
+);
+
+// Note: The locks are only created by `create` (or `lock`), only updated
+//       by `unlock`, and never deleted (see module doc).
+impl PeriodLockBmc {
+	/// Locks the period of the ctx user, or, for an admin, of another user
+	/// or of all the users.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		lock_c: PeriodLockForCreate,
+	) -> crate::model::Result<i64> {
+		let PeriodLockForCreate {
+			user_id,
+			all_users,
+			start_time,
+			stop_time,
+			comment,
+		} = lock_c;
+
+		let user_id = if all_users {
+			None
+		} else {
+			Some(user_id.unwrap_or(ctx.user_id()))
+		};
+		if user_id != Some(ctx.user_id()) {
+			UserBmc::check_admin(ctx, mm).await?;
+		}
+
+		Self::lock(ctx, mm, user_id, start_time, stop_time, comment).await
+	}
+
+	/// Locks the period of `user_id` (`None` for all the users),
+	/// without any admin check (e.g., for an approved timesheet).
+	pub(in crate::model) async fn lock(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		user_id: Option<i64>,
+		start_time: OffsetDateTime,
+		stop_time: OffsetDateTime,
+		comment: String,
+	) -> crate::model::Result<i64> {
+		if stop_time <= start_time {
+			return Err(Error::PeriodLockInvalid {
+				start_time: format_time(start_time),
+				stop_time: format_time(stop_time),
+			});
+		}
+
+		let lock_c = PeriodLockForCreateInner {
+			user_id,
+			start_time,
+			stop_time,
+			comment,
+		};
+		base::create::<Self, _>(ctx, mm, lock_c).await
+	}
+
+	/// Unlocks the lock `id` (admins only), recording the ctx user,
+	/// the time and the `reason`.
+	pub async fn unlock(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		reason: String,
+	) -> crate::model::Result<()> {
+		UserBmc::check_admin(ctx, mm).await?;
+
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let lock = Self::get(ctx, &mm, id).await?;
+			if !lock.is_active() {
+				return Err(Error::PeriodLockNotActive { id });
+			}
+			let lock_u = PeriodLockForUnlock {
+				unlocked_by: ctx.user_id(),
+				unlocked_at: now_utc(),
+				unlock_reason: reason,
+			};
+			base::update::<Self, _>(ctx, &mm, id, lock_u).await
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Fails with `Error::PeriodLocked` if the `[start_time, stop_time]` of an
+	/// entry of `user_id` overlaps an active lock of this user, or of all.
+	pub async fn check_unlocked(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		user_id: i64,
+		start_time: OffsetDateTime,
+		stop_time: OffsetDateTime,
+	) -> crate::model::Result<()> {
+		// -- The active locks around the entry
+		//    (of the user or of all, filtered below, as modql has no `$null`
+		//    for the numbers)
+		// Note: `list` (not `list_stream`), as this runs in the entry
+		//       create/update/delete transaction.
+		let filter: PeriodLockFilter = serde_json::from_value(json!({
+			"unlocked_at": {"$null": true},
+			"start_time": {"$lte": format_time(start_time.max(stop_time))},
+			"stop_time": {"$gt": format_time(start_time)},
+		}))?;
+		let locks: Vec<PeriodLock> = Self::list(ctx, mm, Some(vec![filter]), None)
+			.await?
+			.into_iter()
+			.filter(|lock| lock.user_id.is_none_or(|id| id == user_id))
+			.collect();
+
+		match locks
+			.iter()
+			.find(|lock| lock.overlaps(start_time, stop_time))
+		{
+			Some(lock) => Err(Error::PeriodLocked {
+				lock_id: lock.id,
+				start_time: format_time(lock.start_time),
+				stop_time: format_time(lock.stop_time),
+			}),
+			None => Ok(()),
+		}
+	}
+}
+// endregion: --- PeriodLockBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::task::TaskBmc;
+	use crate::model::tasktime::{
+		TaskTimeBmc, TaskTimeForCreate, TaskTimeForUpdate,
+	};
+	use crate::model::timerecord::{TimeRecordBmc, TimeRecordForCreate};
+	use crate::model::user::User;
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+	use time::macros::datetime;
+	use time::Duration;
+
+	#[test]
+	fn test_overlaps() -> Result<()> {
+		// -- Setup & Fixtures
+		let now = now_utc();
+		let fx_lock = PeriodLock {
+			id: 1000,
+			user_id: None,
+			start_time: datetime!(2024-03-01 00:00 UTC),
+			stop_time: datetime!(2024-04-01 00:00 UTC),
+			comment: String::new(),
+			unlocked_by: None,
+			unlocked_at: None,
+			unlock_reason: None,
+			cid: 0,
+			ctime: now,
+			mid: 0,
+			mtime: now,
+		};
+
+		// -- Exec & Check
+		let overlaps = |start, stop| fx_lock.overlaps(start, stop);
+		assert!(overlaps(
+			datetime!(2024-02-29 23:00 UTC),
+			datetime!(2024-03-01 01:00 UTC)
+		));
+		assert!(overlaps(
+			datetime!(2024-03-01 00:00 UTC),
+			datetime!(2024-03-01 00:00 UTC)
+		));
+		assert!(!overlaps(
+			datetime!(2024-02-29 23:00 UTC),
+			datetime!(2024-03-01 00:00 UTC)
+		));
+		assert!(!overlaps(
+			datetime!(2024-04-01 00:00 UTC),
+			datetime!(2024-04-01 01:00 UTC)
+		));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_lock_rejects_entries_until_unlocked() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_lock_rejects_entries_until_unlocked project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_lock_rejects_entries_until_unlocked task",
+		)
+		.await?;
+		let fx_start = datetime!(2031-03-04 09:00 UTC);
+		let fx_tasktime_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_lock_rejects_entries_until_unlocked".to_string(),
+				start_time: Some(fx_start),
+				stop_time: Some(fx_start + Duration::hours(1)),
+				billable: None,
+			},
+		)
+		.await?;
+		let fx_lock_id = PeriodLockBmc::create(
+			&ctx,
+			&mm,
+			PeriodLockForCreate {
+				user_id: None,
+				all_users: false,
+				start_time: datetime!(2031-03-01 00:00 UTC),
+				stop_time: datetime!(2031-04-01 00:00 UTC),
+				comment: "March submitted".to_string(),
+			},
+		)
+		.await?;
+
+		// -- Exec & Check - Locked
+		let res = TaskTimeBmc::update(
+			&ctx,
+			&mm,
+			fx_tasktime_id,
+			TaskTimeForUpdate {
+				comment: Some("changed".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
+		assert!(
+			matches!(
+				res,
+				Err(Error::PeriodLocked { lock_id, .. }) if lock_id == fx_lock_id
+			),
+			"PeriodLocked not matching"
+		);
+		let res = TaskTimeBmc::delete(&ctx, &mm, fx_tasktime_id).await;
+		assert!(
+			matches!(res, Err(Error::PeriodLocked { .. })),
+			"PeriodLocked not matching"
+		);
+		let res = TimeRecordBmc::create(
+			&ctx,
+			&mm,
+			TimeRecordForCreate {
+				place: "test_lock_rejects_entries_until_unlocked".to_string(),
+				place_id: None,
+				start_time: Some(fx_start),
+				stop_time: Some(fx_start + Duration::hours(8)),
+			},
+		)
+		.await;
+		assert!(
+			matches!(res, Err(Error::PeriodLocked { .. })),
+			"PeriodLocked not matching"
+		);
+
+		// -- Exec & Check - Unlocked
+		PeriodLockBmc::unlock(&ctx, &mm, fx_lock_id, "fix a typo".to_string())
+			.await?;
+		let lock = PeriodLockBmc::get(&ctx, &mm, fx_lock_id).await?;
+		assert!(!lock.is_active());
+		assert_eq!(lock.unlocked_by, Some(ctx.user_id()));
+		assert_eq!(lock.unlock_reason.as_deref(), Some("fix a typo"));
+		TaskTimeBmc::delete(&ctx, &mm, fx_tasktime_id).await?;
+		let res = PeriodLockBmc::unlock(&ctx, &mm, fx_lock_id, String::new()).await;
+		assert!(
+			matches!(res, Err(Error::PeriodLockNotActive { .. })),
+			"PeriodLockNotActive not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_lock_rejects_cascade_delete() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_lock_rejects_cascade_delete project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_lock_rejects_cascade_delete task",
+		)
+		.await?;
+		let fx_start = datetime!(2032-03-04 09:00 UTC);
+		let fx_tasktime_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_lock_rejects_cascade_delete".to_string(),
+				start_time: Some(fx_start),
+				stop_time: Some(fx_start + Duration::hours(1)),
+				billable: None,
+			},
+		)
+		.await?;
+		let fx_lock_id = PeriodLockBmc::create(
+			&ctx,
+			&mm,
+			PeriodLockForCreate {
+				user_id: None,
+				all_users: false,
+				start_time: datetime!(2032-03-01 00:00 UTC),
+				stop_time: datetime!(2032-04-01 00:00 UTC),
+				comment: "March submitted".to_string(),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let res_task = TaskBmc::delete(&ctx, &mm, fx_task_id).await;
+		let res_project = ProjectBmc::delete(&ctx, &mm, fx_project_id).await;
+
+		// -- Check
+		assert!(
+			matches!(res_task, Err(Error::PeriodLocked { .. })),
+			"PeriodLocked not matching"
+		);
+		assert!(
+			matches!(res_project, Err(Error::PeriodLocked { .. })),
+			"PeriodLocked not matching"
+		);
+		TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		TaskTimeBmc::get(&ctx, &mm, fx_tasktime_id).await?;
+
+		// -- Clean
+		PeriodLockBmc::unlock(&ctx, &mm, fx_lock_id, "clean".to_string()).await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_admin_only_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(demo1.id)?;
+		let fx_lock_c = |all_users| PeriodLockForCreate {
+			user_id: None,
+			all_users,
+			start_time: datetime!(2031-05-01 00:00 UTC),
+			stop_time: datetime!(2031-06-01 00:00 UTC),
+			comment: "test_admin_only_err".to_string(),
+		};
+
+		// -- Exec
+		let res_all = PeriodLockBmc::create(&ctx, &mm, fx_lock_c(true)).await;
+		let own_id = PeriodLockBmc::create(&ctx, &mm, fx_lock_c(false)).await?;
+		let res_unlock =
+			PeriodLockBmc::unlock(&ctx, &mm, own_id, "undo".to_string()).await;
+
+		// -- Check
+		assert!(
+			matches!(res_all, Err(Error::AdminOnly)),
+			"AdminOnly not matching"
+		);
+		assert!(
+			matches!(res_unlock, Err(Error::AdminOnly)),
+			"AdminOnly not matching"
+		);
+		let lock = PeriodLockBmc::get(&ctx, &mm, own_id).await?;
+		assert_eq!(lock.user_id, Some(demo1.id));
+
+		// -- Clean
+		PeriodLockBmc::unlock(&root_ctx, &mm, own_id, "test clean".to_string())
+			.await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,289 @@
+//! Places (e.g., "Office", "Home"), referenced by the `TimeRecord`s
+//! (`timerecord.place_id`) and the `Travel`s (from/to place).
+//!
+//! Design:
+//!
+//! - The places are per user (`cid`), and unique per `name_key`, the normalized
+//!   name (see `place_name_key`), so "Office", "office" and "Office "
+//!   are the same place.
+//! - `PlaceBmc::get_or_create_by_name` is how the `TimeRecordBmc` resolves
+//!   the free text `place` of a timerecord (e.g., from the imports).
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::travel::TravelBmc;
+use crate::model::{Error, ModelManager, Result};
+use async_trait::async_trait;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{ListOptions, OpValsString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Place Types
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Place {
+	pub id: i64,
+
+	pub name: String,
+	/// The normalized name (see `place_name_key`).
+	pub name_key: String,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
+pub struct PlaceForCreate {
+	pub name: String,
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
+pub struct PlaceForUpdate {
+	pub name: Option<String>,
+}
+
+/// The `PlaceForCreate` with the `name_key` computed from the name.
+#[derive(Fields)]
+struct PlaceForCreateInner {
+	pub name: String,
+	pub name_key: String,
+}
+
+#[derive(Fields)]
+struct PlaceForUpdateInner {
+	pub name: Option<String>,
+	pub name_key: Option<String>,
+}
+
+generate_filter!(PlaceFilter {
+	name: OpValsString,
+	name_key: OpValsString,
+});
+// endregion: --- Place Types
+
+/// Returns the trimmed `name`, with its inner whitespaces collapsed
+/// (e.g., " Main   office " to "Main office").
+pub fn place_name(name: &str) -> String {
+	name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the key of the place `name`, i.e., its `place_name` lowercased
+/// (e.g., " Main   Office " to "main office").
+pub fn place_name_key(name: &str) -> String {
+	place_name(name).to_lowercase()
+}
+
+// region:    --- PlaceBmc
+pub struct PlaceBmc;
+
+/// Note: The travels from or to the place are deleted through their Bmc
+///       first (see `base::delete_children`).
+#[async_trait]
+impl DbBmc for PlaceBmc {
+	const TABLE: &'static str = "place";
+
+	async fn before_delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::delete_children::<TravelBmc>(ctx, mm, "from_place_id", id).await?;
+		base::delete_children::<TravelBmc>(ctx, mm, "to_place_id", id).await
+	}
+}
+
+generate_common_bmc_fns!(
+	Bmc: PlaceBmc,
+	Entity: Place,
+	ForCreate: PlaceForCreate,
+	ForUpdate: PlaceForUpdate,
+	Filter: PlaceFilter,
+	Fns: [get, list, list_stream, delete, get_many, delete_many],
+);
+
+// Note: `create`, `update` (and their bulk versions) are overridden
+//       to set the `name_key` from the name (see `PlaceForCreateInner`).
+impl PlaceBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		place_c: PlaceForCreate,
+	) -> Result<i64> {
+		base::create::<Self, _>(ctx, mm, place_c.into_inner()?).await
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		places_c: Vec<PlaceForCreate>,
+	) -> Result<Vec<i64>> {
+		let places_c = places_c
+			.into_iter()
+			.map(PlaceForCreate::into_inner)
+			.collect::<Result<Vec<_>>>()?;
+		base::create_many::<Self, _>(ctx, mm, places_c).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		place_u: PlaceForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, place_u.into_inner()?).await
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		places_u: Vec<(i64, PlaceForUpdate)>,
+	) -> Result<()> {
+		let places_u = places_u
+			.into_iter()
+			.map(|(id, place_u)| Ok((id, place_u.into_inner()?)))
+			.collect::<Result<Vec<_>>>()?;
+		base::update_many::<Self, _>(ctx, mm, places_u).await
+	}
+
+	/// Returns the ctx user place named `name` (per `place_name_key`),
+	/// creating it if none.
+	pub async fn get_or_create_by_name(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		name: &str,
+	) -> Result<Place> {
+		let filter: PlaceFilter = serde_json::from_value(json!({
+			"cid": ctx.user_id(),
+			"name_key": place_name_key(name),
+		}))?;
+		let list_options = ListOptions {
+			limit: Some(1),
+			..Default::default()
+		};
+		let place = Self::list(ctx, mm, Some(vec![filter]), Some(list_options))
+			.await?
+			.into_iter()
+			.next();
+
+		match place {
+			Some(place) => Ok(place),
+			None => {
+				let place_c = PlaceForCreate {
+					name: name.to_string(),
+				};
+				let id = Self::create(ctx, mm, place_c).await?;
+				Self::get(ctx, mm, id).await
+			}
+		}
+	}
+}
+
+impl PlaceForCreate {
+	fn into_inner(self) -> Result<PlaceForCreateInner> {
+		let name = place_name(&self.name);
+		if name.is_empty() {
+			return Err(Error::PlaceNameEmpty);
+		}
+
+		Ok(PlaceForCreateInner {
+			name_key: name.to_lowercase(),
+			name,
+		})
+	}
+}
+
+impl PlaceForUpdate {
+	fn into_inner(self) -> Result<PlaceForUpdateInner> {
+		let name = self.name.as_deref().map(place_name);
+		if name.as_deref() == Some("") {
+			return Err(Error::PlaceNameEmpty);
+		}
+
+		Ok(PlaceForUpdateInner {
+			name_key: name.as_deref().map(str::to_lowercase),
+			name,
+		})
+	}
+}
+// endregion: --- PlaceBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[test]
+	fn test_place_name_key_ok() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(place_name(" Main \t office "), "Main office");
+		assert_eq!(place_name_key("Office "), "office");
+		assert_eq!(place_name_key("office"), place_name_key("OFFICE"));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_or_create_by_name_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_name = "test_get_or_create_by_name_ok Office";
+
+		// -- Exec
+		let place = PlaceBmc::get_or_create_by_name(&ctx, &mm, fx_name).await?;
+		let same = PlaceBmc::get_or_create_by_name(
+			&ctx,
+			&mm,
+			" test_get_or_create_by_name_ok   OFFICE ",
+		)
+		.await?;
+
+		// -- Check
+		assert_eq!(same.id, place.id);
+		assert_eq!(same.name, fx_name);
+
+		// -- Clean
+		PlaceBmc::delete(&ctx, &mm, place.id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_name_empty() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+
+		// -- Exec
+		let place_c = PlaceForCreate {
+			name: "  ".to_string(),
+		};
+		let res = PlaceBmc::create(&ctx, &mm, place_c).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::PlaceNameEmpty)),
+			"PlaceNameEmpty not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,22 +1,28 @@
 use crate::ctx::Ctx;
 use crate::model::base::{self, DbBmc};
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::rounding::RoundingPolicyBmc;
+use crate::model::task::TaskBmc;
 use crate::model::ModelManager;
 use crate::model::Result;
+use async_trait::async_trait;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{FilterNodes, OpValsString, OpValsValue};
-use modql::filter::{ListOptions, OpValsInt64};
+use modql::filter::{OpValsInt64, OpValsString};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:    --- Project Types
-#[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
 pub struct Project {
 	pub id: i64,
+	/// The invoiced client (see `model::invoice`).
+	pub client_id: Option<i64>,
 
 	pub owner_id: i64,
 	pub name: String,
@@ -25,21 +31,26 @@ pub struct Project {
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectForCreate {
 	pub name: String,
+	#[serde(default)]
+	pub client_id: Option<i64>,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectForUpdate {
 	pub name: Option<String>,
 	pub owner_id: Option<i64>,
+	pub client_id: Option<i64>,
 }
 
 /// The `ProjectForCreateInner` contains all necessary properties
@@ -54,29 +65,44 @@ pub struct ProjectForUpdate {
 struct ProjectForCreateInner {
 	pub name: String,
 	pub owner_id: i64,
+	pub client_id: Option<i64>,
 }
 
-#[derive(FilterNodes, Default, Deserialize)]
-pub struct ProjectFilter {
-	id: Option<OpValsInt64>,
-	name: Option<OpValsString>,
-
-	cid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
-}
+generate_filter!(ProjectFilter {
+	name: OpValsString,
+	client_id: OpValsInt64,
+});
 // endregion: --- Project Types
 
 // region:    --- ProjectBmc
 pub struct ProjectBmc;
 
+/// Note: The project tasks and rounding policy are deleted through their Bmc
+///       first (see `base::delete_children`).
+#[async_trait]
 impl DbBmc for ProjectBmc {
 	const TABLE: &'static str = "project";
+
+	async fn before_delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::delete_children::<TaskBmc>(ctx, mm, "project_id", id).await?;
+		base::delete_children::<RoundingPolicyBmc>(ctx, mm, "project_id", id).await
+	}
 }
 
+generate_common_bmc_fns!(
+	Bmc: ProjectBmc,
+	Entity: Project,
+	ForCreate: ProjectForCreate,
+	ForUpdate: ProjectForUpdate,
+	Filter: ProjectFilter,
+	Fns: [
+		get, list, list_stream, update, delete,
+		get_many, update_many, delete_many
+	],
+);
+
+// Note: `create` and `create_many` are overridden to set the `owner_id`
+//       from the ctx (see `ProjectForCreateInner`).
 impl ProjectBmc {
 	pub async fn create(
 		ctx: &Ctx,
@@ -86,34 +112,25 @@ impl ProjectBmc {
 		let project_c = ProjectForCreateInner {
 			name: project_c.name,
 			owner_id: ctx.user_id(),
+			client_id: project_c.client_id,
 		};
 		base::create::<Self, _>(ctx, mm, project_c).await
 	}
 
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Project> {
-		base::get::<Self, _>(ctx, mm, id).await
-	}
-
-	pub async fn list(
+	pub async fn create_many(
 		ctx: &Ctx,
 		mm: &ModelManager,
-		filter: Option<Vec<ProjectFilter>>,
-		list_options: Option<ListOptions>,
-	) -> Result<Vec<Project>> {
-		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
-	}
-
-	pub async fn update(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		id: i64,
-		project_u: ProjectForUpdate,
-	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, project_u).await
-	}
-
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
+		projects_c: Vec<ProjectForCreate>,
+	) -> Result<Vec<i64>> {
+		let projects_c: Vec<ProjectForCreateInner> = projects_c
+			.into_iter()
+			.map(|project_c| ProjectForCreateInner {
+				name: project_c.name,
+				owner_id: ctx.user_id(),
+				client_id: project_c.client_id,
+			})
+			.collect();
+		base::create_many::<Self, _>(ctx, mm, projects_c).await
 	}
 }
 // endregion: --- ProjectBmc
@@ -0,0 +1,260 @@
+//! Hourly rates, i.e., the billed price of the tracked time
+//! (see `model::invoice`).
+//!
+//! - A rate applies to a project, a task and/or a user (`None` for any),
+//!   from its `valid_from` date up to the next matching rate `valid_from`.
+//! - The most specific rate wins (task, then project, then user),
+//!   and for the same specificity, the latest one in effect
+//!   (see `rate_for`).
+
+use crate::ctx::Ctx;
+use crate::model::base::DbBmc;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::{date_to_sea_value, iso_date, time_to_sea_value};
+use crate::model::{Error, ModelManager};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{ListOptions, OpValsInt64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use time::Date;
+
+// region:    --- Rate Types
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Rate {
+	pub id: i64,
+	pub project_id: Option<i64>,
+	pub task_id: Option<i64>,
+	pub user_id: Option<i64>,
+
+	/// In cents of the client currency.
+	pub hourly_rate_cents: i64,
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl Rate {
+	/// Returns whether the rate applies to the given project, task, and user.
+	pub fn matches(&self, project_id: i64, task_id: i64, user_id: i64) -> bool {
+		self.project_id.is_none_or(|id| id == project_id)
+			&& self.task_id.is_none_or(|id| id == task_id)
+			&& self.user_id.is_none_or(|id| id == user_id)
+	}
+
+	/// The higher, the more specific (task, then project, then user).
+	fn specificity(&self) -> (bool, bool, bool) {
+		(
+			self.task_id.is_some(),
+			self.project_id.is_some(),
+			self.user_id.is_some(),
+		)
+	}
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
+pub struct RateForCreate {
+	#[serde(default)]
+	pub project_id: Option<i64>,
+	#[serde(default)]
+	pub task_id: Option<i64>,
+	#[serde(default)]
+	pub user_id: Option<i64>,
+	pub hourly_rate_cents: i64,
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
+pub struct RateForUpdate {
+	pub hourly_rate_cents: Option<i64>,
+	#[serde(default, with = "iso_date::option")]
+	#[schemars(with = "Option<String>")]
+	pub valid_from: Option<Date>,
+}
+
+generate_filter!(
+	RateFilter {
+		project_id: OpValsInt64,
+		task_id: OpValsInt64,
+		user_id: OpValsInt64,
+		hourly_rate_cents: OpValsInt64,
+	},
+	Dates: [valid_from],
+);
+// endregion: --- Rate Types
+
+// region:    --- RateBmc
+pub struct RateBmc;
+
+/// Note: The rate is checked once stored (create or update),
+///       failing (and rolling back) if negative.
+#[async_trait]
+impl DbBmc for RateBmc {
+	const TABLE: &'static str = "rate";
+
+	async fn after_create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_rate(&Self::get(ctx, mm, id).await?)
+	}
+
+	async fn after_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_rate(&Self::get(ctx, mm, id).await?)
+	}
+}
+
+generate_common_bmc_fns!(
+	Bmc: RateBmc,
+	Entity: Rate,
+	ForCreate: RateForCreate,
+	ForUpdate: RateForUpdate,
+	Filter: RateFilter,
+);
+
+impl RateBmc {
+	/// Returns all the rates of the ctx user, by `valid_from`.
+	pub async fn list_for_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+	) -> crate::model::Result<Vec<Rate>> {
+		let filter: RateFilter =
+			serde_json::from_value(json!({"cid": ctx.user_id()}))?;
+		let list_options = ListOptions {
+			order_bys: Some("valid_from".into()),
+			..Default::default()
+		};
+
+		Self::list_stream(ctx, mm, Some(vec![filter]), Some(list_options))?
+			.try_collect()
+			.await
+	}
+}
+
+fn check_rate(rate: &Rate) -> crate::model::Result<()> {
+	if rate.hourly_rate_cents < 0 {
+		return Err(Error::RateAmountInvalid(rate.hourly_rate_cents));
+	}
+
+	Ok(())
+}
+
+/// Returns the rate of the time of `user_id` on `task_id` (of `project_id`)
+/// on `date`, i.e., the most specific matching rate, and for the same
+/// specificity, the last one with a `valid_from` on or before `date`.
+///
+/// Note: `rates` must be ordered by `valid_from`
+///       (see `RateBmc::list_for_user`).
+pub fn rate_for(
+	rates: &[Rate],
+	project_id: i64,
+	task_id: i64,
+	user_id: i64,
+	date: Date,
+) -> Option<&Rate> {
+	rates
+		.iter()
+		.filter(|rate| rate.valid_from <= date)
+		.filter(|rate| rate.matches(project_id, task_id, user_id))
+		// (the last max, i.e., the latest valid_from)
+		.max_by_key(|rate| rate.specificity())
+}
+// endregion: --- RateBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_utils::time::now_utc;
+	use time::macros::date;
+
+	fn fx_rate(
+		project_id: Option<i64>,
+		task_id: Option<i64>,
+		user_id: Option<i64>,
+		hourly_rate_cents: i64,
+		valid_from: Date,
+	) -> Rate {
+		Rate {
+			id: hourly_rate_cents,
+			project_id,
+			task_id,
+			user_id,
+			hourly_rate_cents,
+			valid_from,
+			cid: 0,
+			ctime: now_utc(),
+			mid: 0,
+			mtime: now_utc(),
+		}
+	}
+
+	#[test]
+	fn test_rate_for_most_specific_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rates = [
+			fx_rate(None, None, Some(3), 100, date!(2024 - 01 - 01)),
+			fx_rate(Some(1), None, None, 200, date!(2024 - 01 - 01)),
+			fx_rate(Some(1), Some(2), None, 300, date!(2024 - 01 - 01)),
+			fx_rate(Some(1), None, None, 250, date!(2024 - 03 - 01)),
+		];
+
+		// -- Exec & Check
+		let rate_of = |project_id, task_id, user_id, date| {
+			rate_for(&fx_rates, project_id, task_id, user_id, date)
+				.map(|rate| rate.hourly_rate_cents)
+		};
+		assert_eq!(rate_of(1, 2, 3, date!(2024 - 04 - 01)), Some(300));
+		assert_eq!(rate_of(1, 5, 3, date!(2024 - 02 - 01)), Some(200));
+		assert_eq!(rate_of(1, 5, 3, date!(2024 - 03 - 01)), Some(250));
+		assert_eq!(rate_of(9, 5, 3, date!(2024 - 03 - 01)), Some(100));
+		assert_eq!(rate_of(9, 5, 4, date!(2024 - 03 - 01)), None);
+		assert_eq!(rate_of(1, 2, 3, date!(2023 - 12 - 31)), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_rate_err_negative() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rate = fx_rate(None, None, None, -1, date!(2024 - 01 - 01));
+
+		// -- Exec
+		let res = check_rate(&fx_rate);
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::RateAmountInvalid(-1))),
+			"RateAmountInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,394 @@
+//! Rounding policies, i.e., how the entry durations are rounded
+//! (e.g., up to 15 minutes) in the reports, exports and invoices.
+//!
+//! - A policy applies to a project, or is the user default (no project),
+//!   the project one taking precedence (see `Roundings`).
+//! - A duration is rounded `up`, `down` or to the `nearest` multiple of the
+//!   `increment_sec` (half up), then raised to the `min_sec` if shorter.
+//! - Only the durations are rounded, the entries `start_time`/`stop_time`
+//!   are never changed, and the reports show both the raw and rounded ones.
+
+use crate::ctx::Ctx;
+use crate::model::base;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::{Error, ModelManager, Result};
+use futures::TryStreamExt;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{OpValsInt64, OpValsString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// The max increment (a day).
+const INCREMENT_SEC_MAX: i64 = 24 * 3600;
+
+// region:    --- RoundingPolicy Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+	Up,
+	Down,
+	Nearest,
+}
+
+impl RoundingMode {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			RoundingMode::Up => "up",
+			RoundingMode::Down => "down",
+			RoundingMode::Nearest => "nearest",
+		}
+	}
+
+	fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"up" => Some(RoundingMode::Up),
+			"down" => Some(RoundingMode::Down),
+			"nearest" => Some(RoundingMode::Nearest),
+			_ => None,
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct RoundingPolicy {
+	pub id: i64,
+	/// `None` for the user default.
+	pub project_id: Option<i64>,
+
+	/// The `RoundingMode` (e.g., "up").
+	pub mode: String,
+	pub increment_sec: i64,
+	pub min_sec: i64,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl RoundingPolicy {
+	/// Returns the rounded `duration_sec`.
+	pub fn round(&self, duration_sec: i64) -> i64 {
+		// Note: The mode is always valid, as only stored from a `RoundingMode`.
+		let mode =
+			RoundingMode::from_name(&self.mode).unwrap_or(RoundingMode::Nearest);
+
+		round_sec(mode, self.increment_sec, self.min_sec, duration_sec)
+	}
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct RoundingPolicyForCreate {
+	/// `None` for the user default.
+	#[serde(default)]
+	pub project_id: Option<i64>,
+	pub mode: RoundingMode,
+	/// e.g., 900 (15 minutes)
+	pub increment_sec: i64,
+	#[serde(default)]
+	pub min_sec: i64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
+pub struct RoundingPolicyForUpdate {
+	pub mode: Option<RoundingMode>,
+	pub increment_sec: Option<i64>,
+	pub min_sec: Option<i64>,
+}
+
+/// The `RoundingPolicyForCreate` with the `mode` as stored.
+#[derive(Fields)]
+struct RoundingPolicyForCreateInner {
+	pub project_id: Option<i64>,
+	pub mode: String,
+	pub increment_sec: i64,
+	pub min_sec: i64,
+}
+
+#[derive(Fields)]
+struct RoundingPolicyForUpdateInner {
+	pub mode: Option<String>,
+	pub increment_sec: Option<i64>,
+	pub min_sec: Option<i64>,
+}
+
+generate_filter!(RoundingPolicyFilter {
+	project_id: OpValsInt64,
+	mode: OpValsString,
+	increment_sec: OpValsInt64,
+	min_sec: OpValsInt64,
+});
+
+/// The rounding policies of a user, by project.
+#[derive(Debug, Clone, Default)]
+pub struct Roundings {
+	default: Option<RoundingPolicy>,
+	projects: HashMap<i64, RoundingPolicy>,
+}
+
+impl Roundings {
+	pub fn new(policies: Vec<RoundingPolicy>) -> Self {
+		let mut roundings = Roundings::default();
+		for policy in policies {
+			match policy.project_id {
+				Some(project_id) => {
+					roundings.projects.insert(project_id, policy);
+				}
+				None => roundings.default = Some(policy),
+			}
+		}
+
+		roundings
+	}
+
+	/// Returns the rounded `duration_sec` of an entry of `project_id`
+	/// (`None` for a timerecord), per the project policy, or else the user
+	/// default one (unchanged without any).
+	pub fn round(&self, project_id: Option<i64>, duration_sec: i64) -> i64 {
+		let policy = project_id
+			.and_then(|project_id| self.projects.get(&project_id))
+			.or(self.default.as_ref());
+
+		match policy {
+			Some(policy) => policy.round(duration_sec),
+			None => duration_sec,
+		}
+	}
+}
+
+// endregion: --- RoundingPolicy Types
+
+// region:    --- RoundingPolicyBmc
+pub struct RoundingPolicyBmc;
+
+generate_common_bmc_fns!(
+	Bmc: RoundingPolicyBmc,
+	Table: "rounding_policy",
+	Entity: RoundingPolicy,
+	ForCreate: RoundingPolicyForCreate,
+	ForUpdate: RoundingPolicyForUpdate,
+	Filter: RoundingPolicyFilter,
+	Fns: [get, list, list_stream, delete, get_many, delete_many],
+);
+
+// Note: `create`, `update` (and their bulk versions) are overridden
+//       to store the `mode` as text (see `RoundingPolicyForCreateInner`).
+impl RoundingPolicyBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		policy_c: RoundingPolicyForCreate,
+	) -> Result<i64> {
+		base::create::<Self, _>(ctx, mm, policy_c.into_inner()?).await
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		policies_c: Vec<RoundingPolicyForCreate>,
+	) -> Result<Vec<i64>> {
+		let policies_c = policies_c
+			.into_iter()
+			.map(RoundingPolicyForCreate::into_inner)
+			.collect::<Result<Vec<_>>>()?;
+		base::create_many::<Self, _>(ctx, mm, policies_c).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		policy_u: RoundingPolicyForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, policy_u.into_inner()?).await
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		policies_u: Vec<(i64, RoundingPolicyForUpdate)>,
+	) -> Result<()> {
+		let policies_u = policies_u
+			.into_iter()
+			.map(|(id, policy_u)| Ok((id, policy_u.into_inner()?)))
+			.collect::<Result<Vec<_>>>()?;
+		base::update_many::<Self, _>(ctx, mm, policies_u).await
+	}
+
+	/// Returns the rounding policies of the ctx user.
+	pub async fn roundings_for_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+	) -> Result<Roundings> {
+		let filter: RoundingPolicyFilter =
+			serde_json::from_value(json!({"cid": ctx.user_id()}))?;
+		let policies: Vec<RoundingPolicy> =
+			Self::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+
+		Ok(Roundings::new(policies))
+	}
+}
+
+fn check_increment(increment_sec: i64) -> Result<()> {
+	if !(1..=INCREMENT_SEC_MAX).contains(&increment_sec) {
+		return Err(Error::RoundingIncrementInvalid(increment_sec));
+	}
+
+	Ok(())
+}
+
+fn check_min(min_sec: i64) -> Result<()> {
+	if !(0..=INCREMENT_SEC_MAX).contains(&min_sec) {
+		return Err(Error::RoundingMinInvalid(min_sec));
+	}
+
+	Ok(())
+}
+
+impl RoundingPolicyForCreate {
+	fn into_inner(self) -> Result<RoundingPolicyForCreateInner> {
+		check_increment(self.increment_sec)?;
+		check_min(self.min_sec)?;
+
+		Ok(RoundingPolicyForCreateInner {
+			project_id: self.project_id,
+			mode: self.mode.as_str().to_string(),
+			increment_sec: self.increment_sec,
+			min_sec: self.min_sec,
+		})
+	}
+}
+
+impl RoundingPolicyForUpdate {
+	fn into_inner(self) -> Result<RoundingPolicyForUpdateInner> {
+		self.increment_sec.map(check_increment).transpose()?;
+		self.min_sec.map(check_min).transpose()?;
+
+		Ok(RoundingPolicyForUpdateInner {
+			mode: self.mode.map(|mode| mode.as_str().to_string()),
+			increment_sec: self.increment_sec,
+			min_sec: self.min_sec,
+		})
+	}
+}
+
+/// Returns `duration_sec` rounded to a multiple of `increment_sec` (> 0)
+/// per `mode`, then raised to `min_sec` if shorter.
+pub fn round_sec(
+	mode: RoundingMode,
+	increment_sec: i64,
+	min_sec: i64,
+	duration_sec: i64,
+) -> i64 {
+	let duration_sec = duration_sec.max(0);
+	let increments = match mode {
+		RoundingMode::Up => (duration_sec + increment_sec - 1) / increment_sec,
+		RoundingMode::Down => duration_sec / increment_sec,
+		RoundingMode::Nearest => (duration_sec + increment_sec / 2) / increment_sec,
+	};
+
+	(increments * increment_sec).max(min_sec)
+}
+// endregion: --- RoundingPolicyBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use lib_utils::time::now_utc;
+
+	#[test]
+	fn test_round_sec_modes() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_increment = 15 * 60;
+		// 22m30s, half way between two increments
+		let fx_duration = 22 * 60 + 30;
+
+		// -- Exec & Check
+		let round = |mode| round_sec(mode, fx_increment, 0, fx_duration);
+		assert_eq!(round(RoundingMode::Up), 30 * 60);
+		assert_eq!(round(RoundingMode::Down), 15 * 60);
+		assert_eq!(round(RoundingMode::Nearest), 30 * 60);
+		assert_eq!(
+			round_sec(RoundingMode::Nearest, fx_increment, 0, fx_duration - 1),
+			15 * 60
+		);
+		// exact multiples are unchanged
+		assert_eq!(round_sec(RoundingMode::Up, fx_increment, 0, 3600), 3600);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_round_sec_min() -> Result<()> {
+		// -- Exec & Check
+		// 2 minutes, rounded down to 0, raised to the 6 minutes min
+		assert_eq!(round_sec(RoundingMode::Down, 360, 360, 120), 360);
+		// longer than the min, rounded only
+		assert_eq!(round_sec(RoundingMode::Down, 360, 360, 1000), 720);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_roundings_project_then_default() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_policy =
+			|id, project_id, mode: RoundingMode, increment_sec| RoundingPolicy {
+				id,
+				project_id,
+				mode: mode.as_str().to_string(),
+				increment_sec,
+				min_sec: 0,
+				cid: 0,
+				ctime: now_utc(),
+				mid: 0,
+				mtime: now_utc(),
+			};
+		let fx_roundings = Roundings::new(vec![
+			fx_policy(1, None, RoundingMode::Up, 360),
+			fx_policy(2, Some(1000), RoundingMode::Down, 900),
+		]);
+
+		// -- Exec & Check
+		assert_eq!(fx_roundings.round(Some(1000), 1000), 900);
+		assert_eq!(fx_roundings.round(Some(1001), 1000), 1080);
+		assert_eq!(fx_roundings.round(None, 1000), 1080);
+		assert_eq!(Roundings::default().round(None, 1000), 1000);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_increment_err_zero() -> Result<()> {
+		// -- Exec
+		let res = check_increment(0);
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::RoundingIncrementInvalid(0))),
+			"RoundingIncrementInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
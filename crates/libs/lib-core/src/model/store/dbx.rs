@@ -0,0 +1,204 @@
+//! `Dbx` is the db executor used by the model layer.
+//!
+//! It wraps the sqlx pool, and optionally a shared transaction, so that
+//! all the queries of a given `ModelManager` (e.g., a Bmc operation and its
+//! lifecycle hooks) run inside the same transaction.
+//!
+//! - `Dbx::new(db_pool, false)` executes everything on the pool.
+//! - `Dbx::new(db_pool, true)` executes on the open transaction, if any
+//!   (see `begin_txn` / `commit_txn`), and on the pool otherwise.
+//! - `begin_txn` / `commit_txn` are counted, so nested calls
+//!   (e.g., a hook calling another Bmc with the same `ModelManager`)
+//!   only commit on the outermost `commit_txn`.
+//! - A nested level is a savepoint, so `rollback_txn` only undoes this
+//!   level, and the outer levels can still commit (e.g., a hook handling
+//!   the error of the Bmc it called).
+//! - An uncommitted transaction is rolled back when the last `Dbx`
+//!   holding it is dropped (e.g., early return on error).
+
+use crate::model::store::{Db, Error, Result};
+use sqlx::postgres::PgRow;
+use sqlx::query::{Query, QueryAs};
+use sqlx::{Executor, FromRow, IntoArguments, Postgres, Transaction};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Clone)]
+pub struct Dbx {
+	db_pool: Db,
+	txn_holder: Arc<Mutex<Option<TxnHolder>>>,
+	with_txn: bool,
+}
+
+struct TxnHolder {
+	txn: Transaction<'static, Postgres>,
+	counter: i32,
+}
+
+// Constructor & Accessors
+impl Dbx {
+	pub fn new(db_pool: Db, with_txn: bool) -> Self {
+		Dbx {
+			db_pool,
+			txn_holder: Arc::default(),
+			with_txn,
+		}
+	}
+
+	pub fn db(&self) -> &Db {
+		&self.db_pool
+	}
+
+	pub fn with_txn(&self) -> bool {
+		self.with_txn
+	}
+}
+
+// Transaction
+impl Dbx {
+	pub async fn begin_txn(&self) -> Result<()> {
+		if !self.with_txn {
+			return Err(Error::CannotBeginTxnWithTxnFalse);
+		}
+
+		let mut txh_g = self.txn_holder.lock().await;
+		if let Some(txh) = txh_g.as_mut() {
+			txh.counter += 1;
+			let sql = format!("SAVEPOINT {}", savepoint_name(txh.counter));
+			txh.txn.execute(sql.as_str()).await?;
+		} else {
+			let txn = self.db_pool.begin().await?;
+			*txh_g = Some(TxnHolder { txn, counter: 1 });
+		}
+
+		Ok(())
+	}
+
+	pub async fn commit_txn(&self) -> Result<()> {
+		if !self.with_txn {
+			return Err(Error::CannotCommitTxnWithTxnFalse);
+		}
+
+		let mut txh_g = self.txn_holder.lock().await;
+		let Some(txh) = txh_g.as_mut() else {
+			return Err(Error::TxnCantCommitNoOpenTxn);
+		};
+
+		if txh.counter > 1 {
+			let sql = format!("RELEASE SAVEPOINT {}", savepoint_name(txh.counter));
+			txh.txn.execute(sql.as_str()).await?;
+			txh.counter -= 1;
+		} else if let Some(txh) = txh_g.take() {
+			txh.txn.commit().await?;
+		}
+
+		Ok(())
+	}
+
+	pub async fn is_txn_open(&self) -> bool {
+		self.txn_holder.lock().await.is_some()
+	}
+
+	/// Rollback the innermost transaction level, i.e., back to its savepoint
+	/// when nested, or the whole transaction when outermost.
+	pub async fn rollback_txn(&self) -> Result<()> {
+		if !self.with_txn {
+			return Err(Error::CannotRollbackTxnWithTxnFalse);
+		}
+
+		let mut txh_g = self.txn_holder.lock().await;
+		let Some(txh) = txh_g.as_mut() else {
+			return Err(Error::TxnCantRollbackNoOpenTxn);
+		};
+
+		if txh.counter > 1 {
+			let name = savepoint_name(txh.counter);
+			let sql =
+				format!("ROLLBACK TO SAVEPOINT {name}; RELEASE SAVEPOINT {name}");
+			txh.txn.execute(sql.as_str()).await?;
+			txh.counter -= 1;
+		} else if let Some(txh) = txh_g.take() {
+			txh.txn.rollback().await?;
+		}
+
+		Ok(())
+	}
+}
+
+fn savepoint_name(level: i32) -> String {
+	format!("dbx_txn_{level}")
+}
+
+// Executors
+impl Dbx {
+	pub async fn fetch_one<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> Result<O>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				return Ok(query.fetch_one(&mut *txh.txn).await?);
+			}
+		}
+
+		Ok(query.fetch_one(self.db()).await?)
+	}
+
+	pub async fn fetch_optional<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> Result<Option<O>>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				return Ok(query.fetch_optional(&mut *txh.txn).await?);
+			}
+		}
+
+		Ok(query.fetch_optional(self.db()).await?)
+	}
+
+	pub async fn fetch_all<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> Result<Vec<O>>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				return Ok(query.fetch_all(&mut *txh.txn).await?);
+			}
+		}
+
+		Ok(query.fetch_all(self.db()).await?)
+	}
+
+	/// Execute the query, and return the number of rows affected.
+	pub async fn execute<'q, A>(&self, query: Query<'q, Postgres, A>) -> Result<u64>
+	where
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				let row_affected =
+					query.execute(&mut *txh.txn).await?.rows_affected();
+				return Ok(row_affected);
+			}
+		}
+
+		Ok(query.execute(self.db()).await?.rows_affected())
+	}
+}
@@ -1,7 +1,9 @@
 // region:    --- Modules
 
+mod dbx;
 mod error;
 
+pub use self::dbx::Dbx;
 pub use self::error::{Error, Result};
 
 use crate::core_config;
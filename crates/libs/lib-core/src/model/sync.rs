@@ -0,0 +1,948 @@
+//! Change feed and offline sync (e.g., for the desktop/mobile clients).
+//!
+//! Design:
+//!
+//! - Every write made through `model::base` (create, update, delete, and bulk)
+//!   appends a row to the `change_log` table, in the same transaction,
+//!   with the entity owner (its `cid`).
+//!   `change_log.seq` is the monotonic change sequence across all entities.
+//!   The children of a deleted entity (e.g., the tasks of a project) are deleted
+//!   through their Bmc first (see `base::delete_children`), so they get their
+//!   tombstones too (not removed silently by the db `ON DELETE CASCADE`).
+//! - `SyncBmc::changes_since` returns the last change of each entity of the ctx
+//!   user changed after a given `seq`, as upserts (with the current entity data)
+//!   or tombstones, and the `last_seq` to give to the next call.
+//! - `SyncBmc::push` applies the changes made offline by a client.
+//!   - New entities are identified by a client generated uuid, mapped to the
+//!     server id in the `sync_uuid` table (so, a re-pushed create is applied once).
+//!   - Conflicts are resolved with the server `mtime` (last write wins).
+//!     An offline edit older than the server `mtime` is not applied and returned
+//!     as `Conflict`, the client then takes the server version from the change feed.
+//!   - An applied offline create or edit keeps the client `mtime` (not the push
+//!     time), so the next offline edits of the same entity are compared with
+//!     the client time they follow.
+//!
+//! Note: `change_log.seq` is allocated at insert time, so two concurrent transactions
+//!       can commit out of `seq` order. Clients should pull with a small overlap
+//!       (e.g., `since: last_seq - 100`), which is safe since changes are idempotent.
+
+use crate::ctx::Ctx;
+use crate::model::base::DbBmc;
+use crate::model::project::{
+	ProjectBmc, ProjectFilter, ProjectForCreate, ProjectForUpdate,
+};
+use crate::model::task::{TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate};
+use crate::model::taskprogress::{
+	TaskProgressBmc, TaskProgressFilter, TaskProgressForCreate,
+	TaskProgressForUpdate,
+};
+use crate::model::tasktime::{
+	TaskTimeBmc, TaskTimeFilter, TaskTimeForCreate, TaskTimeForUpdate,
+};
+use crate::model::timerecord::{
+	TimeRecordBmc, TimeRecordFilter, TimeRecordForCreate, TimeRecordForUpdate,
+};
+use crate::model::{Error, ModelManager, Result};
+use lib_utils::time::{now_utc, Rfc3339};
+use modql::filter::ListOptions;
+use modql::SIden;
+use schemars::JsonSchema;
+use sea_query::{
+	Expr, Iden, IntoIden, Order, PostgresQueryBuilder, Query, TableRef,
+};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const CHANGES_LIMIT_DEFAULT: i64 = 500;
+const CHANGES_LIMIT_MAX: i64 = 1000;
+
+// region:    --- Types
+
+/// The entities exposed to the sync protocol (serialized as their table name).
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncEntity {
+	Project,
+	Task,
+	TaskProgress,
+	TaskTime,
+	TimeRecord,
+}
+
+impl SyncEntity {
+	const ALL: [SyncEntity; 5] = [
+		SyncEntity::Project,
+		SyncEntity::Task,
+		SyncEntity::TaskProgress,
+		SyncEntity::TaskTime,
+		SyncEntity::TimeRecord,
+	];
+
+	pub fn table(&self) -> &'static str {
+		match self {
+			SyncEntity::Project => ProjectBmc::TABLE,
+			SyncEntity::Task => TaskBmc::TABLE,
+			SyncEntity::TaskProgress => TaskProgressBmc::TABLE,
+			SyncEntity::TaskTime => TaskTimeBmc::TABLE,
+			SyncEntity::TimeRecord => TimeRecordBmc::TABLE,
+		}
+	}
+
+	fn from_table(table: &str) -> Option<SyncEntity> {
+		Self::ALL.into_iter().find(|entity| entity.table() == table)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOp {
+	Upsert,
+	Delete,
+}
+
+impl ChangeOp {
+	fn as_str(&self) -> &'static str {
+		match self {
+			ChangeOp::Upsert => "upsert",
+			ChangeOp::Delete => "delete",
+		}
+	}
+
+	fn from_str(op: &str) -> Option<ChangeOp> {
+		match op {
+			"upsert" => Some(ChangeOp::Upsert),
+			"delete" => Some(ChangeOp::Delete),
+			_ => None,
+		}
+	}
+}
+
+/// A change of the feed.
+/// `data` is the current entity for an `Upsert`, and `None` for a `Delete` (tombstone).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Change {
+	pub seq: i64,
+	pub entity: SyncEntity,
+	pub id: i64,
+	pub op: ChangeOp,
+	pub data: Option<Value>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChangeSet {
+	pub changes: Vec<Change>,
+	/// The `seq` to give to the next `changes_since` call.
+	pub last_seq: i64,
+	/// True if the limit was reached (i.e., more changes to pull).
+	pub has_more: bool,
+}
+
+/// A change made offline by a client.
+///
+/// - `uuid` is generated by the client for each entity it created offline,
+///   and `id` is the server id when the entity is already known by the server.
+/// - `data` is the `...ForCreate` (new entity) or `...ForUpdate` json
+///   of the entity (ignored for `Delete`).
+/// - `mtime` is the time of the offline edit.
+#[serde_as(schemars = false)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SyncPushItem {
+	pub uuid: Uuid,
+	pub entity: SyncEntity,
+	pub op: ChangeOp,
+	pub id: Option<i64>,
+	pub data: Option<Value>,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncPushStatus {
+	/// The change was applied.
+	Applied,
+	/// The server version is more recent, the change was not applied.
+	Conflict,
+	/// The entity was deleted on the server, the change was not applied.
+	Gone,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SyncPushResult {
+	pub uuid: Uuid,
+	pub id: Option<i64>,
+	pub status: SyncPushStatus,
+}
+
+#[derive(Iden)]
+enum ChangeLogIden {
+	#[iden = "change_log"]
+	Table,
+	Seq,
+	Entity,
+	EntityId,
+	Op,
+	Cid,
+	Ctime,
+}
+
+#[derive(Iden)]
+enum SyncUuidIden {
+	#[iden = "sync_uuid"]
+	Table,
+	Uuid,
+	Entity,
+	EntityId,
+	Cid,
+	Ctime,
+}
+
+#[derive(Iden)]
+enum SyncEntityIden {
+	Id,
+	Mtime,
+}
+
+#[derive(FromRow)]
+struct ChangeLogRow {
+	seq: i64,
+	entity: String,
+	entity_id: i64,
+	op: String,
+}
+
+// endregion: --- Types
+
+// region:    --- Entity Dispatch
+
+/// Generates the `SyncEntity` functions dispatching the json data to the
+/// typed Bmc functions of each entity.
+macro_rules! impl_sync_entity_dispatch {
+	($($variant:ident => $bmc:ident, $for_create:ty, $for_update:ty, $filter:ty;)*) => {
+		impl SyncEntity {
+			async fn create(&self, ctx: &Ctx, mm: &ModelManager, data: Value) -> Result<i64> {
+				match self {
+					$(SyncEntity::$variant => {
+						let data: $for_create = serde_json::from_value(data)?;
+						$bmc::create(ctx, mm, data).await
+					})*
+				}
+			}
+
+			async fn update(
+				&self,
+				ctx: &Ctx,
+				mm: &ModelManager,
+				id: i64,
+				data: Value,
+			) -> Result<()> {
+				match self {
+					$(SyncEntity::$variant => {
+						let data: $for_update = serde_json::from_value(data)?;
+						$bmc::update(ctx, mm, id, data).await
+					})*
+				}
+			}
+
+			async fn delete(&self, ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+				match self {
+					$(SyncEntity::$variant => $bmc::delete(ctx, mm, id).await,)*
+				}
+			}
+
+			/// Returns the json of the entities found for `ids` (missing ones are skipped).
+			async fn list_json(
+				&self,
+				ctx: &Ctx,
+				mm: &ModelManager,
+				ids: &[i64],
+			) -> Result<HashMap<i64, Value>> {
+				let list_options = ListOptions {
+					limit: Some(ids.len() as i64),
+					..Default::default()
+				};
+				let filter = json!({"id": {"$in": ids}});
+
+				let mut by_id = HashMap::new();
+				match self {
+					$(SyncEntity::$variant => {
+						let filter: $filter = serde_json::from_value(filter)?;
+						let entities =
+							$bmc::list(ctx, mm, Some(vec![filter]), Some(list_options))
+								.await?;
+						for entity in entities {
+							by_id.insert(entity.id, serde_json::to_value(entity)?);
+						}
+					})*
+				}
+
+				Ok(by_id)
+			}
+		}
+	};
+}
+
+impl_sync_entity_dispatch!(
+	Project => ProjectBmc, ProjectForCreate, ProjectForUpdate, ProjectFilter;
+	Task => TaskBmc, TaskForCreate, TaskForUpdate, TaskFilter;
+	TaskProgress => TaskProgressBmc, TaskProgressForCreate, TaskProgressForUpdate,
+		TaskProgressFilter;
+	TaskTime => TaskTimeBmc, TaskTimeForCreate, TaskTimeForUpdate, TaskTimeFilter;
+	TimeRecord => TimeRecordBmc, TimeRecordForCreate, TimeRecordForUpdate,
+		TimeRecordFilter;
+);
+
+// endregion: --- Entity Dispatch
+
+// region:    --- SyncBmc
+
+pub struct SyncBmc;
+
+impl SyncBmc {
+	/// Returns the last change of each entity of the ctx user changed after `since`,
+	/// in `seq` order.
+	pub async fn changes_since(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		since: i64,
+		limit: Option<i64>,
+	) -> Result<ChangeSet> {
+		let limit = limit.unwrap_or(CHANGES_LIMIT_DEFAULT);
+		if limit > CHANGES_LIMIT_MAX {
+			return Err(Error::ListLimitOverMax {
+				max: CHANGES_LIMIT_MAX,
+				actual: limit,
+			});
+		}
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(ChangeLogIden::Table)
+			.columns([
+				ChangeLogIden::Seq,
+				ChangeLogIden::Entity,
+				ChangeLogIden::EntityId,
+				ChangeLogIden::Op,
+			])
+			.and_where(Expr::col(ChangeLogIden::Cid).eq(ctx.user_id()))
+			.and_where(Expr::col(ChangeLogIden::Seq).gt(since))
+			.order_by(ChangeLogIden::Seq, Order::Asc)
+			.limit(limit as u64);
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, ChangeLogRow, _>(&sql, values);
+		let rows = mm.dbx().fetch_all(sqlx_query).await?;
+
+		let has_more = rows.len() as i64 == limit;
+		let last_seq = rows.last().map(|row| row.seq).unwrap_or(since);
+
+		// -- Keep the last change per entity (rows are in seq order)
+		let mut last_changes: HashMap<(SyncEntity, i64), (i64, ChangeOp)> =
+			HashMap::new();
+		for row in rows {
+			let (Some(entity), Some(op)) = (
+				SyncEntity::from_table(&row.entity),
+				ChangeOp::from_str(&row.op),
+			) else {
+				continue;
+			};
+			last_changes.insert((entity, row.entity_id), (row.seq, op));
+		}
+
+		// -- Load the data of the upserts
+		let mut upsert_ids: HashMap<SyncEntity, Vec<i64>> = HashMap::new();
+		for ((entity, id), (_, op)) in last_changes.iter() {
+			if *op == ChangeOp::Upsert {
+				upsert_ids.entry(*entity).or_default().push(*id);
+			}
+		}
+		let mut datas: HashMap<(SyncEntity, i64), Value> = HashMap::new();
+		for (entity, ids) in upsert_ids {
+			for (id, data) in entity.list_json(ctx, mm, &ids).await? {
+				datas.insert((entity, id), data);
+			}
+		}
+
+		// -- Build the changes
+		//    (an upsert of an entity deleted since is sent as a tombstone)
+		let mut changes: Vec<Change> = last_changes
+			.into_iter()
+			.map(|((entity, id), (seq, op))| {
+				let data = datas.remove(&(entity, id));
+				let op = if data.is_some() { op } else { ChangeOp::Delete };
+				Change {
+					seq,
+					entity,
+					id,
+					op,
+					data,
+				}
+			})
+			.collect();
+		changes.sort_by_key(|change| change.seq);
+
+		Ok(ChangeSet {
+			changes,
+			last_seq,
+			has_more,
+		})
+	}
+
+	/// Applies the offline changes of a client, in order, in a single transaction.
+	pub async fn push(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		items: Vec<SyncPushItem>,
+	) -> Result<Vec<SyncPushResult>> {
+		// -- Start the transaction
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let mut results = Vec::with_capacity(items.len());
+			for item in items {
+				let result = Self::push_item(ctx, &mm, item).await?;
+				results.push(result);
+			}
+
+			Ok(results)
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	async fn push_item(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		item: SyncPushItem,
+	) -> Result<SyncPushResult> {
+		let SyncPushItem {
+			uuid,
+			entity,
+			op,
+			id,
+			data,
+			mtime,
+		} = item;
+
+		let id = match id {
+			Some(id) => Some(id),
+			None => Self::id_for_uuid(mm, entity, uuid).await?,
+		};
+
+		let result = |id, status| SyncPushResult { uuid, id, status };
+
+		// -- Entity unknown to the server (created offline)
+		let Some(id) = id else {
+			return match op {
+				ChangeOp::Upsert => {
+					let data = data.ok_or(Error::SyncPushDataMissing { uuid })?;
+					let id = entity.create(ctx, mm, data).await?;
+					Self::insert_uuid(ctx, mm, entity, uuid, id).await?;
+					Self::set_mtime(mm, entity, id, mtime).await?;
+					Ok(result(Some(id), SyncPushStatus::Applied))
+				}
+				// created and deleted offline, nothing to do.
+				ChangeOp::Delete => Ok(result(None, SyncPushStatus::Applied)),
+			};
+		};
+
+		// -- Entity known by the server
+		let Some(server_mtime) = Self::mtime(mm, entity, id).await? else {
+			let status = match op {
+				ChangeOp::Upsert => SyncPushStatus::Gone,
+				ChangeOp::Delete => SyncPushStatus::Applied,
+			};
+			return Ok(result(Some(id), status));
+		};
+
+		if server_mtime > mtime {
+			return Ok(result(Some(id), SyncPushStatus::Conflict));
+		}
+
+		match op {
+			ChangeOp::Upsert => {
+				let data = data.ok_or(Error::SyncPushDataMissing { uuid })?;
+				entity.update(ctx, mm, id, data).await?;
+				Self::set_mtime(mm, entity, id, mtime).await?;
+			}
+			ChangeOp::Delete => entity.delete(ctx, mm, id).await?,
+		}
+
+		Ok(result(Some(id), SyncPushStatus::Applied))
+	}
+
+	async fn id_for_uuid(
+		mm: &ModelManager,
+		entity: SyncEntity,
+		uuid: Uuid,
+	) -> Result<Option<i64>> {
+		let mut query = Query::select();
+		query
+			.from(SyncUuidIden::Table)
+			.column(SyncUuidIden::EntityId)
+			.and_where(Expr::col(SyncUuidIden::Uuid).eq(uuid))
+			.and_where(Expr::col(SyncUuidIden::Entity).eq(entity.table()));
+
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let id = mm.dbx().fetch_optional(sqlx_query).await?.map(|(id,)| id);
+
+		Ok(id)
+	}
+
+	async fn insert_uuid(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		entity: SyncEntity,
+		uuid: Uuid,
+		id: i64,
+	) -> Result<()> {
+		let mut query = Query::insert();
+		query
+			.into_table(SyncUuidIden::Table)
+			.columns([
+				SyncUuidIden::Uuid,
+				SyncUuidIden::Entity,
+				SyncUuidIden::EntityId,
+				SyncUuidIden::Cid,
+				SyncUuidIden::Ctime,
+			])
+			.values([
+				uuid.into(),
+				entity.table().into(),
+				id.into(),
+				ctx.user_id().into(),
+				now_utc().into(),
+			])?;
+
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Sets the `mtime` of the entity to the client `mtime` of the applied change
+	/// (see module doc).
+	async fn set_mtime(
+		mm: &ModelManager,
+		entity: SyncEntity,
+		id: i64,
+		mtime: OffsetDateTime,
+	) -> Result<()> {
+		let mut query = Query::update();
+		query
+			.table(TableRef::Table(SIden(entity.table()).into_iden()))
+			.value(SyncEntityIden::Mtime, mtime)
+			.and_where(Expr::col(SyncEntityIden::Id).eq(id));
+
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Returns the server `mtime` of the entity, or None if it does not exist.
+	async fn mtime(
+		mm: &ModelManager,
+		entity: SyncEntity,
+		id: i64,
+	) -> Result<Option<OffsetDateTime>> {
+		let mut query = Query::select();
+		query
+			.from(TableRef::Table(SIden(entity.table()).into_iden()))
+			.column(SyncEntityIden::Mtime)
+			.and_where(Expr::col(SyncEntityIden::Id).eq(id));
+
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query =
+			sqlx::query_as_with::<_, (OffsetDateTime,), _>(&sql, values);
+		let mtime = mm
+			.dbx()
+			.fetch_optional(sqlx_query)
+			.await?
+			.map(|(mtime,)| mtime);
+
+		Ok(mtime)
+	}
+}
+
+/// Appends the `changes`, i.e., the `(id, owner_id)` of the changed entities,
+/// to the `change_log`.
+/// (Called by the `model::base` write functions, within their transaction)
+pub(in crate::model) async fn record_changes(
+	mm: &ModelManager,
+	table: &'static str,
+	changes: &[(i64, i64)],
+	op: ChangeOp,
+) -> Result<()> {
+	if changes.is_empty() {
+		return Ok(());
+	}
+
+	let now = now_utc();
+	let mut query = Query::insert();
+	query.into_table(ChangeLogIden::Table).columns([
+		ChangeLogIden::Entity,
+		ChangeLogIden::EntityId,
+		ChangeLogIden::Op,
+		ChangeLogIden::Cid,
+		ChangeLogIden::Ctime,
+	]);
+	for (id, owner_id) in changes {
+		query.values([
+			table.into(),
+			(*id).into(),
+			op.as_str().into(),
+			(*owner_id).into(),
+			now.into(),
+		])?;
+	}
+
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_with(&sql, values);
+	mm.dbx().execute(sqlx_query).await?;
+
+	Ok(())
+}
+
+// endregion: --- SyncBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::user::{User, UserBmc};
+	use anyhow::{Context, Result};
+	use lib_utils::time::now_utc_plus_sec_str;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_changes_since_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_since = last_seq(&ctx, &mm).await?;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_changes_since_ok project")
+				.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_changes_since_ok task",
+		)
+		.await?;
+		TaskBmc::delete(&ctx, &mm, fx_task_id).await?;
+
+		// -- Exec
+		let change_set = SyncBmc::changes_since(&ctx, &mm, fx_since, None).await?;
+
+		// -- Check
+		assert_eq!(
+			change_set.changes.len(),
+			2,
+			"project upsert & task tombstone"
+		);
+		let project_change = &change_set.changes[0];
+		assert_eq!(project_change.entity, SyncEntity::Project);
+		assert_eq!(project_change.op, ChangeOp::Upsert);
+		assert_eq!(
+			project_change
+				.data
+				.as_ref()
+				.and_then(|v| v["name"].as_str()),
+			Some("test_changes_since_ok project")
+		);
+		let task_change = &change_set.changes[1];
+		assert_eq!(task_change.entity, SyncEntity::Task);
+		assert_eq!(task_change.id, fx_task_id);
+		assert_eq!(task_change.op, ChangeOp::Delete);
+		assert!(task_change.data.is_none());
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_changes_since_cascade_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_changes_since_cascade_delete_ok project",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_changes_since_cascade_delete_ok task 01",
+				"test_changes_since_cascade_delete_ok task 02",
+			],
+		)
+		.await?;
+		let fx_tasktimes = _dev_utils::seed_tasktimes(
+			&ctx,
+			&mm,
+			fx_tasks[0].id,
+			&["test_changes_since_cascade_delete_ok tasktime"],
+		)
+		.await?;
+		let fx_since = last_seq(&ctx, &mm).await?;
+
+		// -- Exec
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		let change_set = SyncBmc::changes_since(&ctx, &mm, fx_since, None).await?;
+
+		// -- Check
+		let tombstones: Vec<(SyncEntity, i64)> = change_set
+			.changes
+			.iter()
+			.filter(|c| c.op == ChangeOp::Delete && c.data.is_none())
+			.map(|c| (c.entity, c.id))
+			.collect();
+		assert_eq!(tombstones.len(), change_set.changes.len());
+		assert!(tombstones.contains(&(SyncEntity::Project, fx_project_id)));
+		for fx_task in &fx_tasks {
+			assert!(tombstones.contains(&(SyncEntity::Task, fx_task.id)));
+		}
+		assert!(tombstones.contains(&(SyncEntity::TaskTime, fx_tasktimes[0].id)));
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_changes_since_create_update_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_since = last_seq(&ctx, &mm).await?;
+
+		// -- Exec
+		let project_c = ProjectForCreate {
+			name: "test_changes_since_create_update_ok".to_string(),
+			client_id: None,
+		};
+		let project_id = ProjectBmc::create(&ctx, &mm, project_c).await?;
+		let change_set_c = SyncBmc::changes_since(&ctx, &mm, fx_since, None).await?;
+		let project_u = ProjectForUpdate {
+			name: Some("test_changes_since_create_update_ok updated".to_string()),
+			owner_id: None,
+			client_id: None,
+		};
+		ProjectBmc::update(&ctx, &mm, project_id, project_u).await?;
+		let change_set_u =
+			SyncBmc::changes_since(&ctx, &mm, change_set_c.last_seq, None).await?;
+
+		// -- Check
+		let [change_c] = change_set_c.changes.as_slice() else {
+			panic!("should have one change: {:?}", change_set_c.changes);
+		};
+		assert_eq!(change_c.entity, SyncEntity::Project);
+		assert_eq!(change_c.id, project_id);
+		assert_eq!(change_c.op, ChangeOp::Upsert);
+		let [change_u] = change_set_u.changes.as_slice() else {
+			panic!("should have one change: {:?}", change_set_u.changes);
+		};
+		assert!(change_u.seq > change_c.seq, "update after the create");
+		assert_eq!(
+			change_u.data.as_ref().and_then(|v| v["name"].as_str()),
+			Some("test_changes_since_create_update_ok updated")
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_changes_since_owner_only_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(demo1.id)?;
+		let fx_root_since = last_seq(&root_ctx, &mm).await?;
+		let fx_since = last_seq(&ctx, &mm).await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_changes_since_owner_only_ok project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_changes_since_owner_only_ok task",
+		)
+		.await?;
+		TaskBmc::delete(&ctx, &mm, fx_task_id).await?;
+
+		// -- Exec
+		let change_set = SyncBmc::changes_since(&ctx, &mm, fx_since, None).await?;
+		let root_change_set =
+			SyncBmc::changes_since(&root_ctx, &mm, fx_root_since, None).await?;
+
+		// -- Check
+		let ids: Vec<i64> = change_set.changes.iter().map(|c| c.id).collect();
+		assert_eq!(ids, [fx_project_id, fx_task_id]);
+		assert!(
+			root_change_set.changes.is_empty(),
+			"should not have the demo1 changes: {:?}",
+			root_change_set.changes
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_push_create_idempotent_and_conflict() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_push_create_idempotent_and_conflict project",
+		)
+		.await?;
+		let fx_uuid = Uuid::new_v4();
+		let fx_item_c = json!({
+			"uuid": fx_uuid,
+			"entity": "task",
+			"op": "upsert",
+			"data": {"project_id": fx_project_id, "title": "offline task"},
+			"mtime": now_utc_plus_sec_str(0.),
+		});
+		let fx_item_u_old = json!({
+			"uuid": fx_uuid,
+			"entity": "task",
+			"op": "upsert",
+			"data": {"title": "offline task - old edit"},
+			"mtime": now_utc_plus_sec_str(-3600.),
+		});
+
+		// -- Exec
+		let items: Vec<SyncPushItem> =
+			serde_json::from_value(json!([fx_item_c.clone(), fx_item_c]))?;
+		let results_c = SyncBmc::push(&ctx, &mm, items).await?;
+		let items: Vec<SyncPushItem> =
+			serde_json::from_value(json!([fx_item_u_old]))?;
+		let results_u = SyncBmc::push(&ctx, &mm, items).await?;
+
+		// -- Check
+		let task_id = results_c[0].id.context("should have id")?;
+		assert_eq!(results_c[0].status, SyncPushStatus::Applied);
+		assert_eq!(results_c[1].id, Some(task_id), "create applied only once");
+		assert_eq!(results_u[0].status, SyncPushStatus::Conflict);
+		let task = TaskBmc::get(&ctx, &mm, task_id).await?;
+		assert_eq!(task.title, "offline task");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_push_create_then_update_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_push_create_then_update_ok project",
+		)
+		.await?;
+		let fx_uuid = Uuid::new_v4();
+		// (both made offline, before the push)
+		let fx_items = json!([
+			{
+				"uuid": fx_uuid,
+				"entity": "task",
+				"op": "upsert",
+				"data": {"project_id": fx_project_id, "title": "offline task"},
+				"mtime": now_utc_plus_sec_str(-120.),
+			},
+			{
+				"uuid": fx_uuid,
+				"entity": "task",
+				"op": "upsert",
+				"data": {"title": "offline task - edited"},
+				"mtime": now_utc_plus_sec_str(-60.),
+			},
+		]);
+
+		// -- Exec
+		let items: Vec<SyncPushItem> = serde_json::from_value(fx_items)?;
+		let results = SyncBmc::push(&ctx, &mm, items).await?;
+
+		// -- Check
+		let statuses: Vec<SyncPushStatus> =
+			results.iter().map(|result| result.status).collect();
+		assert_eq!(statuses, [SyncPushStatus::Applied, SyncPushStatus::Applied]);
+		let task_id = results[0].id.context("should have id")?;
+		assert_eq!(results[1].id, Some(task_id));
+		let task = TaskBmc::get(&ctx, &mm, task_id).await?;
+		assert_eq!(task.title, "offline task - edited");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	// region:    --- Support
+
+	async fn last_seq(ctx: &Ctx, mm: &ModelManager) -> Result<i64> {
+		let mut since = 0;
+		loop {
+			let change_set =
+				SyncBmc::changes_since(ctx, mm, since, Some(CHANGES_LIMIT_MAX))
+					.await?;
+			since = change_set.last_seq;
+			if !change_set.has_more {
+				return Ok(since);
+			}
+		}
+	}
+
+	// endregion: --- Support
+}
+// endregion: --- Tests
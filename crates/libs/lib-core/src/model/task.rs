@@ -1,21 +1,23 @@
 use crate::ctx::Ctx;
 use crate::model::base::{self, DbBmc};
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::taskprogress::TaskProgressBmc;
+use crate::model::tasktime::TaskTimeBmc;
 use crate::model::ModelManager;
-use crate::model::Result;
+use async_trait::async_trait;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{
-	FilterNodes, ListOptions, OpValsBool, OpValsInt64, OpValsString, OpValsValue,
-};
+use modql::filter::{OpValsBool, OpValsInt64, OpValsString};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:    --- Task Types
-#[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
 pub struct Task {
 	pub id: i64,
 	pub project_id: i64,
@@ -27,82 +29,60 @@ pub struct Task {
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct TaskForCreate {
 	pub title: String,
 	pub project_id: i64,
 }
 
-#[derive(Fields, Deserialize, Default)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TaskForUpdate {
 	pub title: Option<String>,
 	pub done: Option<bool>,
 }
 
-#[derive(FilterNodes, Deserialize, Default, Debug)]
-pub struct TaskFilter {
-	id: Option<OpValsInt64>,
-	project_id: Option<OpValsInt64>,
-	title: Option<OpValsString>,
-	done: Option<OpValsBool>,
-
-	cid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
-}
+generate_filter!(TaskFilter {
+	project_id: OpValsInt64,
+	title: OpValsString,
+	done: OpValsBool,
+});
 // endregion: --- Task Types
 
 // region:    --- TaskBmc
 pub struct TaskBmc;
 
+/// Note: The task progresses and times are deleted through their Bmc first
+///       (see `base::delete_children`), e.g., so a time in a locked period
+///       fails the task delete.
+#[async_trait]
 impl DbBmc for TaskBmc {
 	const TABLE: &'static str = "task";
-}
-
-impl TaskBmc {
-	pub async fn create(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		task_c: TaskForCreate,
-	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, task_c).await
-	}
-
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
-		base::get::<Self, _>(ctx, mm, id).await
-	}
-
-	pub async fn list(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		filter: Option<Vec<TaskFilter>>,
-		list_options: Option<ListOptions>,
-	) -> Result<Vec<Task>> {
-		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
-	}
 
-	pub async fn update(
+	async fn before_delete(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		id: i64,
-		task_u: TaskForUpdate,
-	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, task_u).await
-	}
-
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
+	) -> crate::model::Result<()> {
+		base::delete_children::<TaskProgressBmc>(ctx, mm, "task_id", id).await?;
+		base::delete_children::<TaskTimeBmc>(ctx, mm, "task_id", id).await
 	}
 }
+
+generate_common_bmc_fns!(
+	Bmc: TaskBmc,
+	Entity: Task,
+	ForCreate: TaskForCreate,
+	ForUpdate: TaskForUpdate,
+	Filter: TaskFilter,
+);
 // endregion: --- TaskBmc
 
 // region:    --- Tests
@@ -110,10 +90,14 @@ impl TaskBmc {
 mod tests {
 	use super::*;
 	use crate::_dev_utils;
+	use crate::ctx::Ctx;
+	use crate::model::base::{self, DbBmc};
 	use crate::model::project::ProjectBmc;
-	use crate::model::Error;
+	use crate::model::{Error, ModelManager};
 	use anyhow::Result;
+	use async_trait::async_trait;
 	use lib_utils::time::{format_time, now_utc};
+	use modql::filter::ListOptions;
 	use modql::filter::OpValString;
 	use serde_json::json;
 	use serial_test::serial;
@@ -371,6 +355,164 @@ mod tests {
 		Ok(())
 	}
 
+	#[serial]
+	#[tokio::test]
+	async fn test_create_many_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &["test_create_many_ok 01", "test_create_many_ok 02"];
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_create_many_ok project")
+				.await?;
+
+		// -- Exec
+		let tasks_c = fx_titles
+			.iter()
+			.map(|title| TaskForCreate {
+				project_id: fx_project_id,
+				title: title.to_string(),
+			})
+			.collect();
+		let ids = TaskBmc::create_many(&ctx, &mm, tasks_c).await?;
+
+		// -- Check
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
+		let titles: Vec<String> = tasks.into_iter().map(|t| t.title).collect();
+		assert_eq!(&titles, fx_titles);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_many_mixed_fields_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		#[derive(Fields)]
+		struct TaskForCreateDone {
+			project_id: i64,
+			title: String,
+			done: Option<bool>,
+		}
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_dones = [Some(true), None, Some(false)];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_create_many_mixed_fields_ok project",
+		)
+		.await?;
+
+		// -- Exec
+		let tasks_c = fx_dones
+			.iter()
+			.map(|done| TaskForCreateDone {
+				project_id: fx_project_id,
+				title: "test_create_many_mixed_fields_ok".to_string(),
+				done: *done,
+			})
+			.collect();
+		let ids = base::create_many::<TaskBmc, _>(&ctx, &mm, tasks_c).await?;
+
+		// -- Check
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
+		let dones: Vec<bool> = tasks.into_iter().map(|t| t.done).collect();
+		// Note: `done` defaults to false.
+		assert_eq!(dones, [true, false, false]);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_many_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &["test_update_many_ok 01", "test_update_many_ok 02"];
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_update_many_ok project")
+				.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let tasks_u = fx_tasks
+			.iter()
+			.map(|task| {
+				(
+					task.id,
+					TaskForUpdate {
+						done: Some(true),
+						..Default::default()
+					},
+				)
+			})
+			.collect();
+		TaskBmc::update_many(&ctx, &mm, tasks_u).await?;
+
+		// -- Check
+		let ids: Vec<i64> = fx_tasks.iter().map(|t| t.id).collect();
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
+		assert!(tasks.iter().all(|t| t.done));
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_many_err_not_found_rollback() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_delete_many_err_not_found_rollback project",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_delete_many_err_not_found_rollback 01"],
+		)
+		.await?
+		.remove(0);
+
+		// -- Exec
+		let res = TaskBmc::delete_many(&ctx, &mm, &[fx_task.id, 100]).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::EntityNotFound {
+					entity: "task",
+					id: 100
+				})
+			),
+			"EntityNotFound not matching"
+		);
+		// The existing task must still be there (rollback).
+		TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
 	#[serial]
 	#[tokio::test]
 	async fn test_delete_err_not_found() -> Result<()> {
@@ -396,5 +538,78 @@ mod tests {
 
 		Ok(())
 	}
+
+	/// A task Bmc whose `after_update` hook fails (i.e., after the write).
+	struct TaskFailingUpdateBmc;
+
+	#[async_trait]
+	impl DbBmc for TaskFailingUpdateBmc {
+		const TABLE: &'static str = "task";
+
+		async fn after_update(
+			_ctx: &Ctx,
+			_mm: &ModelManager,
+			id: i64,
+		) -> crate::model::Result<()> {
+			Err(Error::EntityNotFound { entity: "task", id })
+		}
+	}
+
+	/// A task Bmc whose `after_create` hook handles the error of a nested
+	/// (failing) update.
+	struct TaskNestedUpdateBmc;
+
+	#[async_trait]
+	impl DbBmc for TaskNestedUpdateBmc {
+		const TABLE: &'static str = "task";
+
+		async fn after_create(
+			ctx: &Ctx,
+			mm: &ModelManager,
+			id: i64,
+		) -> crate::model::Result<()> {
+			let task_u = TaskForUpdate {
+				title: Some("nested update".to_string()),
+				..Default::default()
+			};
+			let res =
+				base::update::<TaskFailingUpdateBmc, _>(ctx, mm, id, task_u).await;
+			assert!(res.is_err(), "nested update should fail");
+
+			Ok(())
+		}
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_nested_err_handled_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_create_nested_err_handled_ok";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_create_nested_err_handled_ok project",
+		)
+		.await?;
+
+		// -- Exec
+		let task_c = TaskForCreate {
+			project_id: fx_project_id,
+			title: fx_title.to_string(),
+		};
+		let id = base::create::<TaskNestedUpdateBmc, _>(&ctx, &mm, task_c).await?;
+
+		// -- Check
+		// The create is committed, without the nested update.
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(task.title, fx_title);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
 }
 // endregion: --- Tests
@@ -1,21 +1,22 @@
 use crate::ctx::Ctx;
-use crate::model::base::{self, DbBmc};
+use crate::model::base::DbBmc;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
 use crate::model::modql_utils::time_to_sea_value;
-use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::task::{TaskBmc, TaskForUpdate};
+use crate::model::{ModelManager, Result};
+use async_trait::async_trait;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{
-	FilterNodes, ListOptions, OpValsInt32, OpValsInt64, OpValsValue,
-};
+use modql::filter::{OpValsInt32, OpValsInt64};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:    --- Task Types
-#[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
 pub struct TaskProgress {
 	pub id: i64,
 	pub task_id: i64,
@@ -26,78 +27,77 @@ pub struct TaskProgress {
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub mtime: OffsetDateTime,
 }
 
-#[derive(Fields, Deserialize)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct TaskProgressForCreate {
 	pub progress: i32,
 	pub task_id: i64,
 }
 
-#[derive(Fields, Deserialize, Default)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TaskProgressForUpdate {
 	pub progress: i32,
 }
 
-#[derive(FilterNodes, Deserialize, Default, Debug)]
-pub struct TaskProgressFilter {
-	id: Option<OpValsInt64>,
-	task_id: Option<OpValsInt64>,
-	progress: Option<OpValsInt32>,
-
-	cid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
-}
+generate_filter!(TaskProgressFilter {
+	task_id: OpValsInt64,
+	progress: OpValsInt32,
+});
 // endregion: --- Task Types
 
 // region:    --- TaskBmc
 pub struct TaskProgressBmc;
 
+/// Progress value at which the associated task is marked as done.
+const PROGRESS_DONE: i32 = 100;
+
+#[async_trait]
 impl DbBmc for TaskProgressBmc {
 	const TABLE: &'static str = "taskprogress";
-}
 
-impl TaskProgressBmc {
-	pub async fn create(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		taskprogress_c: TaskProgressForCreate,
-	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, taskprogress_c).await
+	async fn after_create(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::mark_task_done_if_complete(ctx, mm, id).await
 	}
 
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<TaskProgress> {
-		base::get::<Self, _>(ctx, mm, id).await
+	async fn after_update(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::mark_task_done_if_complete(ctx, mm, id).await
 	}
+}
 
-	pub async fn list(
-		ctx: &Ctx,
-		mm: &ModelManager,
-		filter: Option<Vec<TaskProgressFilter>>,
-		list_options: Option<ListOptions>,
-	) -> Result<Vec<TaskProgress>> {
-		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
-	}
+generate_common_bmc_fns!(
+	Bmc: TaskProgressBmc,
+	Entity: TaskProgress,
+	ForCreate: TaskProgressForCreate,
+	ForUpdate: TaskProgressForUpdate,
+	Filter: TaskProgressFilter,
+);
 
-	pub async fn update(
+impl TaskProgressBmc {
+	/// Marks the task of this taskprogress as done when the progress reached
+	/// `PROGRESS_DONE`. (Called from the `DbBmc` hooks, so, in the same transaction)
+	async fn mark_task_done_if_complete(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		id: i64,
-		taskprogress_u: TaskProgressForUpdate,
 	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, taskprogress_u).await
-	}
+		let taskprogress = Self::get(ctx, mm, id).await?;
 
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
+		if taskprogress.progress >= PROGRESS_DONE {
+			let task_u = TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			};
+			TaskBmc::update(ctx, mm, taskprogress.task_id, task_u).await?;
+		}
+
+		Ok(())
 	}
 }
 // endregion: --- TaskBmc
@@ -111,6 +111,7 @@ mod tests {
 	use crate::model::Error;
 	use anyhow::Result;
 	use lib_utils::time::{format_time, now_utc};
+	use modql::filter::ListOptions;
 	use serde_json::json;
 	use serial_test::serial;
 	use std::time::Duration;
@@ -368,6 +369,51 @@ mod tests {
 		Ok(())
 	}
 
+	#[serial]
+	#[tokio::test]
+	async fn test_update_progress_done_marks_task_done() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_progress_done_marks_task_done project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_update_progress_done_marks_task_done task",
+		)
+		.await?;
+		let fx_taskprogress =
+			_dev_utils::seed_taskprogresses(&ctx, &mm, fx_task_id, &[80])
+				.await?
+				.remove(0);
+
+		// -- Exec
+		let task_before = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		TaskProgressBmc::update(
+			&ctx,
+			&mm,
+			fx_taskprogress.id,
+			TaskProgressForUpdate { progress: 100 },
+		)
+		.await?;
+
+		// -- Check
+		assert!(!task_before.done, "task should not be done before");
+		let task = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		assert!(task.done, "task should be done after progress 100");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
 	#[serial]
 	#[tokio::test]
 	async fn test_list_by_ctime_ok() -> Result<()> {
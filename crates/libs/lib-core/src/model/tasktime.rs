@@ -1,123 +1,159 @@
 use crate::ctx::Ctx;
-use crate::model::base::{self, DbBmc};
+use crate::model::anomaly::{AnomalyBmc, AnomalyEntity};
+use crate::model::base::DbBmc;
+use crate::model::invoice::InvoiceBmc;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::period_lock::PeriodLockBmc;
 use crate::model::ModelManager;
-use crate::model::Result;
+use async_trait::async_trait;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{
-	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
-};
+use modql::filter::{OpValsBool, OpValsInt64, OpValsString};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:    --- TaskTime Types
-#[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTime {
 	pub id: i64,
 	pub task_id: i64,
 
 	pub comment: String,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub start_time: OffsetDateTime,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub stop_time: OffsetDateTime,
+	/// Whether the time is invoiced to the project client (see `model::invoice`).
+	pub billable: bool,
 
 	// -- Timestamps
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub mtime: OffsetDateTime,
 }
 
-#[serde_as]
-#[derive(Fields, Deserialize)]
+#[serde_as(schemars = false)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTimeForCreate {
 	pub task_id: i64,
 	pub comment: String,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub start_time: Option<OffsetDateTime>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub stop_time: Option<OffsetDateTime>,
+	/// Defaults to `true`.
+	#[serde(default)]
+	pub billable: Option<bool>,
 }
 
-#[serde_as]
-#[derive(Fields, Deserialize, Default)]
+#[serde_as(schemars = false)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TaskTimeForUpdate {
 	pub comment: Option<String>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub start_time: Option<OffsetDateTime>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub stop_time: Option<OffsetDateTime>,
+	pub billable: Option<bool>,
 }
 
-#[derive(FilterNodes, Deserialize, Default, Debug)]
-pub struct TaskTimeFilter {
-	id: Option<OpValsInt64>,
-	task_id: Option<OpValsInt64>,
-	comment: Option<OpValsString>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	start_time: Option<OpValsValue>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	stop_time: Option<OpValsValue>,
-
-	cid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
-}
+generate_filter!(
+	TaskTimeFilter {
+		task_id: OpValsInt64,
+		comment: OpValsString,
+		billable: OpValsBool,
+	},
+	Times: [start_time, stop_time],
+);
 // endregion: --- TaskTime Types
 
 // region:    --- TaskTimeBmc
 pub struct TaskTimeBmc;
 
+#[async_trait]
 impl DbBmc for TaskTimeBmc {
 	const TABLE: &'static str = "tasktime";
-}
 
-impl TaskTimeBmc {
-	pub async fn create(
+	async fn after_create(
 		ctx: &Ctx,
 		mm: &ModelManager,
-		tasktime_c: TaskTimeForCreate,
-	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, tasktime_c).await
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await?;
+		AnomalyBmc::reject_anomalies(ctx, mm, AnomalyEntity::TaskTime, id).await
 	}
 
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<TaskTime> {
-		base::get::<Self, _>(ctx, mm, id).await
+	async fn before_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		_fields: &mut Fields,
+	) -> crate::model::Result<()> {
+		InvoiceBmc::check_tasktime_not_invoiced(mm, id).await?;
+		check_unlocked(ctx, mm, id).await
 	}
 
-	pub async fn list(
+	async fn after_update(
 		ctx: &Ctx,
 		mm: &ModelManager,
-		filter: Option<Vec<TaskTimeFilter>>,
-		list_options: Option<ListOptions>,
-	) -> Result<Vec<TaskTime>> {
-		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await?;
+		AnomalyBmc::reject_anomalies(ctx, mm, AnomalyEntity::TaskTime, id).await
 	}
 
-	pub async fn update(
+	async fn before_delete(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		id: i64,
-		tasktime_u: TaskTimeForUpdate,
-	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, tasktime_u).await
+	) -> crate::model::Result<()> {
+		InvoiceBmc::check_tasktime_not_invoiced(mm, id).await?;
+		check_unlocked(ctx, mm, id).await
 	}
+}
 
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
-	}
+/// Fails if the tasktime `id` is in a locked period (see `model::period_lock`).
+async fn check_unlocked(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+) -> crate::model::Result<()> {
+	let entity: TaskTime = TaskTimeBmc::get(ctx, mm, id).await?;
+
+	PeriodLockBmc::check_unlocked(
+		ctx,
+		mm,
+		entity.cid,
+		entity.start_time,
+		entity.stop_time,
+	)
+	.await
 }
+
+generate_common_bmc_fns!(
+	Bmc: TaskTimeBmc,
+	Entity: TaskTime,
+	ForCreate: TaskTimeForCreate,
+	ForUpdate: TaskTimeForUpdate,
+	Filter: TaskTimeFilter,
+);
 // endregion: --- TaskTimeBmc
 
 // region: --- TaskTimePreFormat
@@ -140,6 +176,7 @@ mod tests {
 	use crate::model::Error;
 	use anyhow::Result;
 	use lib_utils::time::{format_time, now_utc};
+	use modql::filter::ListOptions;
 	use modql::filter::OpValString;
 	use serde_json::json;
 	use serial_test::serial;
@@ -173,12 +210,14 @@ mod tests {
 			comment: fx_comment.to_string(),
 			start_time: Some(now_utc()),
 			stop_time: Some(now_utc()),
+			billable: None,
 		};
 		let id = TaskTimeBmc::create(&ctx, &mm, tasktime_c).await?;
 
 		// -- Check
 		let tasktime = TaskTimeBmc::get(&ctx, &mm, id).await?;
 		assert_eq!(tasktime.comment, fx_comment);
+		assert!(tasktime.billable);
 
 		// -- Clean
 		TaskTimeBmc::delete(&ctx, &mm, id).await?;
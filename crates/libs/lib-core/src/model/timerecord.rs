@@ -1,104 +1,213 @@
 use crate::ctx::Ctx;
+use crate::model::anomaly::{AnomalyBmc, AnomalyEntity};
 use crate::model::base::{self, DbBmc};
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::period_lock::PeriodLockBmc;
+use crate::model::place::{place_name, PlaceBmc};
 use crate::model::ModelManager;
-use crate::model::Result;
+use async_trait::async_trait;
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
-use modql::filter::{
-	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
-};
+use modql::filter::{OpValsInt64, OpValsString};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
 
 // region:    --- TimeRecord Types
-#[serde_as]
-#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
 pub struct TimeRecord {
 	pub id: i64,
+	/// The place name (see `PlaceBmc`).
 	pub place: String,
+	pub place_id: Option<i64>,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub start_time: OffsetDateTime,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub stop_time: OffsetDateTime,
 
 	// -- Timestamps
 	//    (creator and last modified user_id/time)
 	pub cid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub ctime: OffsetDateTime,
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
 	pub mtime: OffsetDateTime,
 }
 
-#[serde_as]
-#[derive(Fields, Deserialize)]
+#[serde_as(schemars = false)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
 pub struct TimeRecordForCreate {
+	/// The place name, resolved to (or creating) the `place_id`
+	/// when not given.
+	#[serde(default)]
 	pub place: String,
+	pub place_id: Option<i64>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub start_time: Option<OffsetDateTime>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub stop_time: Option<OffsetDateTime>,
 }
 
-#[serde_as]
-#[derive(Fields, Deserialize, Default)]
+#[serde_as(schemars = false)]
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TimeRecordForUpdate {
 	pub place: Option<String>,
+	pub place_id: Option<i64>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
 	pub start_time: Option<OffsetDateTime>,
 	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub stop_time: Option<OffsetDateTime>,
+}
+
+/// The `TimeRecordForCreate` with the place resolved
+/// (see `TimeRecordBmc::resolve_place`).
+#[derive(Fields)]
+struct TimeRecordForCreateInner {
+	pub place: String,
+	pub place_id: Option<i64>,
+	pub start_time: Option<OffsetDateTime>,
 	pub stop_time: Option<OffsetDateTime>,
 }
 
-#[derive(FilterNodes, Deserialize, Default, Debug)]
-pub struct TimeRecordFilter {
-	id: Option<OpValsInt64>,
-	place: Option<OpValsString>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	start_time: Option<OpValsValue>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	stop_time: Option<OpValsValue>,
-
-	cid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	ctime: Option<OpValsValue>,
-	mid: Option<OpValsInt64>,
-	#[modql(to_sea_value_fn = "time_to_sea_value")]
-	mtime: Option<OpValsValue>,
+#[derive(Fields)]
+struct TimeRecordForUpdateInner {
+	pub place: Option<String>,
+	pub place_id: Option<i64>,
+	pub start_time: Option<OffsetDateTime>,
+	pub stop_time: Option<OffsetDateTime>,
 }
+
+generate_filter!(
+	TimeRecordFilter {
+		place: OpValsString,
+		place_id: OpValsInt64,
+	},
+	Times: [start_time, stop_time],
+);
 // endregion: --- TimeRecord Types
 
 // region:    --- TimeRecordBmc
 pub struct TimeRecordBmc;
 
+#[async_trait]
 impl DbBmc for TimeRecordBmc {
 	const TABLE: &'static str = "timerecord";
+
+	async fn after_create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await?;
+		AnomalyBmc::reject_anomalies(ctx, mm, AnomalyEntity::TimeRecord, id).await
+	}
+
+	async fn before_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		_fields: &mut Fields,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await
+	}
+
+	async fn after_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await?;
+		AnomalyBmc::reject_anomalies(ctx, mm, AnomalyEntity::TimeRecord, id).await
+	}
+
+	async fn before_delete(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_unlocked(ctx, mm, id).await
+	}
 }
 
+/// Fails if the timerecord `id` is in a locked period (see `model::period_lock`).
+async fn check_unlocked(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+) -> crate::model::Result<()> {
+	let entity: TimeRecord = TimeRecordBmc::get(ctx, mm, id).await?;
+
+	PeriodLockBmc::check_unlocked(
+		ctx,
+		mm,
+		entity.cid,
+		entity.start_time,
+		entity.stop_time,
+	)
+	.await
+}
+
+generate_common_bmc_fns!(
+	Bmc: TimeRecordBmc,
+	Entity: TimeRecord,
+	ForCreate: TimeRecordForCreate,
+	ForUpdate: TimeRecordForUpdate,
+	Filter: TimeRecordFilter,
+	Fns: [get, list, list_stream, delete, get_many, delete_many],
+);
+
+// Note: `create`, `update` (and their bulk versions) are overridden
+//       to resolve the place (see `resolve_place`), in the same transaction.
 impl TimeRecordBmc {
 	pub async fn create(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		timerecord_c: TimeRecordForCreate,
-	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, timerecord_c).await
-	}
+	) -> crate::model::Result<i64> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let timerecord_c = Self::create_inner(ctx, &mm, timerecord_c).await?;
+			base::create::<Self, _>(ctx, &mm, timerecord_c).await
+		}
+		.await;
 
-	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<TimeRecord> {
-		base::get::<Self, _>(ctx, mm, id).await
+		mm.end_txn(res).await
 	}
 
-	pub async fn list(
+	pub async fn create_many(
 		ctx: &Ctx,
 		mm: &ModelManager,
-		filter: Option<Vec<TimeRecordFilter>>,
-		list_options: Option<ListOptions>,
-	) -> Result<Vec<TimeRecord>> {
-		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+		timerecords_c: Vec<TimeRecordForCreate>,
+	) -> crate::model::Result<Vec<i64>> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let mut timerecords_inner = Vec::with_capacity(timerecords_c.len());
+			for timerecord_c in timerecords_c {
+				timerecords_inner
+					.push(Self::create_inner(ctx, &mm, timerecord_c).await?);
+			}
+			base::create_many::<Self, _>(ctx, &mm, timerecords_inner).await
+		}
+		.await;
+
+		mm.end_txn(res).await
 	}
 
 	pub async fn update(
@@ -106,12 +215,107 @@ impl TimeRecordBmc {
 		mm: &ModelManager,
 		id: i64,
 		timerecord_u: TimeRecordForUpdate,
-	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, timerecord_u).await
+	) -> crate::model::Result<()> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let timerecord_u = Self::update_inner(ctx, &mm, timerecord_u).await?;
+			base::update::<Self, _>(ctx, &mm, id, timerecord_u).await
+		}
+		.await;
+
+		mm.end_txn(res).await
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timerecords_u: Vec<(i64, TimeRecordForUpdate)>,
+	) -> crate::model::Result<()> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let mut timerecords_inner = Vec::with_capacity(timerecords_u.len());
+			for (id, timerecord_u) in timerecords_u {
+				let timerecord_u =
+					Self::update_inner(ctx, &mm, timerecord_u).await?;
+				timerecords_inner.push((id, timerecord_u));
+			}
+			base::update_many::<Self, _>(ctx, &mm, timerecords_inner).await
+		}
+		.await;
+
+		mm.end_txn(res).await
+	}
+
+	async fn create_inner(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timerecord_c: TimeRecordForCreate,
+	) -> crate::model::Result<TimeRecordForCreateInner> {
+		let TimeRecordForCreate {
+			place,
+			place_id,
+			start_time,
+			stop_time,
+		} = timerecord_c;
+		let (place, place_id) =
+			Self::resolve_place(ctx, mm, Some(place), place_id).await?;
+
+		Ok(TimeRecordForCreateInner {
+			place: place.unwrap_or_default(),
+			place_id,
+			start_time,
+			stop_time,
+		})
+	}
+
+	async fn update_inner(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timerecord_u: TimeRecordForUpdate,
+	) -> crate::model::Result<TimeRecordForUpdateInner> {
+		let TimeRecordForUpdate {
+			place,
+			place_id,
+			start_time,
+			stop_time,
+		} = timerecord_u;
+		let (place, place_id) =
+			Self::resolve_place(ctx, mm, place, place_id).await?;
+
+		Ok(TimeRecordForUpdateInner {
+			place,
+			place_id,
+			start_time,
+			stop_time,
+		})
 	}
 
-	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
+	/// Resolves the place of a timerecord write:
+	/// - a `place_id` gives the place name,
+	/// - else, a (non blank) `place` name gives the place,
+	///   created if none (see `PlaceBmc::get_or_create_by_name`).
+	async fn resolve_place(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		place: Option<String>,
+		place_id: Option<i64>,
+	) -> crate::model::Result<(Option<String>, Option<i64>)> {
+		if let Some(place_id) = place_id {
+			let place = PlaceBmc::get(ctx, mm, place_id).await?;
+			return Ok((Some(place.name), Some(place.id)));
+		}
+
+		match place {
+			Some(name) if !place_name(&name).is_empty() => {
+				let place = PlaceBmc::get_or_create_by_name(ctx, mm, &name).await?;
+				Ok((Some(place.name), Some(place.id)))
+			}
+			place => Ok((place, None)),
+		}
 	}
 }
 // endregion: --- TimeRecordBmc
@@ -135,6 +339,7 @@ mod tests {
 	use crate::{_dev_utils, model::tasktime::TaskTimeBmc};
 	use anyhow::Result;
 	use lib_utils::time::{format_time, now_utc};
+	use modql::filter::ListOptions;
 	use modql::filter::OpValString;
 	use serde_json::json;
 	use serial_test::serial;
@@ -152,6 +357,7 @@ mod tests {
 		// -- Exec
 		let timerecord_c = TimeRecordForCreate {
 			place: fx_place.to_string(),
+			place_id: None,
 			start_time: Some(now_utc()),
 			stop_time: Some(now_utc()),
 		};
@@ -167,6 +373,36 @@ mod tests {
 		Ok(())
 	}
 
+	#[serial]
+	#[tokio::test]
+	async fn test_create_same_place_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_places = &[
+			"test_create_same_place_ok Office",
+			" test_create_same_place_ok  office ",
+		];
+
+		// -- Exec
+		let timerecords = _dev_utils::seed_timerecords(&ctx, &mm, fx_places).await?;
+
+		// -- Check
+		assert!(timerecords[0].place_id.is_some(), "place_id should be set");
+		assert_eq!(timerecords[0].place_id, timerecords[1].place_id);
+		assert_eq!(timerecords[1].place, "test_create_same_place_ok Office");
+
+		// -- Clean
+		for timerecord in timerecords.iter() {
+			TimeRecordBmc::delete(&ctx, &mm, timerecord.id).await?;
+		}
+		if let Some(place_id) = timerecords[0].place_id {
+			PlaceBmc::delete(&ctx, &mm, place_id).await?;
+		}
+
+		Ok(())
+	}
+
 	#[serial]
 	#[tokio::test]
 	async fn test_get_err_not_found() -> Result<()> {
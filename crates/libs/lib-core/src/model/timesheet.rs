@@ -0,0 +1,902 @@
+//! Timesheets, i.e., the entries of a user period (e.g., a week) submitted
+//! to their team lead for approval.
+//!
+//! Design:
+//!
+//! - A timesheet covers the `[start_time, stop_time)` of its user, and goes
+//!   `draft` -> `submitted` -> `approved` or `rejected` (a rejected one being
+//!   submitted again once fixed).
+//! - The user submits it to their approver (see `UserBmc::set_approver`),
+//!   who is the only one to approve or reject it.
+//! - Approving it locks its period for the user (see `model::period_lock`),
+//!   so its `TaskTime`s and `TimeRecord`s can no longer change.
+//! - Each submit, approve, and reject records a `TimesheetComment`
+//!   (required to reject), i.e., the review history.
+//! - Only a `draft` or `rejected` timesheet can be deleted, and only by its user.
+//! - A timesheet is only visible (`get`, `list`) to its user and its approver.
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, DbBmc};
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::period_lock::PeriodLockBmc;
+use crate::model::tasktime::{TaskTime, TaskTimeBmc, TaskTimeFilter};
+use crate::model::timerecord::{TimeRecord, TimeRecordBmc, TimeRecordFilter};
+use crate::model::user::{User, UserBmc};
+use crate::model::{EntityStream, Error, ModelManager};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lib_utils::time::{format_time, now_utc, Rfc3339};
+use modql::field::Fields;
+use modql::filter::{
+	FilterGroup, FilterGroups, FilterNode, ListOptions, OpVal, OpValInt64,
+	OpValsInt64, OpValsString,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Timesheet Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimesheetStatus {
+	Draft,
+	Submitted,
+	Approved,
+	Rejected,
+}
+
+impl TimesheetStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TimesheetStatus::Draft => "draft",
+			TimesheetStatus::Submitted => "submitted",
+			TimesheetStatus::Approved => "approved",
+			TimesheetStatus::Rejected => "rejected",
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Timesheet {
+	pub id: i64,
+	pub user_id: i64,
+	/// The user approver, once submitted.
+	pub approver_id: Option<i64>,
+	/// The period lock, once approved.
+	pub lock_id: Option<i64>,
+
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+	/// The `TimesheetStatus` (e.g., "submitted").
+	pub status: String,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub submitted_at: Option<OffsetDateTime>,
+	pub reviewed_by: Option<i64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub reviewed_at: Option<OffsetDateTime>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl Timesheet {
+	fn has_status(&self, status: TimesheetStatus) -> bool {
+		self.status == status.as_str()
+	}
+
+	/// Returns true if the ctx user is the timesheet user or approver.
+	fn is_visible_to(&self, ctx: &Ctx) -> bool {
+		self.user_id == ctx.user_id() || self.approver_id == Some(ctx.user_id())
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct TimesheetForCreate {
+	/// e.g., the Monday midnight of the week.
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+}
+
+/// The `TimesheetForCreate` of the ctx user.
+#[derive(Fields)]
+struct TimesheetForCreateInner {
+	user_id: i64,
+	start_time: OffsetDateTime,
+	stop_time: OffsetDateTime,
+	status: String,
+}
+
+#[derive(Fields)]
+struct TimesheetForSubmit {
+	approver_id: i64,
+	status: String,
+	submitted_at: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct TimesheetForReview {
+	lock_id: Option<i64>,
+	status: String,
+	reviewed_by: i64,
+	reviewed_at: OffsetDateTime,
+}
+
+generate_filter!(
+	TimesheetFilter {
+		user_id: OpValsInt64,
+		approver_id: OpValsInt64,
+		status: OpValsString,
+	},
+	Times: [start_time, stop_time, submitted_at, reviewed_at],
+);
+
+/// A submitted timesheet, as listed to its approver.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PendingTimesheet {
+	#[serde(flatten)]
+	pub timesheet: Timesheet,
+	pub username: String,
+	/// The tasktimes time within the period.
+	pub worked_sec: i64,
+	/// The timerecords time within the period.
+	pub presence_sec: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimesheetAction {
+	Submit,
+	Approve,
+	Reject,
+}
+
+impl TimesheetAction {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TimesheetAction::Submit => "submit",
+			TimesheetAction::Approve => "approve",
+			TimesheetAction::Reject => "reject",
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct TimesheetComment {
+	pub id: i64,
+	pub timesheet_id: i64,
+
+	/// The `TimesheetAction` (e.g., "reject").
+	pub action: String,
+	pub text: String,
+
+	// -- Timestamps
+	//    (the author user_id and time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct TimesheetCommentForCreate {
+	timesheet_id: i64,
+	action: String,
+	text: String,
+}
+
+generate_filter!(TimesheetCommentFilter {
+	timesheet_id: OpValsInt64,
+	action: OpValsString,
+});
+
+// endregion: --- Timesheet Types
+
+// region:    --- TimesheetBmc
+pub struct TimesheetBmc;
+
+/// Note: Only a `draft` or `rejected` timesheet can be deleted
+///       (e.g., not an approved one, which keeps its period locked),
+///       and only by its user.
+#[async_trait]
+impl DbBmc for TimesheetBmc {
+	const TABLE: &'static str = "timesheet";
+
+	async fn before_delete(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		let timesheet = Self::get(ctx, mm, id).await?;
+		check_owner(ctx, &timesheet)?;
+		check_status(
+			&timesheet,
+			&[TimesheetStatus::Draft, TimesheetStatus::Rejected],
+			"delete",
+		)
+	}
+}
+
+generate_common_bmc_fns!(
+	Bmc: TimesheetBmc,
+	Entity: Timesheet,
+	ForCreate: TimesheetForCreate,
+	ForUpdate: TimesheetForReview,
+	Filter: TimesheetFilter,
+	Fns: [delete],
+);
+
+// Note: `create` is overridden (the timesheet of the ctx user), `get` and
+//       `list` only return the timesheets visible to the ctx user
+//       (see module doc), and the timesheets are only updated by `submit`,
+//       `approve`, and `reject`.
+impl TimesheetBmc {
+	/// Fails with `Error::EntityNotFound` if the timesheet is not visible
+	/// to the ctx user (i.e., neither its user nor its approver).
+	pub async fn get(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<Timesheet> {
+		let timesheet: Timesheet = base::get::<Self, _>(ctx, mm, id).await?;
+		if !timesheet.is_visible_to(ctx) {
+			return Err(Error::EntityNotFound {
+				entity: Self::TABLE,
+				id,
+			});
+		}
+
+		Ok(timesheet)
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TimesheetFilter>>,
+		list_options: Option<ListOptions>,
+	) -> crate::model::Result<Vec<Timesheet>> {
+		let filter = visible_filter(ctx, filter);
+		base::list::<Self, _, _>(ctx, mm, Some(filter), list_options).await
+	}
+
+	pub fn list_stream(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TimesheetFilter>>,
+		list_options: Option<ListOptions>,
+	) -> crate::model::Result<EntityStream<Timesheet>> {
+		let filter = visible_filter(ctx, filter);
+		base::list_stream::<Self, _, _>(ctx, mm, Some(filter), list_options)
+	}
+
+	/// Creates the `draft` timesheet of the period, for the ctx user.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timesheet_c: TimesheetForCreate,
+	) -> crate::model::Result<i64> {
+		let TimesheetForCreate {
+			start_time,
+			stop_time,
+		} = timesheet_c;
+		if stop_time <= start_time {
+			return Err(Error::TimesheetPeriodInvalid {
+				start_time: format_time(start_time),
+				stop_time: format_time(stop_time),
+			});
+		}
+
+		let timesheet_c = TimesheetForCreateInner {
+			user_id: ctx.user_id(),
+			start_time,
+			stop_time,
+			status: TimesheetStatus::Draft.as_str().to_string(),
+		};
+		base::create::<Self, _>(ctx, mm, timesheet_c).await
+	}
+
+	/// Submits the `draft` (or `rejected`) timesheet of the ctx user
+	/// to their approver.
+	pub async fn submit(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		comment: String,
+	) -> crate::model::Result<()> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let timesheet = Self::get(ctx, &mm, id).await?;
+			check_owner(ctx, &timesheet)?;
+			check_status(
+				&timesheet,
+				&[TimesheetStatus::Draft, TimesheetStatus::Rejected],
+				TimesheetAction::Submit.as_str(),
+			)?;
+			let user: User = UserBmc::get(ctx, &mm, timesheet.user_id).await?;
+			let approver_id =
+				user.approver_id.ok_or(Error::TimesheetApproverMissing {
+					user_id: timesheet.user_id,
+				})?;
+
+			let timesheet_u = TimesheetForSubmit {
+				approver_id,
+				status: TimesheetStatus::Submitted.as_str().to_string(),
+				submitted_at: now_utc(),
+			};
+			base::update::<Self, _>(ctx, &mm, id, timesheet_u).await?;
+			TimesheetCommentBmc::create(
+				ctx,
+				&mm,
+				id,
+				TimesheetAction::Submit,
+				comment,
+			)
+			.await?;
+
+			Ok(())
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Approves the `submitted` timesheet (its approver only),
+	/// which locks its period for its user.
+	pub async fn approve(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		comment: String,
+	) -> crate::model::Result<()> {
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			let timesheet =
+				Self::get_for_review(ctx, &mm, id, TimesheetAction::Approve).await?;
+			let lock_id = PeriodLockBmc::lock(
+				ctx,
+				&mm,
+				Some(timesheet.user_id),
+				timesheet.start_time,
+				timesheet.stop_time,
+				format!("timesheet {id} approved"),
+			)
+			.await?;
+
+			let timesheet_u = TimesheetForReview {
+				lock_id: Some(lock_id),
+				status: TimesheetStatus::Approved.as_str().to_string(),
+				reviewed_by: ctx.user_id(),
+				reviewed_at: now_utc(),
+			};
+			base::update::<Self, _>(ctx, &mm, id, timesheet_u).await?;
+			TimesheetCommentBmc::create(
+				ctx,
+				&mm,
+				id,
+				TimesheetAction::Approve,
+				comment,
+			)
+			.await?;
+
+			Ok(())
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Rejects the `submitted` timesheet (its approver only),
+	/// with the required `comment` (e.g., what to fix).
+	pub async fn reject(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		comment: String,
+	) -> crate::model::Result<()> {
+		if comment.trim().is_empty() {
+			return Err(Error::TimesheetCommentRequired { id });
+		}
+
+		let mm = mm.new_with_txn();
+		mm.begin_txn().await?;
+
+		let res = async {
+			Self::get_for_review(ctx, &mm, id, TimesheetAction::Reject).await?;
+
+			let timesheet_u = TimesheetForReview {
+				lock_id: None,
+				status: TimesheetStatus::Rejected.as_str().to_string(),
+				reviewed_by: ctx.user_id(),
+				reviewed_at: now_utc(),
+			};
+			base::update::<Self, _>(ctx, &mm, id, timesheet_u).await?;
+			TimesheetCommentBmc::create(
+				ctx,
+				&mm,
+				id,
+				TimesheetAction::Reject,
+				comment,
+			)
+			.await?;
+
+			Ok(())
+		}
+		.await;
+
+		// -- Commit the transaction (or rollback on error)
+		mm.end_txn(res).await
+	}
+
+	/// Returns the `submitted` timesheets the ctx user is the approver of,
+	/// by period, with their user worked and presence times.
+	pub async fn list_pending(
+		ctx: &Ctx,
+		mm: &ModelManager,
+	) -> crate::model::Result<Vec<PendingTimesheet>> {
+		let filter: TimesheetFilter = serde_json::from_value(json!({
+			"approver_id": ctx.user_id(),
+			"status": TimesheetStatus::Submitted.as_str(),
+		}))?;
+		let list_options = ListOptions {
+			order_bys: Some("start_time".into()),
+			..Default::default()
+		};
+		let timesheets: Vec<Timesheet> =
+			Self::list_stream(ctx, mm, Some(vec![filter]), Some(list_options))?
+				.try_collect()
+				.await?;
+
+		let mut pendings = Vec::with_capacity(timesheets.len());
+		for timesheet in timesheets {
+			let user: User = UserBmc::get(ctx, mm, timesheet.user_id).await?;
+			let (worked_sec, presence_sec) =
+				Self::entries_sec(ctx, mm, &timesheet).await?;
+			pendings.push(PendingTimesheet {
+				timesheet,
+				username: user.username,
+				worked_sec,
+				presence_sec,
+			});
+		}
+
+		Ok(pendings)
+	}
+
+	/// Returns the `submitted` timesheet `id`, if the ctx user is its approver.
+	async fn get_for_review(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		action: TimesheetAction,
+	) -> crate::model::Result<Timesheet> {
+		let timesheet = Self::get(ctx, mm, id).await?;
+		check_status(&timesheet, &[TimesheetStatus::Submitted], action.as_str())?;
+		if timesheet.approver_id != Some(ctx.user_id()) {
+			return Err(Error::TimesheetNotApprover { id });
+		}
+
+		Ok(timesheet)
+	}
+
+	/// Returns the tasktimes and timerecords times of the timesheet user,
+	/// clipped to its period.
+	async fn entries_sec(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timesheet: &Timesheet,
+	) -> crate::model::Result<(i64, i64)> {
+		let filter_json = json!({
+			"cid": timesheet.user_id,
+			"start_time": {"$lt": format_time(timesheet.stop_time)},
+			"stop_time": {"$gt": format_time(timesheet.start_time)},
+		});
+		let clipped_sec = |start: OffsetDateTime, stop: OffsetDateTime| {
+			let start = start.max(timesheet.start_time);
+			let stop = stop.min(timesheet.stop_time);
+			(stop - start).whole_seconds().max(0)
+		};
+
+		let filter: TaskTimeFilter = serde_json::from_value(filter_json.clone())?;
+		let tasktimes: Vec<TaskTime> =
+			TaskTimeBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let worked_sec = tasktimes
+			.iter()
+			.map(|tt| clipped_sec(tt.start_time, tt.stop_time))
+			.sum();
+
+		let filter: TimeRecordFilter = serde_json::from_value(filter_json)?;
+		let timerecords: Vec<TimeRecord> =
+			TimeRecordBmc::list_stream(ctx, mm, Some(vec![filter]), None)?
+				.try_collect()
+				.await?;
+		let presence_sec = timerecords
+			.iter()
+			.map(|tr| clipped_sec(tr.start_time, tr.stop_time))
+			.sum();
+
+		Ok((worked_sec, presence_sec))
+	}
+}
+
+/// Returns the `filter` groups, each restricted to the timesheets
+/// of the ctx user, or the ones it is the approver of.
+fn visible_filter(ctx: &Ctx, filter: Option<Vec<TimesheetFilter>>) -> FilterGroups {
+	let groups: Vec<Vec<FilterNode>> = match filter {
+		Some(filters) => filters
+			.into_iter()
+			.map(|filter| FilterGroup::from(filter).nodes().clone())
+			.collect(),
+		None => vec![Vec::new()],
+	};
+
+	let mut visible_groups = FilterGroups::from(Vec::<Vec<FilterNode>>::new());
+	for nodes in groups {
+		for user_prop in ["user_id", "approver_id"] {
+			let mut nodes = nodes.clone();
+			nodes.push(FilterNode::new(
+				user_prop,
+				vec![OpVal::Int64(OpValInt64::Eq(ctx.user_id()))],
+			));
+			visible_groups.add_group(nodes);
+		}
+	}
+
+	visible_groups
+}
+
+fn check_owner(ctx: &Ctx, timesheet: &Timesheet) -> crate::model::Result<()> {
+	if timesheet.user_id != ctx.user_id() {
+		return Err(Error::TimesheetNotOwner { id: timesheet.id });
+	}
+
+	Ok(())
+}
+
+fn check_status(
+	timesheet: &Timesheet,
+	allowed: &[TimesheetStatus],
+	action: &'static str,
+) -> crate::model::Result<()> {
+	if !allowed.iter().any(|status| timesheet.has_status(*status)) {
+		return Err(Error::TimesheetStatusInvalid {
+			id: timesheet.id,
+			status: timesheet.status.clone(),
+			action,
+		});
+	}
+
+	Ok(())
+}
+// endregion: --- TimesheetBmc
+
+// region:    --- TimesheetCommentBmc
+pub struct TimesheetCommentBmc;
+
+impl DbBmc for TimesheetCommentBmc {
+	const TABLE: &'static str = "timesheet_comment";
+}
+
+impl TimesheetCommentBmc {
+	/// Returns the comments of the timesheet `timesheet_id`, oldest first.
+	pub async fn list_for_timesheet(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timesheet_id: i64,
+	) -> crate::model::Result<Vec<TimesheetComment>> {
+		// (only visible with its timesheet)
+		TimesheetBmc::get(ctx, mm, timesheet_id).await?;
+
+		let filter: TimesheetCommentFilter =
+			serde_json::from_value(json!({"timesheet_id": timesheet_id}))?;
+		let list_options = ListOptions {
+			order_bys: Some("id".into()),
+			..Default::default()
+		};
+
+		base::list_stream::<Self, _, _>(
+			ctx,
+			mm,
+			Some(vec![filter]),
+			Some(list_options),
+		)?
+		.try_collect()
+		.await
+	}
+
+	async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		timesheet_id: i64,
+		action: TimesheetAction,
+		text: String,
+	) -> crate::model::Result<i64> {
+		let comment_c = TimesheetCommentForCreate {
+			timesheet_id,
+			action: action.as_str().to_string(),
+			text,
+		};
+
+		base::create::<Self, _>(ctx, mm, comment_c).await
+	}
+}
+// endregion: --- TimesheetCommentBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::tasktime::{TaskTimeForCreate, TaskTimeForUpdate};
+	use anyhow::{Context, Result};
+	use serial_test::serial;
+	use time::macros::datetime;
+	use time::Duration;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_submit_reject_approve_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(demo1.id)?;
+		UserBmc::set_approver(&root_ctx, &mm, demo1.id, Some(root_ctx.user_id()))
+			.await?;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_submit_reject_approve_ok")
+				.await?;
+		let fx_task_id = _dev_utils::seed_task(
+			&ctx,
+			&mm,
+			fx_project_id,
+			"test_submit_reject_approve_ok task",
+		)
+		.await?;
+		let fx_start = datetime!(2032-03-01 00:00 UTC);
+		let fx_stop = fx_start + Duration::weeks(1);
+		let fx_tasktime_id = TaskTimeBmc::create(
+			&ctx,
+			&mm,
+			TaskTimeForCreate {
+				task_id: fx_task_id,
+				comment: "test_submit_reject_approve_ok".to_string(),
+				start_time: Some(fx_start + Duration::hours(9)),
+				stop_time: Some(fx_start + Duration::hours(11)),
+				billable: None,
+			},
+		)
+		.await?;
+		let fx_id = TimesheetBmc::create(
+			&ctx,
+			&mm,
+			TimesheetForCreate {
+				start_time: fx_start,
+				stop_time: fx_stop,
+			},
+		)
+		.await?;
+
+		// -- Exec & Check - Submit & Reject
+		TimesheetBmc::submit(&ctx, &mm, fx_id, "week 10".to_string()).await?;
+		let pendings = TimesheetBmc::list_pending(&root_ctx, &mm).await?;
+		let pending = pendings
+			.iter()
+			.find(|pending| pending.timesheet.id == fx_id)
+			.context("Should be pending")?;
+		assert_eq!(pending.username, "demo1");
+		assert_eq!(pending.worked_sec, 2 * 3600);
+		let res = TimesheetBmc::approve(&ctx, &mm, fx_id, String::new()).await;
+		assert!(
+			matches!(res, Err(Error::TimesheetNotApprover { .. })),
+			"TimesheetNotApprover not matching"
+		);
+		let res = TimesheetBmc::reject(&root_ctx, &mm, fx_id, " ".to_string()).await;
+		assert!(
+			matches!(res, Err(Error::TimesheetCommentRequired { .. })),
+			"TimesheetCommentRequired not matching"
+		);
+		TimesheetBmc::reject(&root_ctx, &mm, fx_id, "missing friday".to_string())
+			.await?;
+
+		// -- Exec & Check - Resubmit & Approve
+		TimesheetBmc::submit(&ctx, &mm, fx_id, "fixed".to_string()).await?;
+		TimesheetBmc::approve(&root_ctx, &mm, fx_id, "ok".to_string()).await?;
+		let timesheet = TimesheetBmc::get(&ctx, &mm, fx_id).await?;
+		assert_eq!(timesheet.status, "approved");
+		assert_eq!(timesheet.reviewed_by, Some(root_ctx.user_id()));
+		assert!(timesheet.lock_id.is_some());
+		let comments =
+			TimesheetCommentBmc::list_for_timesheet(&ctx, &mm, fx_id).await?;
+		let actions: Vec<&str> =
+			comments.iter().map(|c| c.action.as_str()).collect();
+		assert_eq!(actions, ["submit", "reject", "submit", "approve"]);
+
+		// -- Check - Locked
+		let res = TaskTimeBmc::update(
+			&ctx,
+			&mm,
+			fx_tasktime_id,
+			TaskTimeForUpdate {
+				comment: Some("changed".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
+		assert!(
+			matches!(res, Err(Error::PeriodLocked { .. })),
+			"PeriodLocked not matching"
+		);
+		let res = TimesheetBmc::delete(&ctx, &mm, fx_id).await;
+		assert!(
+			matches!(res, Err(Error::TimesheetStatusInvalid { .. })),
+			"TimesheetStatusInvalid not matching"
+		);
+
+		// -- Clean
+		//    (the approved timesheet is kept, its period unlocked)
+		let lock_id = timesheet.lock_id.context("Should have lock_id")?;
+		PeriodLockBmc::unlock(&root_ctx, &mm, lock_id, "test clean".to_string())
+			.await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		UserBmc::set_approver(&root_ctx, &mm, demo1.id, None).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_other_user_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(demo1.id)?;
+		// (neither the timesheet user nor its approver)
+		let other_ctx = Ctx::new(demo1.id + 1_000)?;
+		UserBmc::set_approver(&root_ctx, &mm, demo1.id, Some(root_ctx.user_id()))
+			.await?;
+		let fx_start = datetime!(2032-05-03 00:00 UTC);
+		let fx_id = TimesheetBmc::create(
+			&ctx,
+			&mm,
+			TimesheetForCreate {
+				start_time: fx_start,
+				stop_time: fx_start + Duration::weeks(1),
+			},
+		)
+		.await?;
+		let is_not_found = |res: &crate::model::Result<()>| match res {
+			Err(Error::EntityNotFound { entity, id }) => {
+				*entity == "timesheet" && *id == fx_id
+			}
+			_ => false,
+		};
+
+		// -- Exec & Check - Draft (only visible to its user)
+		let res = TimesheetBmc::get(&root_ctx, &mm, fx_id).await.map(|_| ());
+		assert!(is_not_found(&res), "EntityNotFound not matching (approver)");
+		let res = TimesheetBmc::get(&other_ctx, &mm, fx_id).await.map(|_| ());
+		assert!(is_not_found(&res), "EntityNotFound not matching (get)");
+		let res = TimesheetBmc::delete(&other_ctx, &mm, fx_id).await;
+		assert!(is_not_found(&res), "EntityNotFound not matching (delete)");
+		let res = TimesheetBmc::submit(&other_ctx, &mm, fx_id, String::new()).await;
+		assert!(is_not_found(&res), "EntityNotFound not matching (submit)");
+
+		// -- Exec & Check - Submitted (visible to its approver too)
+		TimesheetBmc::submit(&ctx, &mm, fx_id, String::new()).await?;
+		let timesheet = TimesheetBmc::get(&root_ctx, &mm, fx_id).await?;
+		assert_eq!(timesheet.user_id, demo1.id);
+		let filter: TimesheetFilter =
+			serde_json::from_value(json!({"start_time": format_time(fx_start)}))?;
+		let timesheets =
+			TimesheetBmc::list(&root_ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(timesheets.len(), 1);
+		let filter: TimesheetFilter =
+			serde_json::from_value(json!({"start_time": format_time(fx_start)}))?;
+		let timesheets =
+			TimesheetBmc::list(&other_ctx, &mm, Some(vec![filter]), None).await?;
+		assert!(timesheets.is_empty(), "should not list other timesheets");
+		let res = TimesheetBmc::delete(&root_ctx, &mm, fx_id).await;
+		assert!(
+			matches!(res, Err(Error::TimesheetNotOwner { .. })),
+			"TimesheetNotOwner not matching (delete)"
+		);
+		let res = TimesheetBmc::submit(&root_ctx, &mm, fx_id, String::new()).await;
+		assert!(
+			matches!(res, Err(Error::TimesheetNotOwner { .. })),
+			"TimesheetNotOwner not matching (submit)"
+		);
+		let res = TimesheetBmc::approve(&other_ctx, &mm, fx_id, String::new()).await;
+		assert!(is_not_found(&res), "EntityNotFound not matching (approve)");
+		let res =
+			TimesheetBmc::reject(&other_ctx, &mm, fx_id, "no".to_string()).await;
+		assert!(is_not_found(&res), "EntityNotFound not matching (reject)");
+		let res = TimesheetCommentBmc::list_for_timesheet(&other_ctx, &mm, fx_id)
+			.await
+			.map(|_| ());
+		assert!(is_not_found(&res), "EntityNotFound not matching (comments)");
+
+		// -- Clean
+		TimesheetBmc::reject(&root_ctx, &mm, fx_id, "test clean".to_string())
+			.await?;
+		TimesheetBmc::delete(&ctx, &mm, fx_id).await?;
+		UserBmc::set_approver(&root_ctx, &mm, demo1.id, None).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_submit_err_approver_missing() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_start = datetime!(2032-04-05 00:00 UTC);
+		let fx_id = TimesheetBmc::create(
+			&ctx,
+			&mm,
+			TimesheetForCreate {
+				start_time: fx_start,
+				stop_time: fx_start + Duration::weeks(1),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let res = TimesheetBmc::submit(&ctx, &mm, fx_id, String::new()).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::TimesheetApproverMissing { user_id: 0 })),
+			"TimesheetApproverMissing not matching"
+		);
+
+		// -- Clean
+		TimesheetBmc::delete(&ctx, &mm, fx_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
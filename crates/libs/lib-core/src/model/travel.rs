@@ -0,0 +1,301 @@
+//! Travels between two places (e.g., a commute, or between two workplaces),
+//! with their mode and optional distance.
+//!
+//! (see `export::travel` for the monthly commute time and mileage report)
+
+use crate::ctx::Ctx;
+use crate::model::base;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::{Error, ModelManager};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{OpValsFloat64, OpValsInt64, OpValsString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Travel Types
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TravelMode {
+	Car,
+	Bike,
+	PublicTransport,
+	Walk,
+	Plane,
+	Other,
+}
+
+impl TravelMode {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TravelMode::Car => "car",
+			TravelMode::Bike => "bike",
+			TravelMode::PublicTransport => "public_transport",
+			TravelMode::Walk => "walk",
+			TravelMode::Plane => "plane",
+			TravelMode::Other => "other",
+		}
+	}
+}
+
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct Travel {
+	pub id: i64,
+	pub from_place_id: i64,
+	pub to_place_id: i64,
+
+	/// The `TravelMode` (e.g., "public_transport").
+	pub mode: String,
+	pub distance_km: Option<f64>,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct TravelForCreate {
+	pub from_place_id: i64,
+	pub to_place_id: i64,
+	pub mode: TravelMode,
+	pub distance_km: Option<f64>,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub start_time: OffsetDateTime,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub stop_time: OffsetDateTime,
+}
+
+#[serde_as(schemars = false)]
+#[derive(Serialize, Deserialize, JsonSchema, Default)]
+pub struct TravelForUpdate {
+	pub from_place_id: Option<i64>,
+	pub to_place_id: Option<i64>,
+	pub mode: Option<TravelMode>,
+	pub distance_km: Option<f64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub start_time: Option<OffsetDateTime>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[schemars(with = "Option<String>")]
+	pub stop_time: Option<OffsetDateTime>,
+}
+
+/// The `TravelForCreate` with the `mode` as stored.
+#[derive(Fields)]
+struct TravelForCreateInner {
+	pub from_place_id: i64,
+	pub to_place_id: i64,
+	pub mode: String,
+	pub distance_km: Option<f64>,
+	pub start_time: OffsetDateTime,
+	pub stop_time: OffsetDateTime,
+}
+
+#[derive(Fields)]
+struct TravelForUpdateInner {
+	pub from_place_id: Option<i64>,
+	pub to_place_id: Option<i64>,
+	pub mode: Option<String>,
+	pub distance_km: Option<f64>,
+	pub start_time: Option<OffsetDateTime>,
+	pub stop_time: Option<OffsetDateTime>,
+}
+
+generate_filter!(
+	TravelFilter {
+		from_place_id: OpValsInt64,
+		to_place_id: OpValsInt64,
+		mode: OpValsString,
+		distance_km: OpValsFloat64,
+	},
+	Times: [start_time, stop_time],
+);
+// endregion: --- Travel Types
+
+// region:    --- TravelBmc
+pub struct TravelBmc;
+
+generate_common_bmc_fns!(
+	Bmc: TravelBmc,
+	Table: "travel",
+	Entity: Travel,
+	ForCreate: TravelForCreate,
+	ForUpdate: TravelForUpdate,
+	Filter: TravelFilter,
+	Fns: [get, list, list_stream, delete, get_many, delete_many],
+);
+
+// Note: `create`, `update` (and their bulk versions) are overridden
+//       to store the `mode` as text (see `TravelForCreateInner`).
+impl TravelBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		travel_c: TravelForCreate,
+	) -> crate::model::Result<i64> {
+		base::create::<Self, _>(ctx, mm, travel_c.into_inner()?).await
+	}
+
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		travels_c: Vec<TravelForCreate>,
+	) -> crate::model::Result<Vec<i64>> {
+		let travels_c = travels_c
+			.into_iter()
+			.map(TravelForCreate::into_inner)
+			.collect::<crate::model::Result<Vec<_>>>()?;
+		base::create_many::<Self, _>(ctx, mm, travels_c).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		travel_u: TravelForUpdate,
+	) -> crate::model::Result<()> {
+		base::update::<Self, _>(ctx, mm, id, travel_u.into_inner()?).await
+	}
+
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		travels_u: Vec<(i64, TravelForUpdate)>,
+	) -> crate::model::Result<()> {
+		let travels_u = travels_u
+			.into_iter()
+			.map(|(id, travel_u)| Ok((id, travel_u.into_inner()?)))
+			.collect::<crate::model::Result<Vec<_>>>()?;
+		base::update_many::<Self, _>(ctx, mm, travels_u).await
+	}
+}
+
+fn check_distance(distance_km: Option<f64>) -> crate::model::Result<()> {
+	match distance_km {
+		Some(distance_km) if !distance_km.is_finite() || distance_km < 0. => {
+			Err(Error::TravelDistanceInvalid(distance_km))
+		}
+		_ => Ok(()),
+	}
+}
+
+impl TravelForCreate {
+	fn into_inner(self) -> crate::model::Result<TravelForCreateInner> {
+		check_distance(self.distance_km)?;
+
+		Ok(TravelForCreateInner {
+			from_place_id: self.from_place_id,
+			to_place_id: self.to_place_id,
+			mode: self.mode.as_str().to_string(),
+			distance_km: self.distance_km,
+			start_time: self.start_time,
+			stop_time: self.stop_time,
+		})
+	}
+}
+
+impl TravelForUpdate {
+	fn into_inner(self) -> crate::model::Result<TravelForUpdateInner> {
+		check_distance(self.distance_km)?;
+
+		Ok(TravelForUpdateInner {
+			from_place_id: self.from_place_id,
+			to_place_id: self.to_place_id,
+			mode: self.mode.map(|mode| mode.as_str().to_string()),
+			distance_km: self.distance_km,
+			start_time: self.start_time,
+			stop_time: self.stop_time,
+		})
+	}
+}
+// endregion: --- TravelBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::place::PlaceBmc;
+	use anyhow::Result;
+	use lib_utils::time::now_utc;
+	use serial_test::serial;
+	use time::Duration;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_home =
+			PlaceBmc::get_or_create_by_name(&ctx, &mm, "test_create_ok travel home")
+				.await?;
+		let fx_office = PlaceBmc::get_or_create_by_name(
+			&ctx,
+			&mm,
+			"test_create_ok travel office",
+		)
+		.await?;
+		let fx_start = now_utc();
+
+		// -- Exec
+		let travel_c = TravelForCreate {
+			from_place_id: fx_home.id,
+			to_place_id: fx_office.id,
+			mode: TravelMode::PublicTransport,
+			distance_km: Some(12.5),
+			start_time: fx_start,
+			stop_time: fx_start + Duration::minutes(35),
+		};
+		let id = TravelBmc::create(&ctx, &mm, travel_c).await?;
+
+		// -- Check
+		let travel = TravelBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(travel.mode, "public_transport");
+		assert_eq!(travel.distance_km, Some(12.5));
+
+		// -- Clean
+		// (the travel deleted by the place fk cascade)
+		PlaceBmc::delete(&ctx, &mm, fx_home.id).await?;
+		PlaceBmc::delete(&ctx, &mm, fx_office.id).await?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_distance_err_negative() -> Result<()> {
+		// -- Exec
+		let res = check_distance(Some(-1.));
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::TravelDistanceInvalid(_))),
+			"TravelDistanceInvalid not matching"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,7 +1,7 @@
 use crate::ctx::Ctx;
 use crate::model::base::{self, add_timestamps_for_update, DbBmc};
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
 use lib_auth::pwd::{self, ContentToHash};
 use modql::field::{Field, Fields, HasFields};
 use sea_query::{Expr, Iden, PostgresQueryBuilder, Query};
@@ -16,6 +16,9 @@ use uuid::Uuid;
 pub struct User {
 	pub id: i64,
 	pub username: String,
+	pub is_admin: bool,
+	/// The team lead approving the user timesheets (see `TimesheetBmc`).
+	pub approver_id: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -49,12 +52,22 @@ pub struct UserForAuth {
 	pub token_salt: Uuid,
 }
 
+#[derive(Clone, FromRow, Fields, Debug)]
+pub struct UserForCalendar {
+	pub id: i64,
+	pub username: String,
+
+	// -- calendar url token
+	pub calendar_token: Option<Uuid>,
+}
+
 /// Marker trait
 pub trait UserBy: HasFields + for<'r> FromRow<'r, PgRow> + Unpin + Send {}
 
 impl UserBy for User {}
 impl UserBy for UserForLogin {}
 impl UserBy for UserForAuth {}
+impl UserBy for UserForCalendar {}
 
 // Note: Since the entity properties Iden will be given by modql
 //       UserIden does not have to be exhaustive, but just have the columns
@@ -64,6 +77,8 @@ enum UserIden {
 	Id,
 	Username,
 	Pwd,
+	CalendarToken,
+	ApproverId,
 }
 // endregion: --- User Types
 
@@ -90,8 +105,6 @@ impl UserBmc {
 	where
 		E: UserBy,
 	{
-		let db = mm.db();
-
 		// -- Build query
 		let mut query = Query::select();
 		query
@@ -101,9 +114,8 @@ impl UserBmc {
 
 		// -- Execute query
 		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-		let entity = sqlx::query_as_with::<_, E, _>(&sql, values)
-			.fetch_optional(db)
-			.await?;
+		let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+		let entity = mm.dbx().fetch_optional(sqlx_query).await?;
 
 		Ok(entity)
 	}
@@ -114,8 +126,6 @@ impl UserBmc {
 		id: i64,
 		pwd_clear: &str,
 	) -> Result<()> {
-		let db = mm.db();
-
 		// -- Prep password
 		let user: UserForLogin = Self::get(ctx, mm, id).await?;
 		let pwd = pwd::hash_pwd(&ContentToHash {
@@ -137,13 +147,130 @@ impl UserBmc {
 
 		// -- Exec query
 		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-		let _count = sqlx::query_with(&sql, values)
-			.execute(db)
-			.await?
-			.rows_affected();
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let _count = mm.dbx().execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Returns the calendar token of the ctx user, creating it on first use.
+	pub async fn calendar_token(ctx: &Ctx, mm: &ModelManager) -> Result<Uuid> {
+		let user: UserForCalendar = Self::get(ctx, mm, ctx.user_id()).await?;
+
+		match user.calendar_token {
+			Some(token) => Ok(token),
+			None => Self::reset_calendar_token(ctx, mm).await,
+		}
+	}
+
+	/// Replaces the calendar token of the ctx user
+	/// (i.e., revokes the previous calendar url).
+	pub async fn reset_calendar_token(ctx: &Ctx, mm: &ModelManager) -> Result<Uuid> {
+		let token = Uuid::new_v4();
+
+		// -- Prep the data
+		let mut fields =
+			Fields::new(vec![Field::new(UserIden::CalendarToken, token.into())]);
+		add_timestamps_for_update(&mut fields, ctx.user_id());
+
+		// -- Build query
+		let fields = fields.for_sea_update();
+		let mut query = Query::update();
+		query
+			.table(Self::table_ref())
+			.values(fields)
+			.and_where(Expr::col(UserIden::Id).eq(ctx.user_id()));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = mm.dbx().execute(sqlx_query).await?;
+		if count == 0 {
+			return Err(Error::EntityNotFound {
+				entity: Self::TABLE,
+				id: ctx.user_id(),
+			});
+		}
+
+		Ok(token)
+	}
+
+	/// Returns whether the ctx user is an admin
+	/// (e.g., to lock the periods of all the users, see `PeriodLockBmc`).
+	pub async fn is_admin(ctx: &Ctx, mm: &ModelManager) -> Result<bool> {
+		let user: User = Self::get(ctx, mm, ctx.user_id()).await?;
+
+		Ok(user.is_admin)
+	}
+
+	/// Fails with `Error::AdminOnly` if the ctx user is not an admin.
+	pub async fn check_admin(ctx: &Ctx, mm: &ModelManager) -> Result<()> {
+		if !Self::is_admin(ctx, mm).await? {
+			return Err(Error::AdminOnly);
+		}
+
+		Ok(())
+	}
+
+	/// Sets the approver of the timesheets of `user_id` (admins only),
+	/// `None` for none.
+	pub async fn set_approver(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		user_id: i64,
+		approver_id: Option<i64>,
+	) -> Result<()> {
+		Self::check_admin(ctx, mm).await?;
+
+		// -- Prep the data
+		let mut fields =
+			Fields::new(vec![Field::new(UserIden::ApproverId, approver_id.into())]);
+		add_timestamps_for_update(&mut fields, ctx.user_id());
+
+		// -- Build query
+		let fields = fields.for_sea_update();
+		let mut query = Query::update();
+		query
+			.table(Self::table_ref())
+			.values(fields)
+			.and_where(Expr::col(UserIden::Id).eq(user_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = mm.dbx().execute(sqlx_query).await?;
+		if count == 0 {
+			return Err(Error::EntityNotFound {
+				entity: Self::TABLE,
+				id: user_id,
+			});
+		}
 
 		Ok(())
 	}
+
+	pub async fn first_by_calendar_token<E>(
+		_ctx: &Ctx,
+		mm: &ModelManager,
+		token: Uuid,
+	) -> Result<Option<E>>
+	where
+		E: UserBy,
+	{
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.columns(E::field_idens())
+			.and_where(Expr::col(UserIden::CalendarToken).eq(token));
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+		let entity = mm.dbx().fetch_optional(sqlx_query).await?;
+
+		Ok(entity)
+	}
 }
 
 // endregion: --- UserBmc
@@ -174,5 +301,54 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_is_admin_root_only() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let demo1: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let demo1_ctx = Ctx::new(demo1.id)?;
+
+		// -- Exec & Check
+		assert!(UserBmc::is_admin(&root_ctx, &mm).await?);
+		assert!(!UserBmc::is_admin(&demo1_ctx, &mm).await?);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_calendar_token_reset_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let root_ctx = Ctx::root_ctx();
+		let user: User = UserBmc::first_by_username(&root_ctx, &mm, "demo1")
+			.await?
+			.context("Should have user 'demo1'")?;
+		let ctx = Ctx::new(user.id)?;
+		let fx_token = UserBmc::calendar_token(&ctx, &mm).await?;
+
+		// -- Exec
+		let same_token = UserBmc::calendar_token(&ctx, &mm).await?;
+		let new_token = UserBmc::reset_calendar_token(&ctx, &mm).await?;
+
+		// -- Check
+		assert_eq!(same_token, fx_token);
+		assert_ne!(new_token, fx_token);
+		let old: Option<User> =
+			UserBmc::first_by_calendar_token(&root_ctx, &mm, fx_token).await?;
+		assert!(old.is_none(), "old token should be revoked");
+		let found: User =
+			UserBmc::first_by_calendar_token(&root_ctx, &mm, new_token)
+				.await?
+				.context("Should find user by new token")?;
+		assert_eq!(found.id, user.id);
+
+		Ok(())
+	}
 }
 // endregion: --- Tests
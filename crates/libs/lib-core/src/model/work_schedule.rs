@@ -0,0 +1,272 @@
+//! Work schedules, i.e., the contracted hours per weekday of the user,
+//! from their `valid_from` date up to the next schedule `valid_from`.
+//!
+//! (see `export::flex` for the flex-time balance against those hours)
+
+use crate::ctx::Ctx;
+use crate::model::base::DbBmc;
+use crate::model::macro_utils::{generate_common_bmc_fns, generate_filter};
+use crate::model::modql_utils::{date_to_sea_value, iso_date, time_to_sea_value};
+use crate::model::{Error, ModelManager};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{ListOptions, OpValsFloat64};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use time::{Date, Weekday};
+
+/// The max hours of a schedule weekday.
+const DAY_HOURS_MAX: f64 = 24.;
+
+// region:    --- WorkSchedule Types
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Fields, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct WorkSchedule {
+	pub id: i64,
+
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+	pub mon_hours: f64,
+	pub tue_hours: f64,
+	pub wed_hours: f64,
+	pub thu_hours: f64,
+	pub fri_hours: f64,
+	pub sat_hours: f64,
+	pub sun_hours: f64,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	#[schemars(with = "String")]
+	pub mtime: OffsetDateTime,
+}
+
+impl WorkSchedule {
+	/// Returns the contracted hours of the `weekday`.
+	pub fn hours(&self, weekday: Weekday) -> f64 {
+		match weekday {
+			Weekday::Monday => self.mon_hours,
+			Weekday::Tuesday => self.tue_hours,
+			Weekday::Wednesday => self.wed_hours,
+			Weekday::Thursday => self.thu_hours,
+			Weekday::Friday => self.fri_hours,
+			Weekday::Saturday => self.sat_hours,
+			Weekday::Sunday => self.sun_hours,
+		}
+	}
+}
+
+/// Note: The omitted weekday hours are 0.
+#[derive(Fields, Serialize, Deserialize, JsonSchema)]
+pub struct WorkScheduleForCreate {
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub valid_from: Date,
+	#[serde(default)]
+	pub mon_hours: f64,
+	#[serde(default)]
+	pub tue_hours: f64,
+	#[serde(default)]
+	pub wed_hours: f64,
+	#[serde(default)]
+	pub thu_hours: f64,
+	#[serde(default)]
+	pub fri_hours: f64,
+	#[serde(default)]
+	pub sat_hours: f64,
+	#[serde(default)]
+	pub sun_hours: f64,
+}
+
+#[derive(Fields, Serialize, Deserialize, JsonSchema, Default)]
+pub struct WorkScheduleForUpdate {
+	#[serde(default, with = "iso_date::option")]
+	#[schemars(with = "Option<String>")]
+	pub valid_from: Option<Date>,
+	pub mon_hours: Option<f64>,
+	pub tue_hours: Option<f64>,
+	pub wed_hours: Option<f64>,
+	pub thu_hours: Option<f64>,
+	pub fri_hours: Option<f64>,
+	pub sat_hours: Option<f64>,
+	pub sun_hours: Option<f64>,
+}
+
+generate_filter!(
+	WorkScheduleFilter {
+		mon_hours: OpValsFloat64,
+		tue_hours: OpValsFloat64,
+		wed_hours: OpValsFloat64,
+		thu_hours: OpValsFloat64,
+		fri_hours: OpValsFloat64,
+		sat_hours: OpValsFloat64,
+		sun_hours: OpValsFloat64,
+	},
+	Dates: [valid_from],
+);
+// endregion: --- WorkSchedule Types
+
+// region:    --- WorkScheduleBmc
+pub struct WorkScheduleBmc;
+
+/// Note: The weekday hours are checked once stored (create or update),
+///       failing (and rolling back) if not within `0..=DAY_HOURS_MAX`.
+#[async_trait]
+impl DbBmc for WorkScheduleBmc {
+	const TABLE: &'static str = "work_schedule";
+
+	async fn after_create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_hours(&Self::get(ctx, mm, id).await?)
+	}
+
+	async fn after_update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> crate::model::Result<()> {
+		check_hours(&Self::get(ctx, mm, id).await?)
+	}
+}
+
+generate_common_bmc_fns!(
+	Bmc: WorkScheduleBmc,
+	Entity: WorkSchedule,
+	ForCreate: WorkScheduleForCreate,
+	ForUpdate: WorkScheduleForUpdate,
+	Filter: WorkScheduleFilter,
+);
+
+impl WorkScheduleBmc {
+	/// Returns all the work schedules of the ctx user, by `valid_from`.
+	pub async fn list_for_user(
+		ctx: &Ctx,
+		mm: &ModelManager,
+	) -> crate::model::Result<Vec<WorkSchedule>> {
+		let filter: WorkScheduleFilter =
+			serde_json::from_value(json!({"cid": ctx.user_id()}))?;
+		let list_options = ListOptions {
+			order_bys: Some("valid_from".into()),
+			..Default::default()
+		};
+
+		Self::list_stream(ctx, mm, Some(vec![filter]), Some(list_options))?
+			.try_collect()
+			.await
+	}
+}
+
+fn check_hours(schedule: &WorkSchedule) -> crate::model::Result<()> {
+	let weekdays = [
+		Weekday::Monday,
+		Weekday::Tuesday,
+		Weekday::Wednesday,
+		Weekday::Thursday,
+		Weekday::Friday,
+		Weekday::Saturday,
+		Weekday::Sunday,
+	];
+
+	for weekday in weekdays {
+		let hours = schedule.hours(weekday);
+		if !hours.is_finite() || !(0. ..=DAY_HOURS_MAX).contains(&hours) {
+			return Err(Error::WorkScheduleHoursInvalid {
+				weekday: weekday.to_string(),
+				hours,
+			});
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns the schedule in effect on `date`, i.e., the last one
+/// with a `valid_from` on or before it.
+///
+/// Note: `schedules` must be ordered by `valid_from`
+///       (see `WorkScheduleBmc::list_for_user`).
+pub fn schedule_on(schedules: &[WorkSchedule], date: Date) -> Option<&WorkSchedule> {
+	schedules.iter().rev().find(|s| s.valid_from <= date)
+}
+// endregion: --- WorkScheduleBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+	use time::macros::date;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+
+		// -- Exec
+		let schedule_c: WorkScheduleForCreate = serde_json::from_value(json!({
+			"valid_from": "2020-01-01",
+			"mon_hours": 8.,
+			"fri_hours": 4.5,
+		}))?;
+		let id = WorkScheduleBmc::create(&ctx, &mm, schedule_c).await?;
+
+		// -- Check
+		let schedule = WorkScheduleBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(schedule.valid_from, date!(2020 - 01 - 01));
+		assert_eq!(schedule.hours(Weekday::Friday), 4.5);
+		assert_eq!(schedule.hours(Weekday::Sunday), 0.);
+
+		// -- Clean
+		WorkScheduleBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_hours_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+
+		// -- Exec
+		let schedule_c: WorkScheduleForCreate = serde_json::from_value(json!({
+			"valid_from": "2020-02-01",
+			"tue_hours": 25.,
+		}))?;
+		let res = WorkScheduleBmc::create(&ctx, &mm, schedule_c).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::WorkScheduleHoursInvalid { .. })),
+			"WorkScheduleHoursInvalid not matching"
+		);
+		let filter: WorkScheduleFilter =
+			serde_json::from_value(json!({"valid_from": "2020-02-01"}))?;
+		let schedules =
+			WorkScheduleBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert!(schedules.is_empty(), "schedule should be rolled back");
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
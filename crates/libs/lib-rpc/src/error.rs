@@ -16,6 +16,8 @@ pub enum Error {
 	// -- Modules
 	#[from]
 	Model(lib_core::model::Error),
+	#[from]
+	Export(lib_core::export::Error),
 
 	// -- External Modules
 	#[from]
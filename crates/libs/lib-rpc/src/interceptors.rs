@@ -0,0 +1,99 @@
+//! Common `RpcInterceptor` implementations (see `router::RpcInterceptor`).
+
+use crate::router::{RpcCall, RpcInterceptor};
+use crate::{Result, RpcResources};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+// region:    --- RpcTimingInterceptor
+
+/// Logs the duration of each rpc call, and warns when over `slow_threshold`.
+pub struct RpcTimingInterceptor {
+	pub slow_threshold: Duration,
+}
+
+impl Default for RpcTimingInterceptor {
+	fn default() -> Self {
+		Self {
+			slow_threshold: Duration::from_millis(500),
+		}
+	}
+}
+
+#[async_trait]
+impl RpcInterceptor for RpcTimingInterceptor {
+	async fn after(&self, call: &RpcCall, result: Result<Value>) -> Result<Value> {
+		let elapsed = call.elapsed();
+		if elapsed > self.slow_threshold {
+			warn!("{:<12} - slow rpc {} - {elapsed:?}", "RPC", call.method());
+		} else {
+			debug!("{:<12} - rpc {} - {elapsed:?}", "RPC", call.method());
+		}
+
+		result
+	}
+}
+
+// endregion: --- RpcTimingInterceptor
+
+// region:    --- RpcParamsLogInterceptor
+
+/// Logs the params of each rpc call, with the values of the `redacted_keys`
+/// (at any depth) replaced by `"REDACTED"`.
+pub struct RpcParamsLogInterceptor {
+	pub redacted_keys: Vec<&'static str>,
+}
+
+impl Default for RpcParamsLogInterceptor {
+	fn default() -> Self {
+		Self {
+			redacted_keys: vec!["pwd", "password", "token", "secret"],
+		}
+	}
+}
+
+#[async_trait]
+impl RpcInterceptor for RpcParamsLogInterceptor {
+	async fn before(
+		&self,
+		call: &RpcCall,
+		_rpc_resources: &RpcResources,
+		params: &mut Option<Value>,
+	) -> Result<()> {
+		let mut logged_params = params.clone();
+		if let Some(logged_params) = logged_params.as_mut() {
+			redact(logged_params, &self.redacted_keys);
+		}
+		debug!(
+			"{:<12} - rpc {} params - {logged_params:?}",
+			"RPC",
+			call.method()
+		);
+
+		Ok(())
+	}
+}
+
+fn redact(value: &mut Value, redacted_keys: &[&str]) {
+	match value {
+		Value::Object(map) => {
+			for (key, value) in map.iter_mut() {
+				if redacted_keys.contains(&key.as_str()) {
+					*value = Value::from("REDACTED");
+				} else {
+					redact(value, redacted_keys);
+				}
+			}
+		}
+		Value::Array(values) => {
+			for value in values.iter_mut() {
+				redact(value, redacted_keys);
+			}
+		}
+		_ => (),
+	}
+}
+
+// endregion: --- RpcParamsLogInterceptor
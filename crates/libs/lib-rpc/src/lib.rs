@@ -1,6 +1,7 @@
 // region:    --- Modules
 
 mod error;
+pub mod interceptors;
 mod params;
 mod resources;
 mod rpcs;
@@ -14,4 +15,8 @@ pub use router::RpcRequest;
 
 pub use rpcs::*;
 
+// Re-exported for the `generate_common_rpc_fns!` macro.
+#[doc(hidden)]
+pub use paste;
+
 // endregion: --- Modules
@@ -10,13 +10,14 @@
 use crate::router::{IntoDefaultParams, IntoParams};
 use crate::Result;
 use modql::filter::ListOptions;
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::Value;
 use serde_with::{serde_as, OneOrMany};
 
 /// Params structure for any RPC Create call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsForCreate<D> {
 	pub data: D,
 }
@@ -24,7 +25,7 @@ pub struct ParamsForCreate<D> {
 impl<D> IntoParams for ParamsForCreate<D> where D: DeserializeOwned + Send {}
 
 /// Params structure for any RPC Update call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsForUpdate<D> {
 	pub id: i64,
 	pub data: D,
@@ -33,24 +34,71 @@ pub struct ParamsForUpdate<D> {
 impl<D> IntoParams for ParamsForUpdate<D> where D: DeserializeOwned + Send {}
 
 /// Params structure for any RPC Update call.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct ParamsIded {
 	pub id: i64,
 }
 impl IntoParams for ParamsIded {}
 
+/// Params structure for any RPC Bulk Create call.
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForBulkCreate<D> {
+	pub data: Vec<D>,
+}
+
+impl<D> IntoParams for ParamsForBulkCreate<D> where D: DeserializeOwned + Send {}
+
+/// Params structure for any RPC Bulk Update call.
+/// (each item being the `{id, data}` of a single update)
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForBulkUpdate<D> {
+	pub data: Vec<ParamsForUpdate<D>>,
+}
+
+impl<D> IntoParams for ParamsForBulkUpdate<D> where D: DeserializeOwned + Send {}
+
+/// Params structure for any RPC Bulk Delete call.
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForBulkDelete {
+	pub ids: Vec<i64>,
+}
+impl IntoParams for ParamsForBulkDelete {}
+
 /// Params structure for any RPC List call.
-#[serde_as]
-#[derive(Deserialize, Default)]
+#[serde_as(schemars = false)]
+#[derive(Deserialize, Default, JsonSchema)]
 pub struct ParamsList<F>
 where
 	F: DeserializeOwned,
 {
 	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
+	#[schemars(with = "Option<Vec<F>>")]
 	pub filters: Option<Vec<F>>,
+	#[schemars(with = "Option<ListOptionsSchema>")]
 	pub list_options: Option<ListOptions>,
 }
 
+/// The JSON Schema shape of the modql `ListOptions` (for `rpc.discover` only).
+///
+/// e.g., `{"limit": 10, "offset": 0, "order_bys": "!ctime"}`
+/// (`order_bys` is one or many property names, prefixed with `!` for descending)
+#[allow(unused)]
+#[derive(JsonSchema)]
+#[schemars(rename = "ListOptions")]
+struct ListOptionsSchema {
+	limit: Option<i64>,
+	offset: Option<i64>,
+	order_bys: Option<OneOrManyStrings>,
+}
+
+#[allow(unused)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum OneOrManyStrings {
+	One(String),
+	Many(Vec<String>),
+}
+
 impl<D> IntoDefaultParams for ParamsList<D> where D: DeserializeOwned + Send + Default
 {}
 
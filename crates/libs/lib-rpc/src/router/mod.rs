@@ -23,6 +23,11 @@
 //! - For custom `IntoParams` behavior, implement the `IntoParams::into_params` function.
 //! - Implementing `IntoDefaultParams` on a type that implements `Default` will auto-implement `IntoParams`
 //!   and call `T::default()` when the params `Option<Value>` is None.
+//! - `RpcInterceptor` trait can be implemented and registered on the `RpcRouter`
+//!   (`RpcRouter::with_interceptor`) to see (and change) the params before each call,
+//!   and its result after (e.g., timing, authorization, logging).
+//! - `rpc.discover` is answered by every `RpcRouter` with an OpenRPC-style document
+//!   of its methods, with the JSON Schemas of their params and result (see `RpcRouter::discover`).
 //!
 
 // region:    --- Modules
@@ -31,19 +36,23 @@ mod from_resources;
 mod into_params;
 mod rpc_handler;
 mod rpc_handler_wrapper;
+mod rpc_interceptor;
 
 pub use from_resources::FromResources;
 pub use into_params::{IntoDefaultParams, IntoParams};
 pub use rpc_handler::RpcHandler;
 pub use rpc_handler_wrapper::{RpcHandlerWrapper, RpcHandlerWrapperTrait};
+pub use rpc_interceptor::{RpcCall, RpcInterceptor};
 
 use crate::RpcResources;
 use crate::{Error, Result};
 use futures::Future;
+use schemars::gen::SchemaSettings;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::Arc;
 
 // endregion: --- Modules
 
@@ -55,6 +64,9 @@ pub struct RpcRequest {
 	pub params: Option<Value>,
 }
 
+/// The method name of the discovery document (see `RpcRouter::discover`).
+pub const RPC_DISCOVER_METHOD: &str = "rpc.discover";
+
 pub type PinFutureValue = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
 
 /// method, which calls the appropriate handler matching the method_name.
@@ -62,6 +74,7 @@ pub type PinFutureValue = Pin<Box<dyn Future<Output = Result<Value>> + Send>>;
 /// RpcRouter can be extended with other RpcRouters for composability.
 pub struct RpcRouter {
 	route_by_name: HashMap<&'static str, Box<dyn RpcHandlerWrapperTrait>>,
+	interceptors: Vec<Arc<dyn RpcInterceptor>>,
 }
 
 impl RpcRouter {
@@ -69,6 +82,7 @@ impl RpcRouter {
 	pub fn new() -> Self {
 		Self {
 			route_by_name: HashMap::new(),
+			interceptors: Vec::new(),
 		}
 	}
 
@@ -110,8 +124,26 @@ impl RpcRouter {
 		self.add_dyn(name, handler.into_dyn())
 	}
 
+	/// Add an interceptor called around each call of this router
+	/// (see `RpcInterceptor`).
+	///
+	/// ```ignore
+	/// RpcRouter::new().with_interceptor(MyTimingInterceptor);
+	/// ```
+	pub fn with_interceptor(
+		mut self,
+		interceptor: impl RpcInterceptor + 'static,
+	) -> Self {
+		self.interceptors.push(Arc::new(interceptor));
+		self
+	}
+
+	/// Extend this router with the routes and interceptors of `other_router`.
+	///
+	/// Note: The interceptors apply to all the routes of the resulting router.
 	pub fn extend(mut self, other_router: RpcRouter) -> Self {
 		self.route_by_name.extend(other_router.route_by_name);
+		self.interceptors.extend(other_router.interceptors);
 		self
 	}
 
@@ -119,13 +151,83 @@ impl RpcRouter {
 		&self,
 		method: &str,
 		rpc_resources: RpcResources,
-		params: Option<Value>,
+		mut params: Option<Value>,
 	) -> Result<Value> {
-		if let Some(route) = self.route_by_name.get(method) {
-			route.call(rpc_resources, params).await
-		} else {
-			Err(Error::RpcMethodUnknown(method.to_string()))
+		let call = RpcCall::new(method, &rpc_resources);
+
+		// -- Interceptors before
+		let mut before_count = 0;
+		let mut before_res = Ok(());
+		for interceptor in self.interceptors.iter() {
+			before_count += 1;
+			before_res =
+				interceptor.before(&call, &rpc_resources, &mut params).await;
+			if before_res.is_err() {
+				break;
+			}
+		}
+
+		// -- Exec the route
+		let mut res = match before_res {
+			Ok(()) => match self.route_by_name.get(method) {
+				Some(route) => route.call(rpc_resources, params).await,
+				None if method == RPC_DISCOVER_METHOD => Ok(self.discover()),
+				None => Err(Error::RpcMethodUnknown(method.to_string())),
+			},
+			Err(ex) => Err(ex),
+		};
+
+		// -- Interceptors after (in reverse order)
+		for interceptor in self.interceptors[..before_count].iter().rev() {
+			res = interceptor.after(&call, res).await;
 		}
+
+		res
+	}
+
+	/// Returns the OpenRPC-style discovery document of the router methods.
+	///
+	/// - Each method has a single `params` param, being the json-rpc `params`
+	///   object itself (absent when the handler takes no params).
+	/// - The schemas of the named types (e.g., `TaskForCreate`, `TaskFilter`) are in
+	///   `components.schemas`, referenced with `$ref`.
+	pub fn discover(&self) -> Value {
+		let settings = SchemaSettings::draft07().with(|settings| {
+			settings.definitions_path = "#/components/schemas/".to_string();
+		});
+		let mut gen = settings.into_generator();
+
+		let mut routes: Vec<_> = self.route_by_name.iter().collect();
+		routes.sort_by_key(|(name, _)| **name);
+
+		let methods: Vec<Value> = routes
+			.into_iter()
+			.map(|(name, route)| {
+				let params = match route.params_schema(&mut gen) {
+					Some(schema) => json!([{"name": "params", "schema": schema}]),
+					None => json!([]),
+				};
+				let result = route.result_schema(&mut gen);
+
+				json!({
+					"name": name,
+					"params": params,
+					"result": {"name": "result", "schema": result},
+				})
+			})
+			.collect();
+
+		json!({
+			"openrpc": "1.2.6",
+			"info": {
+				"title": "rpc",
+				"version": env!("CARGO_PKG_VERSION"),
+			},
+			"methods": methods,
+			"components": {
+				"schemas": gen.definitions(),
+			},
+		})
 	}
 }
 
@@ -163,3 +265,133 @@ macro_rules! rpc_router {
         }
     };
 }
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use async_trait::async_trait;
+	use lib_core::_dev_utils;
+	use std::sync::Mutex;
+
+	type Log = Arc<Mutex<Vec<String>>>;
+
+	/// Records its `before`/`after` calls in `log`, and vetoes the call
+	/// in `before` when `veto`.
+	struct RecordInterceptor {
+		name: &'static str,
+		veto: bool,
+		log: Log,
+	}
+
+	#[async_trait]
+	impl RpcInterceptor for RecordInterceptor {
+		async fn before(
+			&self,
+			_call: &RpcCall,
+			_rpc_resources: &RpcResources,
+			_params: &mut Option<Value>,
+		) -> crate::Result<()> {
+			// Yield, to make sure the router awaits each interceptor in turn.
+			tokio::task::yield_now().await;
+			self.log
+				.lock()
+				.unwrap()
+				.push(format!("{}.before", self.name));
+			if self.veto {
+				return Err(Error::MissingCtx);
+			}
+			Ok(())
+		}
+
+		async fn after(
+			&self,
+			_call: &RpcCall,
+			result: crate::Result<Value>,
+		) -> crate::Result<Value> {
+			tokio::task::yield_now().await;
+			let res_kind = if result.is_ok() { "ok" } else { "err" };
+			self.log
+				.lock()
+				.unwrap()
+				.push(format!("{}.after({res_kind})", self.name));
+			result
+		}
+	}
+
+	async fn ping() -> crate::Result<String> {
+		Ok("pong".to_string())
+	}
+
+	fn new_router(log: &Log, vetoing_name: Option<&str>) -> RpcRouter {
+		let mut router = RpcRouter::new().add("ping", ping);
+		for name in ["a", "b", "c"] {
+			router = router.with_interceptor(RecordInterceptor {
+				name,
+				veto: vetoing_name == Some(name),
+				log: log.clone(),
+			});
+		}
+		router
+	}
+
+	async fn new_rpc_resources() -> RpcResources {
+		RpcResources {
+			mm: _dev_utils::init_test().await,
+			ctx: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_interceptors_order_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let log = Log::default();
+		let router = new_router(&log, None);
+
+		// -- Exec
+		let res = router.call("ping", new_rpc_resources().await, None).await?;
+
+		// -- Check
+		assert_eq!(res, Value::from("pong"));
+		assert_eq!(
+			*log.lock().unwrap(),
+			[
+				"a.before",
+				"b.before",
+				"c.before",
+				"c.after(ok)",
+				"b.after(ok)",
+				"a.after(ok)"
+			]
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_interceptors_before_veto_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let log = Log::default();
+		let router = new_router(&log, Some("b"));
+
+		// -- Exec
+		let res = router.call("ping", new_rpc_resources().await, None).await;
+
+		// -- Check
+		// The handler is not called, and the veto error is returned.
+		assert!(
+			matches!(res, Err(Error::MissingCtx)),
+			"should have been the veto error, but was {res:?}"
+		);
+		// `c.before` is not called, and only the interceptors whose
+		// `before` was called (including the vetoing one) have their `after` called.
+		assert_eq!(
+			*log.lock().unwrap(),
+			["a.before", "b.before", "b.after(err)", "a.after(err)"]
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
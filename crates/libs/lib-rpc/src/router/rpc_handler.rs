@@ -6,6 +6,9 @@ use crate::router::RpcHandlerWrapper;
 use crate::router::RpcHandlerWrapperTrait;
 use crate::RpcResources;
 use futures::Future;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::Serialize;
 use serde_json::Value;
 
@@ -21,6 +24,7 @@ use serde_json::Value;
 ///   thus facilitating the use of RpcRoute dynamic dispatch.
 /// - `T` is the tuple of `impl FromResources` arguments.
 /// - `P` is the `impl IntoParams` argument.
+/// - `P` and `R` must implement `JsonSchema`, for the `rpc.discover` document.
 ///
 pub trait RpcHandler<T, P, R>: Clone
 where
@@ -38,6 +42,12 @@ where
 		params: Option<Value>,
 	) -> Self::Future;
 
+	/// The JSON Schema of the params (None if the handler takes no params).
+	fn params_schema(gen: &mut SchemaGenerator) -> Option<Schema>;
+
+	/// The JSON Schema of the result.
+	fn result_schema(gen: &mut SchemaGenerator) -> Schema;
+
 	/// Convert this RpcHandler into a Boxed dyn RpcHandlerWrapperTrait,
 	/// for dynamic dispatch by the Router.
 	fn into_dyn(self) -> Box<dyn RpcHandlerWrapperTrait>
@@ -58,8 +68,8 @@ macro_rules! impl_rpc_handler_pair {
         where
             F: FnOnce($($T,)* P) -> Fut + Clone + Send + 'static,
             $( $T: FromResources + Send + Sync + 'static, )*
-            P: IntoParams + Send + Sync + 'static,
-            R: Serialize + Send + Sync + 'static,
+            P: IntoParams + JsonSchema + Send + Sync + 'static,
+            R: Serialize + JsonSchema + Send + Sync + 'static,
             Fut: Future<Output = Result<R>> + Send,
         {
             type Future = PinFutureValue;
@@ -81,6 +91,14 @@ macro_rules! impl_rpc_handler_pair {
                     Ok(serde_json::to_value(result)?)
                 })
             }
+
+            fn params_schema(gen: &mut SchemaGenerator) -> Option<Schema> {
+                Some(gen.subschema_for::<P>())
+            }
+
+            fn result_schema(gen: &mut SchemaGenerator) -> Schema {
+                gen.subschema_for::<R>()
+            }
         }
 
 				// RpcHandler implementations for zero or more FromResources and NO IntoParams
@@ -88,7 +106,7 @@ macro_rules! impl_rpc_handler_pair {
 				where
 						F: FnOnce($($T,)*) -> Fut + Clone + Send + 'static,
 						$( $T: FromResources + Send + Sync + 'static, )*
-						R: Serialize + Send + Sync + 'static,
+						R: Serialize + JsonSchema + Send + Sync + 'static,
 						Fut: Future<Output = Result<R>> + Send,
 				{
 						type Future = PinFutureValue;
@@ -107,6 +125,14 @@ macro_rules! impl_rpc_handler_pair {
 										Ok(serde_json::to_value(result)?)
 								})
 						}
+
+						fn params_schema(_gen: &mut SchemaGenerator) -> Option<Schema> {
+								None
+						}
+
+						fn result_schema(gen: &mut SchemaGenerator) -> Schema {
+								gen.subschema_for::<R>()
+						}
 				}
     };
 
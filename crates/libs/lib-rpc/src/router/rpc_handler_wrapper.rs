@@ -2,6 +2,8 @@ use crate::router::Result;
 use crate::router::{PinFutureValue, RpcHandler};
 use crate::RpcResources;
 use futures::Future;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
 use serde_json::Value;
 use std::marker::PhantomData;
 use std::pin::Pin;
@@ -52,6 +54,10 @@ pub trait RpcHandlerWrapperTrait: Send + Sync {
 		rpc_resources: RpcResources,
 		params: Option<Value>,
 	) -> PinFutureValue;
+
+	fn params_schema(&self, gen: &mut SchemaGenerator) -> Option<Schema>;
+
+	fn result_schema(&self, gen: &mut SchemaGenerator) -> Schema;
 }
 
 impl<H, T, P, R> RpcHandlerWrapperTrait for RpcHandlerWrapper<H, T, P, R>
@@ -68,4 +74,12 @@ where
 	) -> Pin<Box<dyn Future<Output = Result<Value>> + Send>> {
 		Box::pin(self.call(rpc_resources, params))
 	}
+
+	fn params_schema(&self, gen: &mut SchemaGenerator) -> Option<Schema> {
+		H::params_schema(gen)
+	}
+
+	fn result_schema(&self, gen: &mut SchemaGenerator) -> Schema {
+		H::result_schema(gen)
+	}
 }
@@ -0,0 +1,64 @@
+use crate::router::Result;
+use crate::RpcResources;
+use async_trait::async_trait;
+use lib_core::ctx::Ctx;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// `RpcInterceptor` is called by the `RpcRouter` around each rpc call
+/// (e.g., for timing, authorization, params logging, error translation).
+///
+/// - `before` is called in the registration order, before the params are
+///   given to the handler. It can change the params, or veto the call with an error.
+/// - `after` is called in the reverse order with the result of the call
+///   (or of the vetoing `before`), which it can change (e.g., error translation).
+///   Only the interceptors whose `before` was called have their `after` called.
+///
+/// Both are async (e.g., for db-backed authorization or auditing through
+/// `rpc_resources.mm`), and have a default no-op implementation.
+#[async_trait]
+pub trait RpcInterceptor: Send + Sync {
+	async fn before(
+		&self,
+		_call: &RpcCall,
+		_rpc_resources: &RpcResources,
+		_params: &mut Option<Value>,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	async fn after(&self, _call: &RpcCall, result: Result<Value>) -> Result<Value> {
+		result
+	}
+}
+
+/// The information of the rpc call being intercepted.
+pub struct RpcCall {
+	method: String,
+	ctx: Option<Ctx>,
+	start: Instant,
+}
+
+// Constructor & Accessors
+impl RpcCall {
+	pub(super) fn new(method: &str, rpc_resources: &RpcResources) -> Self {
+		RpcCall {
+			method: method.to_string(),
+			ctx: rpc_resources.ctx.clone(),
+			start: Instant::now(),
+		}
+	}
+
+	pub fn method(&self) -> &str {
+		&self.method
+	}
+
+	pub fn ctx(&self) -> Option<&Ctx> {
+		self.ctx.as_ref()
+	}
+
+	/// Time elapsed since the start of the call (i.e., before the first interceptor).
+	pub fn elapsed(&self) -> Duration {
+		self.start.elapsed()
+	}
+}
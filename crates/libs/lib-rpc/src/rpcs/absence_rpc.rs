@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::absence::{
+	Absence, AbsenceBmc, AbsenceFilter, AbsenceForCreate, AbsenceForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: AbsenceBmc,
+	Entity: Absence,
+	ForCreate: AbsenceForCreate,
+	ForUpdate: AbsenceForUpdate,
+	Filter: AbsenceFilter,
+	Suffix: absence,
+	PluralSuffix: absences,
+);
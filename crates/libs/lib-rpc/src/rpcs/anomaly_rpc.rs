@@ -0,0 +1,53 @@
+//! Tracked time anomalies report (see `lib_core::model::anomaly`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::export::{parse_utc_offset, DateRange};
+use lib_core::model::anomaly::{AnomalyBmc, AnomalyReport};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use time::UtcOffset;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(anomalies)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForAnomalies {
+	/// Rfc3339 time or date (e.g., "2024-03-01").
+	pub from: String,
+	/// Rfc3339 time or date (e.g., "2024-03-31", a date being inclusive).
+	pub to: String,
+	/// The offset of the dates (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForAnomalies {}
+
+// endregion: --- Params
+
+pub async fn anomalies(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForAnomalies,
+) -> Result<AnomalyReport> {
+	let ParamsForAnomalies {
+		from,
+		to,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let range = DateRange::parse(&from, &to, utc_offset)?;
+	let report = AnomalyBmc::report(&ctx, &mm, range.from, range.to).await?;
+
+	Ok(report)
+}
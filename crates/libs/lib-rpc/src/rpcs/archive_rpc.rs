@@ -0,0 +1,44 @@
+//! Account data backup and restore rpcs (see `lib_core::model::archive`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::archive::{Archive, ArchiveBmc, ArchiveImportReport};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(export_my_data, import_my_data)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForImportMyData {
+	/// The archive returned by `export_my_data`.
+	pub archive: Archive,
+}
+
+impl IntoParams for ParamsForImportMyData {}
+
+// endregion: --- Params
+
+pub async fn export_my_data(ctx: Ctx, mm: ModelManager) -> Result<Archive> {
+	let archive = ArchiveBmc::export_my_data(&ctx, &mm).await?;
+
+	Ok(archive)
+}
+
+pub async fn import_my_data(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForImportMyData,
+) -> Result<ArchiveImportReport> {
+	let ParamsForImportMyData { archive } = params;
+
+	let report = ArchiveBmc::import_my_data(&ctx, &mm, archive).await?;
+
+	Ok(report)
+}
@@ -0,0 +1,43 @@
+//! Calendar url token rpcs (see `lib_core::export`).
+//!
+//! The token gives a read-only access to the ctx user calendar, at
+//! `/calendar/{token}.ics` (e.g., for subscriptions from a calendar app).
+
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::user::UserBmc;
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(get_calendar_token, reset_calendar_token)
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct CalendarToken {
+	pub token: Uuid,
+}
+
+/// Returns the calendar token of the ctx user (created on first call).
+pub async fn get_calendar_token(
+	ctx: Ctx,
+	mm: ModelManager,
+) -> Result<CalendarToken> {
+	let token = UserBmc::calendar_token(&ctx, &mm).await?;
+
+	Ok(CalendarToken { token })
+}
+
+/// Replaces the calendar token of the ctx user,
+/// so the previous calendar url stops working.
+pub async fn reset_calendar_token(
+	ctx: Ctx,
+	mm: ModelManager,
+) -> Result<CalendarToken> {
+	let token = UserBmc::reset_calendar_token(&ctx, &mm).await?;
+
+	Ok(CalendarToken { token })
+}
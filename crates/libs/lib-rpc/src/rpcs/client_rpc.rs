@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::client::{
+	Client, ClientBmc, ClientFilter, ClientForCreate, ClientForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: ClientBmc,
+	Entity: Client,
+	ForCreate: ClientForCreate,
+	ForUpdate: ClientForUpdate,
+	Filter: ClientFilter,
+	Suffix: client,
+	PluralSuffix: clients,
+);
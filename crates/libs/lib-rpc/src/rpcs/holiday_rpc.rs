@@ -0,0 +1,76 @@
+//! Public holiday calendars (see `lib_core::model::holiday`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::absence::{Absence, AbsenceBmc};
+use lib_core::model::holiday::{self, Holiday, HolidayBmc, HolidayCalendar};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(list_holiday_calendars, list_holidays, import_holidays)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForHolidays {
+	/// The country code (e.g., "DE", see `list_holiday_calendars`).
+	pub country: String,
+	/// The region code (e.g., "BY"), for its extra holidays.
+	pub region: Option<String>,
+	pub year: i32,
+}
+
+impl IntoParams for ParamsForHolidays {}
+
+// endregion: --- Params
+
+pub async fn list_holiday_calendars(
+	_ctx: Ctx,
+	_mm: ModelManager,
+) -> Result<Vec<HolidayCalendar>> {
+	let calendars = holiday::holiday_calendars()?;
+
+	Ok(calendars)
+}
+
+pub async fn list_holidays(
+	_ctx: Ctx,
+	_mm: ModelManager,
+	params: ParamsForHolidays,
+) -> Result<Vec<Holiday>> {
+	let ParamsForHolidays {
+		country,
+		region,
+		year,
+	} = params;
+
+	let holidays = holiday::holidays(&country, region.as_deref(), year)?;
+
+	Ok(holidays)
+}
+
+/// Creates the holidays of the year as `holiday` absences of the ctx user,
+/// and returns the created ones (none for the already imported dates).
+pub async fn import_holidays(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForHolidays,
+) -> Result<Vec<Absence>> {
+	let ParamsForHolidays {
+		country,
+		region,
+		year,
+	} = params;
+
+	let ids =
+		HolidayBmc::import_holidays(&ctx, &mm, &country, region.as_deref(), year)
+			.await?;
+	let absences = AbsenceBmc::get_many(&ctx, &mm, &ids).await?;
+
+	Ok(absences)
+}
@@ -0,0 +1,61 @@
+//! Import of the exports of other trackers (see `lib_core::model::import`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::export::parse_utc_offset;
+use lib_core::model::import::{
+	ImportBmc, ImportFormat, ImportOptions, ImportReport,
+};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use time::UtcOffset;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(import_time_entries)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForImport {
+	pub format: ImportFormat,
+	/// The content of the uploaded export file.
+	pub content: String,
+	/// Only report what would be imported.
+	#[serde(default)]
+	pub dry_run: bool,
+	/// The offset of the csv local times (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForImport {}
+
+// endregion: --- Params
+
+pub async fn import_time_entries(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForImport,
+) -> Result<ImportReport> {
+	let ParamsForImport {
+		format,
+		content,
+		dry_run,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let options = ImportOptions {
+		dry_run,
+		utc_offset,
+	};
+	let report = ImportBmc::import(&ctx, &mm, format, &content, options).await?;
+
+	Ok(report)
+}
@@ -0,0 +1,146 @@
+//! Invoices (see `lib_core::model::invoice`).
+//!
+//! Note: The invoices are only created by `generate_invoice`, and only
+//!       updated by `issue_invoice` (hence, no common create/update rpcs).
+//!       Their html/csv renderings are downloads of the web-server.
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::{ParamsIded, ParamsList, Result};
+use lib_core::ctx::Ctx;
+use lib_core::export::parse_utc_offset;
+use lib_core::model::invoice::{
+	Invoice, InvoiceBmc, InvoiceFilter, InvoiceLine, InvoiceLineBmc,
+};
+use lib_core::model::modql_utils::iso_date;
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use time::{Date, UtcOffset};
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(
+		list_invoices,
+		get_invoice,
+		list_invoice_lines,
+		generate_invoice,
+		issue_invoice,
+		delete_invoice,
+	)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForGenerateInvoice {
+	pub client_id: i64,
+	/// e.g., "2024-03-01"
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub period_start: Date,
+	/// e.g., "2024-03-31" (inclusive)
+	#[serde(with = "iso_date")]
+	#[schemars(with = "String")]
+	pub period_end: Date,
+	/// The offset of the dates (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForGenerateInvoice {}
+
+// endregion: --- Params
+
+pub async fn list_invoices(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<InvoiceFilter>,
+) -> Result<Vec<Invoice>> {
+	let invoices =
+		InvoiceBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(invoices)
+}
+
+pub async fn get_invoice(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Invoice> {
+	let ParamsIded { id } = params;
+
+	let invoice = InvoiceBmc::get(&ctx, &mm, id).await?;
+
+	Ok(invoice)
+}
+
+/// Returns the lines of the invoice `id`.
+pub async fn list_invoice_lines(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Vec<InvoiceLine>> {
+	let ParamsIded { id } = params;
+
+	let lines = InvoiceLineBmc::list_for_invoice(&ctx, &mm, id).await?;
+
+	Ok(lines)
+}
+
+/// Creates the draft invoice of the client billable tasktimes in the period.
+pub async fn generate_invoice(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForGenerateInvoice,
+) -> Result<Invoice> {
+	let ParamsForGenerateInvoice {
+		client_id,
+		period_start,
+		period_end,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let id = InvoiceBmc::generate(
+		&ctx,
+		&mm,
+		client_id,
+		period_start,
+		period_end,
+		utc_offset,
+	)
+	.await?;
+	let invoice = InvoiceBmc::get(&ctx, &mm, id).await?;
+
+	Ok(invoice)
+}
+
+/// Numbers the draft invoice, which locks it.
+pub async fn issue_invoice(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Invoice> {
+	let ParamsIded { id } = params;
+
+	InvoiceBmc::issue(&ctx, &mm, id).await?;
+	let invoice = InvoiceBmc::get(&ctx, &mm, id).await?;
+
+	Ok(invoice)
+}
+
+/// Deletes the draft invoice (an issued one being locked).
+pub async fn delete_invoice(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Invoice> {
+	let ParamsIded { id } = params;
+
+	let invoice = InvoiceBmc::get(&ctx, &mm, id).await?;
+	InvoiceBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(invoice)
+}
@@ -0,0 +1,221 @@
+/// Generates the common rpc handler functions of an entity, delegating to its Bmc,
+/// and the `rpc_router()` function of the module.
+///
+/// e.g.,
+///
+/// ```ignore
+/// generate_common_rpc_fns!(
+///     Bmc: TaskBmc,
+///     Entity: Task,
+///     ForCreate: TaskForCreate,
+///     ForUpdate: TaskForUpdate,
+///     Filter: TaskFilter,
+///     Suffix: task,
+///     PluralSuffix: tasks,
+/// );
+/// ```
+/// Generates `create_task`, `list_tasks`, `update_task`, `delete_task`,
+/// `bulk_create_tasks`, `bulk_update_tasks`, `bulk_delete_tasks`, and a
+/// `rpc_router()` with all of them.
+///
+/// - `Fns` is optional and defaults to all the common handlers
+///   (`create, list, update, delete, bulk_create, bulk_update, bulk_delete`).
+///   To override a single handler, omit it from `Fns` and write the function
+///   with the same name by hand. It will still be added to `rpc_router()`.
+/// - `Extra` is optional and lists additional handler functions of the module
+///   to be added to `rpc_router()`.
+#[macro_export]
+macro_rules! generate_common_rpc_fns {
+	// -- Without Fns, generate all common handlers.
+	(
+		Bmc: $bmc:ident,
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Filter: $filter:ty,
+		Suffix: $suffix:ident,
+		PluralSuffix: $plural:ident
+		$(, Extra: [$($extra_fn:ident),* $(,)?])?
+		$(,)?
+	) => {
+		$crate::generate_common_rpc_fns!(
+			Bmc: $bmc,
+			Entity: $entity,
+			ForCreate: $for_create,
+			ForUpdate: $for_update,
+			Filter: $filter,
+			Suffix: $suffix,
+			PluralSuffix: $plural,
+			Fns: [
+				create, list, update, delete,
+				bulk_create, bulk_update, bulk_delete
+			]
+			$(, Extra: [$($extra_fn),*])?
+		);
+	};
+
+	// -- With the explicit list of handlers to generate.
+	(
+		Bmc: $bmc:ident,
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Filter: $filter:ty,
+		Suffix: $suffix:ident,
+		PluralSuffix: $plural:ident,
+		Fns: [$($fn_name:ident),* $(,)?]
+		$(, Extra: [$($extra_fn:ident),* $(,)?])?
+		$(,)?
+	) => {
+		$crate::paste::paste! {
+			pub fn rpc_router() -> $crate::router::RpcRouter {
+				$crate::rpc_router!(
+					[<create_ $suffix>],
+					[<list_ $plural>],
+					[<update_ $suffix>],
+					[<delete_ $suffix>],
+					[<bulk_create_ $plural>],
+					[<bulk_update_ $plural>],
+					[<bulk_delete_ $plural>],
+					$($($extra_fn,)*)?
+				)
+			}
+		}
+
+		$(
+			$crate::generate_common_rpc_fns!(
+				@fn $fn_name, $bmc, $entity, $for_create, $for_update, $filter,
+				$suffix, $plural
+			);
+		)*
+	};
+
+	// -- Single handler generators.
+	(@fn create, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<create_ $suffix>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsForCreate<$for_create>,
+			) -> $crate::Result<$entity> {
+				let $crate::ParamsForCreate { data } = params;
+
+				let id = $bmc::create(&ctx, &mm, data).await?;
+				let entity = $bmc::get(&ctx, &mm, id).await?;
+
+				Ok(entity)
+			}
+		}
+	};
+
+	(@fn list, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<list_ $plural>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsList<$filter>,
+			) -> $crate::Result<Vec<$entity>> {
+				let entities =
+					$bmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+				Ok(entities)
+			}
+		}
+	};
+
+	(@fn update, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<update_ $suffix>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsForUpdate<$for_update>,
+			) -> $crate::Result<$entity> {
+				let $crate::ParamsForUpdate { id, data } = params;
+
+				$bmc::update(&ctx, &mm, id, data).await?;
+
+				let entity = $bmc::get(&ctx, &mm, id).await?;
+
+				Ok(entity)
+			}
+		}
+	};
+
+	(@fn delete, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<delete_ $suffix>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsIded,
+			) -> $crate::Result<$entity> {
+				let $crate::ParamsIded { id } = params;
+
+				let entity = $bmc::get(&ctx, &mm, id).await?;
+				$bmc::delete(&ctx, &mm, id).await?;
+
+				Ok(entity)
+			}
+		}
+	};
+
+	(@fn bulk_create, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<bulk_create_ $plural>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsForBulkCreate<$for_create>,
+			) -> $crate::Result<Vec<$entity>> {
+				let $crate::ParamsForBulkCreate { data } = params;
+
+				let ids = $bmc::create_many(&ctx, &mm, data).await?;
+				let entities = $bmc::get_many(&ctx, &mm, &ids).await?;
+
+				Ok(entities)
+			}
+		}
+	};
+
+	(@fn bulk_update, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<bulk_update_ $plural>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsForBulkUpdate<$for_update>,
+			) -> $crate::Result<Vec<$entity>> {
+				let $crate::ParamsForBulkUpdate { data } = params;
+
+				let ids: Vec<i64> = data.iter().map(|item| item.id).collect();
+				let items = data.into_iter().map(|item| (item.id, item.data)).collect();
+				$bmc::update_many(&ctx, &mm, items).await?;
+
+				let entities = $bmc::get_many(&ctx, &mm, &ids).await?;
+
+				Ok(entities)
+			}
+		}
+	};
+
+	(@fn bulk_delete, $bmc:ident, $entity:ty, $for_create:ty, $for_update:ty, $filter:ty,
+		$suffix:ident, $plural:ident) => {
+		$crate::paste::paste! {
+			pub async fn [<bulk_delete_ $plural>](
+				ctx: lib_core::ctx::Ctx,
+				mm: lib_core::model::ModelManager,
+				params: $crate::ParamsForBulkDelete,
+			) -> $crate::Result<Vec<$entity>> {
+				let $crate::ParamsForBulkDelete { ids } = params;
+
+				let entities = $bmc::get_many(&ctx, &mm, &ids).await?;
+				$bmc::delete_many(&ctx, &mm, &ids).await?;
+
+				Ok(entities)
+			}
+		}
+	};
+}
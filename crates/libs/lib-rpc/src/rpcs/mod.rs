@@ -1,4 +1,54 @@
+mod macro_utils;
+
+pub mod absence_rpc;
+pub mod anomaly_rpc;
+pub mod archive_rpc;
+pub mod calendar_rpc;
+pub mod client_rpc;
+pub mod holiday_rpc;
+pub mod import_rpc;
+pub mod invoice_rpc;
+pub mod period_lock_rpc;
+pub mod place_rpc;
 pub mod project_rpc;
+pub mod rate_rpc;
+pub mod report_rpc;
+pub mod rounding_policy_rpc;
+pub mod sync_rpc;
 pub mod task_rpc;
 pub mod taskprogress_rpc;
+pub mod tasktime_rpc;
 pub mod timerecord_rpc;
+pub mod timesheet_rpc;
+pub mod travel_rpc;
+pub mod work_schedule_rpc;
+
+use crate::router::RpcRouter;
+
+/// Build the RpcRouter with all the rpcs of the application.
+/// (e.g., for the web-server transports, and the `gen-ts` tool)
+pub fn all_rpc_router() -> RpcRouter {
+	RpcRouter::new()
+		.extend(timerecord_rpc::rpc_router())
+		.extend(place_rpc::rpc_router())
+		.extend(travel_rpc::rpc_router())
+		.extend(work_schedule_rpc::rpc_router())
+		.extend(absence_rpc::rpc_router())
+		.extend(period_lock_rpc::rpc_router())
+		.extend(timesheet_rpc::rpc_router())
+		.extend(holiday_rpc::rpc_router())
+		.extend(tasktime_rpc::rpc_router())
+		.extend(taskprogress_rpc::rpc_router())
+		.extend(task_rpc::rpc_router())
+		.extend(project_rpc::rpc_router())
+		.extend(client_rpc::rpc_router())
+		.extend(rate_rpc::rpc_router())
+		.extend(invoice_rpc::rpc_router())
+		.extend(rounding_policy_rpc::rpc_router())
+		.extend(sync_rpc::rpc_router())
+		.extend(calendar_rpc::rpc_router())
+		.extend(import_rpc::rpc_router())
+		.extend(archive_rpc::rpc_router())
+		.extend(anomaly_rpc::rpc_router())
+		.extend(report_rpc::rpc_router())
+}
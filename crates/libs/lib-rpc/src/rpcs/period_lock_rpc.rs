@@ -0,0 +1,88 @@
+//! Period locks (see `lib_core::model::period_lock`).
+//!
+//! Note: The locks are never updated, but by `unlock_period_lock`
+//!       (admins only), nor deleted (hence, no common update/delete rpcs).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::{ParamsForCreate, ParamsIded, ParamsList, Result};
+use lib_core::ctx::Ctx;
+use lib_core::model::period_lock::{
+	PeriodLock, PeriodLockBmc, PeriodLockFilter, PeriodLockForCreate,
+};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(
+		list_period_locks,
+		get_period_lock,
+		create_period_lock,
+		unlock_period_lock,
+	)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForUnlock {
+	pub id: i64,
+	/// Why the period is unlocked (kept on the lock).
+	pub reason: String,
+}
+
+impl IntoParams for ParamsForUnlock {}
+
+// endregion: --- Params
+
+pub async fn list_period_locks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<PeriodLockFilter>,
+) -> Result<Vec<PeriodLock>> {
+	let locks =
+		PeriodLockBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(locks)
+}
+
+pub async fn get_period_lock(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<PeriodLock> {
+	let ParamsIded { id } = params;
+
+	let lock = PeriodLockBmc::get(&ctx, &mm, id).await?;
+
+	Ok(lock)
+}
+
+/// Locks a period of the ctx user (or, for an admin, of any or all users).
+pub async fn create_period_lock(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<PeriodLockForCreate>,
+) -> Result<PeriodLock> {
+	let ParamsForCreate { data } = params;
+
+	let id = PeriodLockBmc::create(&ctx, &mm, data).await?;
+	let lock = PeriodLockBmc::get(&ctx, &mm, id).await?;
+
+	Ok(lock)
+}
+
+/// Unlocks a period (admins only), recording the reason.
+pub async fn unlock_period_lock(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUnlock,
+) -> Result<PeriodLock> {
+	let ParamsForUnlock { id, reason } = params;
+
+	PeriodLockBmc::unlock(&ctx, &mm, id, reason).await?;
+	let lock = PeriodLockBmc::get(&ctx, &mm, id).await?;
+
+	Ok(lock)
+}
@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::place::{
+	Place, PlaceBmc, PlaceFilter, PlaceForCreate, PlaceForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: PlaceBmc,
+	Entity: Place,
+	ForCreate: PlaceForCreate,
+	ForUpdate: PlaceForUpdate,
+	Filter: PlaceFilter,
+	Suffix: place,
+	PluralSuffix: places,
+);
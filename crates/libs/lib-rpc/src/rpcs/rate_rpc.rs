@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::rate::{
+	Rate, RateBmc, RateFilter, RateForCreate, RateForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: RateBmc,
+	Entity: Rate,
+	ForCreate: RateForCreate,
+	ForUpdate: RateForUpdate,
+	Filter: RateFilter,
+	Suffix: rate,
+	PluralSuffix: rates,
+);
@@ -0,0 +1,127 @@
+//! Time reports (see `lib_core::export::reconciliation`,
+//! `lib_core::export::travel` and `lib_core::export::flex`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::export::flex::{self, FlexReport};
+use lib_core::export::reconciliation::{self, ReconciliationReport, ReportPeriod};
+use lib_core::export::travel::{self, TravelReport};
+use lib_core::export::{parse_utc_offset, DateRange};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use time::UtcOffset;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(reconciliation_report, travel_report, flex_balance_report)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForReconciliation {
+	/// Rfc3339 time or date (e.g., "2024-03-01").
+	pub from: String,
+	/// Rfc3339 time or date (e.g., "2024-03-31", a date being inclusive).
+	pub to: String,
+	pub period: ReportPeriod,
+	/// The offset of the days (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForReconciliation {}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForTravelReport {
+	/// Rfc3339 time or date (e.g., "2024-01-01").
+	pub from: String,
+	/// Rfc3339 time or date (e.g., "2024-12-31", a date being inclusive).
+	pub to: String,
+	/// The offset of the months (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForTravelReport {}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForFlexBalance {
+	/// Rfc3339 time or date (e.g., "2024-03-01").
+	pub from: String,
+	/// Rfc3339 time or date (e.g., "2024-03-31", a date being inclusive).
+	pub to: String,
+	pub period: ReportPeriod,
+	/// The offset of the days (e.g., "+02:00", default UTC).
+	pub utc_offset: Option<String>,
+}
+
+impl IntoParams for ParamsForFlexBalance {}
+
+// endregion: --- Params
+
+pub async fn reconciliation_report(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForReconciliation,
+) -> Result<ReconciliationReport> {
+	let ParamsForReconciliation {
+		from,
+		to,
+		period,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let range = DateRange::parse(&from, &to, utc_offset)?;
+	let report =
+		reconciliation::reconciliation(&ctx, &mm, range, period, utc_offset).await?;
+
+	Ok(report)
+}
+
+pub async fn travel_report(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForTravelReport,
+) -> Result<TravelReport> {
+	let ParamsForTravelReport {
+		from,
+		to,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let range = DateRange::parse(&from, &to, utc_offset)?;
+	let report = travel::travel_report(&ctx, &mm, range, utc_offset).await?;
+
+	Ok(report)
+}
+
+pub async fn flex_balance_report(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForFlexBalance,
+) -> Result<FlexReport> {
+	let ParamsForFlexBalance {
+		from,
+		to,
+		period,
+		utc_offset,
+	} = params;
+
+	let utc_offset = match utc_offset {
+		Some(utc_offset) => parse_utc_offset(&utc_offset)?,
+		None => UtcOffset::UTC,
+	};
+	let range = DateRange::parse(&from, &to, utc_offset)?;
+	let report = flex::flex_balance(&ctx, &mm, range, period, utc_offset).await?;
+
+	Ok(report)
+}
@@ -0,0 +1,15 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::rounding::{
+	RoundingPolicy, RoundingPolicyBmc, RoundingPolicyFilter,
+	RoundingPolicyForCreate, RoundingPolicyForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: RoundingPolicyBmc,
+	Entity: RoundingPolicy,
+	ForCreate: RoundingPolicyForCreate,
+	ForUpdate: RoundingPolicyForUpdate,
+	Filter: RoundingPolicyFilter,
+	Suffix: rounding_policy,
+	PluralSuffix: rounding_policies,
+);
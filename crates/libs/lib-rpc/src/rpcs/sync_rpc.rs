@@ -0,0 +1,58 @@
+//! Change feed and offline sync rpcs (see `lib_core::model::sync`).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::sync::{ChangeSet, SyncBmc, SyncPushItem, SyncPushResult};
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(sync_changes, sync_push)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForSyncChanges {
+	/// The `last_seq` of the previous call (0 for a full sync).
+	pub since: i64,
+	pub limit: Option<i64>,
+}
+
+impl IntoParams for ParamsForSyncChanges {}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForSyncPush {
+	pub items: Vec<SyncPushItem>,
+}
+
+impl IntoParams for ParamsForSyncPush {}
+
+// endregion: --- Params
+
+pub async fn sync_changes(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForSyncChanges,
+) -> Result<ChangeSet> {
+	let ParamsForSyncChanges { since, limit } = params;
+
+	let change_set = SyncBmc::changes_since(&ctx, &mm, since, limit).await?;
+
+	Ok(change_set)
+}
+
+pub async fn sync_push(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForSyncPush,
+) -> Result<Vec<SyncPushResult>> {
+	let ParamsForSyncPush { items } = params;
+
+	let results = SyncBmc::push(&ctx, &mm, items).await?;
+
+	Ok(results)
+}
@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::tasktime::{
+	TaskTime, TaskTimeBmc, TaskTimeFilter, TaskTimeForCreate, TaskTimeForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: TaskTimeBmc,
+	Entity: TaskTime,
+	ForCreate: TaskTimeForCreate,
+	ForUpdate: TaskTimeForUpdate,
+	Filter: TaskTimeFilter,
+	Suffix: tasktime,
+	PluralSuffix: tasktimes,
+);
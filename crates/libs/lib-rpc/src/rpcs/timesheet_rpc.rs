@@ -0,0 +1,189 @@
+//! Timesheets and their approval (see `lib_core::model::timesheet`).
+//!
+//! Note: The timesheets are only updated by `submit_timesheet`,
+//!       `approve_timesheet`, and `reject_timesheet` (hence, no common
+//!       update rpcs), and the approvers set by `set_user_approver`
+//!       (admins only).
+
+use crate::router::IntoParams;
+use crate::rpc_router;
+use crate::{ParamsForCreate, ParamsIded, ParamsList, Result};
+use lib_core::ctx::Ctx;
+use lib_core::model::timesheet::{
+	PendingTimesheet, Timesheet, TimesheetBmc, TimesheetComment,
+	TimesheetCommentBmc, TimesheetFilter, TimesheetForCreate,
+};
+use lib_core::model::user::UserBmc;
+use lib_core::model::ModelManager;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+pub fn rpc_router() -> crate::router::RpcRouter {
+	rpc_router!(
+		list_timesheets,
+		get_timesheet,
+		create_timesheet,
+		delete_timesheet,
+		submit_timesheet,
+		approve_timesheet,
+		reject_timesheet,
+		list_pending_timesheets,
+		list_timesheet_comments,
+		set_user_approver,
+	)
+}
+
+// region:    --- Params
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForTimesheetAction {
+	pub id: i64,
+	/// Required to reject.
+	#[serde(default)]
+	pub comment: String,
+}
+
+impl IntoParams for ParamsForTimesheetAction {}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ParamsForSetApprover {
+	pub user_id: i64,
+	/// `None` for none.
+	pub approver_id: Option<i64>,
+}
+
+impl IntoParams for ParamsForSetApprover {}
+
+// endregion: --- Params
+
+pub async fn list_timesheets(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TimesheetFilter>,
+) -> Result<Vec<Timesheet>> {
+	let timesheets =
+		TimesheetBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(timesheets)
+}
+
+pub async fn get_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Timesheet> {
+	let ParamsIded { id } = params;
+
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Creates the draft timesheet of a period, for the ctx user.
+pub async fn create_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<TimesheetForCreate>,
+) -> Result<Timesheet> {
+	let ParamsForCreate { data } = params;
+
+	let id = TimesheetBmc::create(&ctx, &mm, data).await?;
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Deletes a draft (or rejected) timesheet.
+pub async fn delete_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Timesheet> {
+	let ParamsIded { id } = params;
+
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+	TimesheetBmc::delete(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Submits a timesheet of the ctx user to their approver.
+pub async fn submit_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForTimesheetAction,
+) -> Result<Timesheet> {
+	let ParamsForTimesheetAction { id, comment } = params;
+
+	TimesheetBmc::submit(&ctx, &mm, id, comment).await?;
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Approves a submitted timesheet, which locks its period.
+pub async fn approve_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForTimesheetAction,
+) -> Result<Timesheet> {
+	let ParamsForTimesheetAction { id, comment } = params;
+
+	TimesheetBmc::approve(&ctx, &mm, id, comment).await?;
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Rejects a submitted timesheet, with a comment.
+pub async fn reject_timesheet(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForTimesheetAction,
+) -> Result<Timesheet> {
+	let ParamsForTimesheetAction { id, comment } = params;
+
+	TimesheetBmc::reject(&ctx, &mm, id, comment).await?;
+	let timesheet = TimesheetBmc::get(&ctx, &mm, id).await?;
+
+	Ok(timesheet)
+}
+
+/// Returns the submitted timesheets the ctx user is the approver of.
+pub async fn list_pending_timesheets(
+	ctx: Ctx,
+	mm: ModelManager,
+) -> Result<Vec<PendingTimesheet>> {
+	let pendings = TimesheetBmc::list_pending(&ctx, &mm).await?;
+
+	Ok(pendings)
+}
+
+/// Returns the comments of the timesheet `id`, oldest first.
+pub async fn list_timesheet_comments(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Vec<TimesheetComment>> {
+	let ParamsIded { id } = params;
+
+	let comments = TimesheetCommentBmc::list_for_timesheet(&ctx, &mm, id).await?;
+
+	Ok(comments)
+}
+
+/// Sets the approver of the timesheets of a user (admins only).
+pub async fn set_user_approver(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForSetApprover,
+) -> Result<()> {
+	let ParamsForSetApprover {
+		user_id,
+		approver_id,
+	} = params;
+
+	UserBmc::set_approver(&ctx, &mm, user_id, approver_id).await?;
+
+	Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::travel::{
+	Travel, TravelBmc, TravelFilter, TravelForCreate, TravelForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: TravelBmc,
+	Entity: Travel,
+	ForCreate: TravelForCreate,
+	ForUpdate: TravelForUpdate,
+	Filter: TravelFilter,
+	Suffix: travel,
+	PluralSuffix: travels,
+);
@@ -0,0 +1,15 @@
+use crate::generate_common_rpc_fns;
+use lib_core::model::work_schedule::{
+	WorkSchedule, WorkScheduleBmc, WorkScheduleFilter, WorkScheduleForCreate,
+	WorkScheduleForUpdate,
+};
+
+generate_common_rpc_fns!(
+	Bmc: WorkScheduleBmc,
+	Entity: WorkSchedule,
+	ForCreate: WorkScheduleForCreate,
+	ForUpdate: WorkScheduleForUpdate,
+	Filter: WorkScheduleFilter,
+	Suffix: work_schedule,
+	PluralSuffix: work_schedules,
+);
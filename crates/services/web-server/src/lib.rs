@@ -0,0 +1,75 @@
+//! The web-server library, exposing `routes_all` so the server can also be
+//! started in-process (e.g., by the `lib-client` tests).
+
+// region:    --- Modules
+
+mod config;
+mod error;
+mod log;
+mod web;
+
+pub use self::error::{Error, Result};
+use config::web_config;
+
+use crate::web::mw_auth::{mw_ctx_require, mw_ctx_resolve};
+use crate::web::mw_res_map::mw_reponse_map;
+use crate::web::mw_stamp::mw_req_stamp;
+use crate::web::routes_rpc::RpcState;
+use crate::web::{
+	routes_events, routes_export, routes_login, routes_rest, routes_static,
+	routes_ws,
+};
+use axum::{http::Method, middleware, Router};
+use lib_core::model::ModelManager;
+use tower_cookies::CookieManagerLayer;
+use tower_http::cors::{Any, CorsLayer}; // Needed for preflight
+
+// endregion: --- Modules
+
+/// Build all the routes of the web-server
+/// (login, rpc, events, ws, rest, export, calendar, graphql with the "graphql"
+/// feature, and static).
+pub fn routes_all(mm: ModelManager) -> Router {
+	// -- Define Routes
+	let rpc_state = RpcState { mm: mm.clone() };
+	let routes_rpc = web::routes_rpc::routes(rpc_state)
+		.route_layer(middleware::from_fn(mw_ctx_require));
+	let routes_events = routes_events::routes(mm.clone())
+		.route_layer(middleware::from_fn(mw_ctx_require));
+	let routes_ws = routes_ws::routes(RpcState { mm: mm.clone() })
+		.route_layer(middleware::from_fn(mw_ctx_require));
+	let routes_rest = routes_rest::routes(mm.clone())
+		.route_layer(middleware::from_fn(mw_ctx_require));
+	let routes_export = routes_export::routes(mm.clone())
+		.route_layer(middleware::from_fn(mw_ctx_require));
+
+	let cors_check = CorsLayer::new()
+		//.allow_origin("http://localhost:5173".parse::<HeaderValue>().unwrap())
+		.allow_origin(Any) // For Testing
+		.allow_headers(Any) // FOr Testing
+		.allow_methods([Method::POST, Method::OPTIONS]);
+
+	let routes_all = Router::new()
+		.merge(routes_login::routes(mm.clone()))
+		.merge(routes_export::routes_calendar(mm.clone()))
+		.nest("/api", routes_rpc)
+		.nest("/api", routes_events)
+		.nest("/api", routes_ws)
+		.nest("/api", routes_rest)
+		.nest("/api", routes_export);
+
+	#[cfg(feature = "graphql")]
+	let routes_all = routes_all.nest(
+		"/api",
+		web::routes_graphql::routes(mm.clone())
+			.route_layer(middleware::from_fn(mw_ctx_require)),
+	);
+
+	routes_all
+		.layer(middleware::map_response(mw_reponse_map))
+		.layer(middleware::from_fn_with_state(mm, mw_ctx_resolve))
+		.layer(middleware::from_fn(mw_req_stamp))
+		.layer(CookieManagerLayer::new())
+		.layer(cors_check)
+		.fallback_service(routes_static::serve_dir())
+}
@@ -3,7 +3,8 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use derive_more::From;
 use lib_auth::{pwd, token};
-use lib_core::model;
+use lib_core::model::anomaly::Anomaly;
+use lib_core::{export, model};
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 use std::sync::Arc;
@@ -31,6 +32,20 @@ pub enum Error {
 	// -- ReqStamp
 	ReqStampNotInResponseExt,
 
+	// -- Rest
+	RestPathInvalid(String),
+	RestBodyInvalid(String),
+	RestQueryInvalid {
+		param: Option<String>,
+		cause: String,
+	},
+	RestQueryUnknownProperty(String),
+
+	// -- Export
+	ExportQueryInvalid(String),
+	InvoiceFileInvalid(String),
+	CalendarTokenInvalid,
+
 	// -- Modules
 	#[from]
 	Model(model::Error),
@@ -40,6 +55,8 @@ pub enum Error {
 	Token(token::Error),
 	#[from]
 	Rpc(lib_rpc::Error),
+	#[from]
+	Export(export::Error),
 
 	// -- External Modules
 	#[from]
@@ -93,8 +110,210 @@ impl Error {
 			// -- Auth
 			CtxExt(_) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
 
+			// -- Rest
+			RestPathInvalid(cause) | RestBodyInvalid(cause) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: cause.to_string(),
+				},
+			),
+			RestQueryInvalid { param, cause } => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: match param {
+						Some(param) => format!("{param}: {cause}"),
+						None => cause.to_string(),
+					},
+				},
+			),
+			RestQueryUnknownProperty(prop) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: format!("unknown filter property '{prop}'"),
+				},
+			),
+
+			// -- Export
+			ExportQueryInvalid(cause) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: cause.to_string(),
+				},
+			),
+			Export(
+				ex @ (export::Error::DateRangeInvalid { .. }
+				| export::Error::DateParseFail(_)
+				| export::Error::UtcOffsetParseFail(_)
+				| export::Error::CsvColumnUnknown(_)
+				| export::Error::CsvFormatInvalid { .. }),
+			) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+			InvoiceFileInvalid(file) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: format!(
+						"invalid invoice file '{file}' (e.g., '1000.html')"
+					),
+				},
+			),
+			CalendarTokenInvalid => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
+
+			// -- Import
+			Rpc(lib_rpc::Error::Model(model::Error::ImportFileInvalid(cause))) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: cause.to_string(),
+				},
+			),
+			Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::ArchiveVersionUnsupported { .. }
+				| model::Error::ArchiveIdDuplicate { .. }
+				| model::Error::ArchiveRefInvalid { .. }),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+			Rpc(lib_rpc::Error::Export(ex)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Place & Travel
+			Model(
+				ex @ (model::Error::PlaceNameEmpty
+				| model::Error::TravelDistanceInvalid(_)),
+			)
+			| Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::PlaceNameEmpty
+				| model::Error::TravelDistanceInvalid(_)),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Work Schedule, Absence & Holiday
+			Model(
+				ex @ (model::Error::WorkScheduleHoursInvalid { .. }
+				| model::Error::AbsenceHoursInvalid(_)
+				| model::Error::HolidayCalendarNotFound(_)
+				| model::Error::HolidayRegionNotFound { .. }),
+			)
+			| Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::WorkScheduleHoursInvalid { .. }
+				| model::Error::AbsenceHoursInvalid(_)
+				| model::Error::HolidayCalendarNotFound(_)
+				| model::Error::HolidayRegionNotFound { .. }),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Rate, Invoice & Rounding
+			Model(
+				ex @ (model::Error::RateAmountInvalid(_)
+				| model::Error::InvoicePeriodInvalid { .. }
+				| model::Error::InvoiceRateMissing { .. }
+				| model::Error::InvoiceTaskTimeInvoiced { .. }
+				| model::Error::InvoiceLocked { .. }
+				| model::Error::RoundingIncrementInvalid(_)
+				| model::Error::RoundingMinInvalid(_)),
+			)
+			| Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::RateAmountInvalid(_)
+				| model::Error::InvoicePeriodInvalid { .. }
+				| model::Error::InvoiceRateMissing { .. }
+				| model::Error::InvoiceTaskTimeInvoiced { .. }
+				| model::Error::InvoiceLocked { .. }
+				| model::Error::RoundingIncrementInvalid(_)
+				| model::Error::RoundingMinInvalid(_)),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Admin & Period Lock
+			Model(model::Error::AdminOnly)
+			| Rpc(lib_rpc::Error::Model(model::Error::AdminOnly)) => {
+				(StatusCode::FORBIDDEN, ClientError::NO_AUTH)
+			}
+			Model(
+				ex @ (model::Error::PeriodLocked { .. }
+				| model::Error::PeriodLockInvalid { .. }
+				| model::Error::PeriodLockNotActive { .. }),
+			)
+			| Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::PeriodLocked { .. }
+				| model::Error::PeriodLockInvalid { .. }
+				| model::Error::PeriodLockNotActive { .. }),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Timesheet
+			Model(
+				model::Error::TimesheetNotOwner { .. }
+				| model::Error::TimesheetNotApprover { .. },
+			)
+			| Rpc(lib_rpc::Error::Model(
+				model::Error::TimesheetNotOwner { .. }
+				| model::Error::TimesheetNotApprover { .. },
+			)) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
+			Model(
+				ex @ (model::Error::TimesheetPeriodInvalid { .. }
+				| model::Error::TimesheetStatusInvalid { .. }
+				| model::Error::TimesheetApproverMissing { .. }
+				| model::Error::TimesheetCommentRequired { .. }),
+			)
+			| Rpc(lib_rpc::Error::Model(
+				ex @ (model::Error::TimesheetPeriodInvalid { .. }
+				| model::Error::TimesheetStatusInvalid { .. }
+				| model::Error::TimesheetApproverMissing { .. }
+				| model::Error::TimesheetCommentRequired { .. }),
+			)) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::INVALID_REQUEST {
+					cause: ex.to_string(),
+				},
+			),
+
+			// -- Anomalies
+			Model(model::Error::AnomalyRejected { anomalies })
+			| Rpc(lib_rpc::Error::Model(model::Error::AnomalyRejected {
+				anomalies,
+			})) => (
+				StatusCode::BAD_REQUEST,
+				ClientError::ANOMALY_REJECTED {
+					anomalies: anomalies.clone(),
+				},
+			),
+
 			// -- Model
-			Model(model::Error::EntityNotFound { entity, id }) => (
+			Model(model::Error::EntityNotFound { entity, id })
+			| Rpc(lib_rpc::Error::Model(model::Error::EntityNotFound {
+				entity,
+				id,
+			}))
+			| Export(export::Error::Model(model::Error::EntityNotFound {
+				entity,
+				id,
+			})) => (
 				StatusCode::BAD_REQUEST,
 				ClientError::ENTITY_NOT_FOUND { entity, id: *id },
 			),
@@ -115,6 +334,8 @@ pub enum ClientError {
 	LOGIN_FAIL,
 	NO_AUTH,
 	ENTITY_NOT_FOUND { entity: &'static str, id: i64 },
+	INVALID_REQUEST { cause: String },
+	ANOMALY_REJECTED { anomalies: Vec<Anomaly> },
 
 	SERVICE_ERROR,
 }
@@ -4,9 +4,15 @@ mod error;
 pub mod mw_auth;
 pub mod mw_res_map;
 pub mod mw_stamp;
+pub mod routes_events;
+pub mod routes_export;
+#[cfg(feature = "graphql")]
+pub mod routes_graphql;
 pub mod routes_login;
+pub mod routes_rest;
 pub mod routes_rpc;
 pub mod routes_static;
+pub mod routes_ws;
 
 pub use self::error::ClientError;
 pub use self::error::{Error, Result};
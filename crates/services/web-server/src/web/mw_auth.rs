@@ -3,8 +3,9 @@ use crate::web::{Error, Result};
 use async_trait::async_trait;
 use axum::body::Body;
 use axum::extract::{FromRequestParts, State};
+use axum::http::header::AUTHORIZATION;
 use axum::http::request::Parts;
-use axum::http::Request;
+use axum::http::{HeaderMap, Request};
 use axum::middleware::Next;
 use axum::response::Response;
 use lib_auth::token::{validate_web_token, Token};
@@ -35,10 +36,10 @@ pub async fn mw_ctx_resolve(
 ) -> Result<Response> {
 	debug!("{:<12} - mw_ctx_resolve", "MIDDLEWARE");
 
-	let ctx_ext_result = _ctx_resolve(mm, &cookies).await;
+	let ctx_ext_result = _ctx_resolve(mm, &cookies, req.headers()).await;
 
 	if ctx_ext_result.is_err()
-		&& !matches!(ctx_ext_result, Err(CtxExtError::TokenNotInCookie))
+		&& !matches!(ctx_ext_result, Err(CtxExtError::TokenNotInRequest))
 	{
 		cookies.remove(Cookie::from(AUTH_TOKEN))
 	}
@@ -50,35 +51,70 @@ pub async fn mw_ctx_resolve(
 	Ok(next.run(req).await)
 }
 
-async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResult {
-	// -- Get Token String
+async fn _ctx_resolve(
+	mm: State<ModelManager>,
+	cookies: &Cookies,
+	headers: &HeaderMap,
+) -> CtxExtResult {
+	let token = request_token(cookies, headers)?;
+	let user = validate_token(&mm, &token).await?;
+
+	// -- Update Token
+	//    (also for bearer clients, which can take the refreshed token
+	//     from the `Set-Cookie` header)
+	set_token_cookie(cookies, &user.username, user.token_salt)
+		.map_err(|_| CtxExtError::CannotSetTokenCookie)?;
+
+	// -- Create CtxExtResult
+	Ctx::new(user.id)
+		.map(CtxW)
+		.map_err(|ex| CtxExtError::CtxCreateFail(ex.to_string()))
+}
+
+/// Returns the auth token of the request, from the cookie, or else from
+/// the `Authorization: Bearer` header (e.g., for non-browser clients).
+///
+/// Note: After `mw_ctx_resolve`, the cookie is the refreshed token.
+pub(in crate::web) fn request_token(
+	cookies: &Cookies,
+	headers: &HeaderMap,
+) -> core::result::Result<Token, CtxExtError> {
 	let token = cookies
 		.get(AUTH_TOKEN)
 		.map(|c| c.value().to_string())
-		.ok_or(CtxExtError::TokenNotInCookie)?;
+		.or_else(|| bearer_token(headers))
+		.ok_or(CtxExtError::TokenNotInRequest)?;
 
-	// -- Parse Token
-	let token: Token = token.parse().map_err(|_| CtxExtError::TokenWrongFormat)?;
+	token.parse().map_err(|_| CtxExtError::TokenWrongFormat)
+}
 
+/// Validates the token (signature with the current user salt, and expiration),
+/// and returns its user.
+pub(in crate::web) async fn validate_token(
+	mm: &ModelManager,
+	token: &Token,
+) -> core::result::Result<UserForAuth, CtxExtError> {
 	// -- Get UserForAuth
 	let user: UserForAuth =
-		UserBmc::first_by_username(&Ctx::root_ctx(), &mm, &token.ident)
+		UserBmc::first_by_username(&Ctx::root_ctx(), mm, &token.ident)
 			.await
 			.map_err(|ex| CtxExtError::ModelAccessError(ex.to_string()))?
 			.ok_or(CtxExtError::UserNotFound)?;
 
 	// -- Validate Token
-	validate_web_token(&token, user.token_salt)
+	validate_web_token(token, user.token_salt)
 		.map_err(|_| CtxExtError::FailValidate)?;
 
-	// -- Update Token
-	set_token_cookie(cookies, &user.username, user.token_salt)
-		.map_err(|_| CtxExtError::CannotSetTokenCookie)?;
+	Ok(user)
+}
 
-	// -- Create CtxExtResult
-	Ctx::new(user.id)
-		.map(CtxW)
-		.map_err(|ex| CtxExtError::CtxCreateFail(ex.to_string()))
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+	headers
+		.get(AUTHORIZATION)?
+		.to_str()
+		.ok()?
+		.strip_prefix("Bearer ")
+		.map(|token| token.trim().to_string())
 }
 
 // region:    --- Ctx Extractor
@@ -107,7 +143,7 @@ type CtxExtResult = core::result::Result<CtxW, CtxExtError>;
 
 #[derive(Clone, Serialize, Debug)]
 pub enum CtxExtError {
-	TokenNotInCookie,
+	TokenNotInRequest,
 	TokenWrongFormat,
 
 	UserNotFound,
@@ -1,6 +1,7 @@
 use crate::log::log_request;
 use crate::web::mw_auth::CtxW;
 use crate::web::mw_stamp::ReqStamp;
+use crate::web::routes_rest::{problem_response, REST_PATH_PREFIX};
 use crate::web::routes_rpc::RpcInfo;
 use crate::web::{self};
 use axum::http::{Method, Uri};
@@ -34,6 +35,16 @@ pub async fn mw_reponse_map(
 		client_status_error
 			.as_ref()
 			.map(|(status_code, client_error)| {
+				// -- REST errors are RFC 7807 problem+json.
+				if uri.path().starts_with(REST_PATH_PREFIX) {
+					return problem_response(
+						*status_code,
+						client_error,
+						uri.path(),
+						uuid,
+					);
+				}
+
 				let client_error = to_value(client_error).ok();
 				let message = client_error.as_ref().and_then(|v| v.get("message"));
 				let detail = client_error.as_ref().and_then(|v| v.get("detail"));
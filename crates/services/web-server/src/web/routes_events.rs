@@ -0,0 +1,43 @@
+//! Server-Sent Events stream of the entity changes of the current user.
+//!
+//! Each committed create/update/delete of an entity owned by the user
+//! is sent as a `change` event, with the `ModelEvent` json as data
+//! (e.g., `{"entity": "tasktime", "id": 1001, "kind": "update"}`).
+//!
+//! Note: Events missed by a lagging client are skipped. The client can
+//!       catch up with the `sync_changes` rpc.
+
+use crate::web::mw_auth::CtxW;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use axum::Router;
+use futures::Stream;
+use lib_core::model::ModelManager;
+use std::convert::Infallible;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+
+// Axum router for '/api/events'
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route("/events", get(events_sse_handler))
+		.with_state(mm)
+}
+
+async fn events_sse_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+	let ctx = ctx.0;
+
+	let stream =
+		BroadcastStream::new(mm.subscribe_events()).filter_map(move |event| {
+			let event = event.ok().filter(|event| event.is_visible_to(&ctx))?;
+			let sse_event =
+				Event::default().event("change").json_data(event).ok()?;
+			Some(Ok(sse_event))
+		});
+
+	Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,201 @@
+//! Time export downloads and calendar subscriptions (see `lib_core::export`).
+//!
+//! - `GET /api/export/timesheet.csv?from=..&to=..` (authenticated)
+//!   - `from` and `to` are Rfc3339 times or dates
+//!     (e.g., `2024-03-01`, a `to` date being inclusive).
+//!   - Optional `columns` (e.g., `date,start,stop,duration,title`),
+//!     `date_format` and `time_format` (e.g., `[day]/[month]/[year]`),
+//!     and `utc_offset` (e.g., `+02:00`, default UTC).
+//! - `GET /api/export/timesheet.ics?from=..&to=..` (authenticated)
+//! - `GET /api/export/invoices/{id}.html` or `{id}.csv` (authenticated)
+//!   - The invoice rendering (see `lib_core::export::invoice`),
+//!     named after its number (e.g., `INV-00042.html`).
+//! - `GET /calendar/{calendar_token}.ics` (public, read-only)
+//!   - The entries of the last `CALENDAR_DAYS_BEFORE` days, up to
+//!     `CALENDAR_DAYS_AFTER` days from now, of the token user.
+//!   - The token is given by the `get_calendar_token` rpc
+//!     (and revoked by `reset_calendar_token`).
+
+use crate::web::mw_auth::CtxW;
+use crate::web::{Error, Result};
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Path, Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use lib_core::ctx::Ctx;
+use lib_core::export::csv::{self, CsvColumn, CsvOptions};
+use lib_core::export::{self, ics, invoice, parse_utc_offset, DateRange};
+use lib_core::model::user::{User, UserBmc};
+use lib_core::model::ModelManager;
+use serde::Deserialize;
+use time::UtcOffset;
+use uuid::Uuid;
+
+const CALENDAR_NAME: &str = "Tracked time";
+const CALENDAR_DAYS_BEFORE: i64 = 180;
+const CALENDAR_DAYS_AFTER: i64 = 60;
+
+const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+const ICS_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+
+// Axum router for '/api/export'
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route("/export/timesheet.csv", get(timesheet_csv_handler))
+		.route("/export/timesheet.ics", get(timesheet_ics_handler))
+		.route("/export/invoices/:file", get(invoice_handler))
+		.with_state(mm)
+}
+
+// Axum router for '/calendar' (no auth required, the token being in the path)
+pub fn routes_calendar(mm: ModelManager) -> Router {
+	Router::new()
+		.route("/calendar/:file", get(calendar_ics_handler))
+		.with_state(mm)
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+	from: String,
+	to: String,
+	columns: Option<String>,
+	date_format: Option<String>,
+	time_format: Option<String>,
+	utc_offset: Option<String>,
+}
+
+impl ExportQuery {
+	fn utc_offset(&self) -> Result<UtcOffset> {
+		let offset = match &self.utc_offset {
+			Some(offset) => parse_utc_offset(offset)?,
+			None => UtcOffset::UTC,
+		};
+
+		Ok(offset)
+	}
+
+	fn date_range(&self) -> Result<DateRange> {
+		let range = DateRange::parse(&self.from, &self.to, self.utc_offset()?)?;
+
+		Ok(range)
+	}
+}
+
+fn export_query(
+	query: std::result::Result<Query<ExportQuery>, QueryRejection>,
+) -> Result<ExportQuery> {
+	let Query(query) =
+		query.map_err(|ex| Error::ExportQueryInvalid(ex.body_text()))?;
+
+	Ok(query)
+}
+
+async fn timesheet_csv_handler(
+	State(mm): State<ModelManager>,
+	CtxW(ctx): CtxW,
+	query: std::result::Result<Query<ExportQuery>, QueryRejection>,
+) -> Result<Response> {
+	let query = export_query(query)?;
+
+	let mut options = CsvOptions {
+		utc_offset: query.utc_offset()?,
+		..Default::default()
+	};
+	if let Some(columns) = &query.columns {
+		options.columns = CsvColumn::parse_list(columns)?;
+	}
+	if let Some(date_format) = query.date_format.clone() {
+		options.date_format = date_format;
+	}
+	if let Some(time_format) = query.time_format.clone() {
+		options.time_format = time_format;
+	}
+
+	let entries = export::time_entries(&ctx, &mm, query.date_range()?).await?;
+	let csv = csv::to_csv(&entries, &options)?;
+
+	Ok(attachment_response(CSV_CONTENT_TYPE, "timesheet.csv", csv))
+}
+
+async fn timesheet_ics_handler(
+	State(mm): State<ModelManager>,
+	CtxW(ctx): CtxW,
+	query: std::result::Result<Query<ExportQuery>, QueryRejection>,
+) -> Result<Response> {
+	let query = export_query(query)?;
+
+	let entries = export::time_entries(&ctx, &mm, query.date_range()?).await?;
+	let ics = ics::to_ics(&entries, CALENDAR_NAME);
+
+	Ok(attachment_response(ICS_CONTENT_TYPE, "timesheet.ics", ics))
+}
+
+async fn invoice_handler(
+	State(mm): State<ModelManager>,
+	CtxW(ctx): CtxW,
+	Path(file): Path<String>,
+) -> Result<Response> {
+	let (id, extension) = file
+		.split_once('.')
+		.and_then(|(id, extension)| Some((id.parse::<i64>().ok()?, extension)))
+		.ok_or_else(|| Error::InvoiceFileInvalid(file.clone()))?;
+
+	let doc = invoice::invoice_document(&ctx, &mm, id).await?;
+	let response = match extension {
+		"html" => attachment_response(
+			HTML_CONTENT_TYPE,
+			&format!("{}.html", doc.number()),
+			invoice::to_html(&doc),
+		),
+		"csv" => attachment_response(
+			CSV_CONTENT_TYPE,
+			&format!("{}.csv", doc.number()),
+			invoice::to_csv(&doc)?,
+		),
+		_ => return Err(Error::InvoiceFileInvalid(file)),
+	};
+
+	Ok(response)
+}
+
+async fn calendar_ics_handler(
+	State(mm): State<ModelManager>,
+	Path(file): Path<String>,
+) -> Result<Response> {
+	let token = file
+		.strip_suffix(".ics")
+		.and_then(|token| Uuid::parse_str(token).ok())
+		.ok_or(Error::CalendarTokenInvalid)?;
+
+	let root_ctx = Ctx::root_ctx();
+	let user: User = UserBmc::first_by_calendar_token(&root_ctx, &mm, token)
+		.await?
+		.ok_or(Error::CalendarTokenInvalid)?;
+	let ctx = Ctx::new(user.id).map_err(|_| Error::CalendarTokenInvalid)?;
+
+	let range = DateRange::around_now(CALENDAR_DAYS_BEFORE, CALENDAR_DAYS_AFTER);
+	let entries = export::time_entries(&ctx, &mm, range).await?;
+	let ics = ics::to_ics(&entries, CALENDAR_NAME);
+
+	Ok(([(CONTENT_TYPE, ICS_CONTENT_TYPE)], ics).into_response())
+}
+
+fn attachment_response(
+	content_type: &'static str,
+	file_name: &str,
+	body: impl IntoResponse,
+) -> Response {
+	let disposition = format!("attachment; filename=\"{file_name}\"");
+
+	(
+		[
+			(CONTENT_TYPE, content_type.to_string()),
+			(CONTENT_DISPOSITION, disposition),
+		],
+		body,
+	)
+		.into_response()
+}
@@ -0,0 +1,216 @@
+//! GraphQL filter inputs, mirroring the lib-core `*Filter` structs.
+//!
+//! Each input serializes to the modql filter json
+//! (e.g., `{"title": {"$contains": "BB"}}`), from which the `*Filter`
+//! is deserialized (see `to_filters`).
+
+use async_graphql::{InputObject, Result};
+use lib_utils::time::Rfc3339;
+use modql::filter::ListOptions;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_with::{serde_as, skip_serializing_none};
+use time::OffsetDateTime;
+
+// region:    --- Operators
+
+#[skip_serializing_none]
+#[derive(InputObject, Serialize)]
+pub struct IntOps {
+	#[serde(rename = "$eq")]
+	eq: Option<i64>,
+	#[serde(rename = "$not")]
+	not: Option<i64>,
+	#[serde(rename = "$lt")]
+	lt: Option<i64>,
+	#[serde(rename = "$lte")]
+	lte: Option<i64>,
+	#[serde(rename = "$gt")]
+	gt: Option<i64>,
+	#[serde(rename = "$gte")]
+	gte: Option<i64>,
+	#[graphql(name = "in")]
+	#[serde(rename = "$in")]
+	in_: Option<Vec<i64>>,
+	#[serde(rename = "$notIn")]
+	not_in: Option<Vec<i64>>,
+	#[serde(rename = "$null")]
+	null: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(InputObject, Serialize)]
+pub struct StringOps {
+	#[serde(rename = "$eq")]
+	eq: Option<String>,
+	#[serde(rename = "$not")]
+	not: Option<String>,
+	#[serde(rename = "$lt")]
+	lt: Option<String>,
+	#[serde(rename = "$lte")]
+	lte: Option<String>,
+	#[serde(rename = "$gt")]
+	gt: Option<String>,
+	#[serde(rename = "$gte")]
+	gte: Option<String>,
+	#[graphql(name = "in")]
+	#[serde(rename = "$in")]
+	in_: Option<Vec<String>>,
+	#[serde(rename = "$notIn")]
+	not_in: Option<Vec<String>>,
+	#[serde(rename = "$contains")]
+	contains: Option<String>,
+	#[serde(rename = "$notContains")]
+	not_contains: Option<String>,
+	#[serde(rename = "$containsAny")]
+	contains_any: Option<Vec<String>>,
+	#[serde(rename = "$containsAll")]
+	contains_all: Option<Vec<String>>,
+	#[serde(rename = "$startsWith")]
+	starts_with: Option<String>,
+	#[serde(rename = "$notStartsWith")]
+	not_starts_with: Option<String>,
+	#[serde(rename = "$startsWithAny")]
+	starts_with_any: Option<Vec<String>>,
+	#[serde(rename = "$endsWith")]
+	ends_with: Option<String>,
+	#[serde(rename = "$notEndsWith")]
+	not_ends_with: Option<String>,
+	#[serde(rename = "$endsWithAny")]
+	ends_with_any: Option<Vec<String>>,
+	#[serde(rename = "$null")]
+	null: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(InputObject, Serialize)]
+pub struct BoolOps {
+	#[serde(rename = "$eq")]
+	eq: Option<bool>,
+	#[serde(rename = "$not")]
+	not: Option<bool>,
+	#[serde(rename = "$null")]
+	null: Option<bool>,
+}
+
+#[serde_as]
+#[skip_serializing_none]
+#[derive(InputObject, Serialize)]
+pub struct TimeOps {
+	#[serde(rename = "$eq")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	eq: Option<OffsetDateTime>,
+	#[serde(rename = "$not")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	not: Option<OffsetDateTime>,
+	#[serde(rename = "$lt")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	lt: Option<OffsetDateTime>,
+	#[serde(rename = "$lte")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	lte: Option<OffsetDateTime>,
+	#[serde(rename = "$gt")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	gt: Option<OffsetDateTime>,
+	#[serde(rename = "$gte")]
+	#[serde_as(as = "Option<Rfc3339>")]
+	gte: Option<OffsetDateTime>,
+	#[serde(rename = "$null")]
+	null: Option<bool>,
+}
+
+// endregion: --- Operators
+
+// region:    --- Filters
+
+/// Generates the filter input of an entity, with the common `id` and timestamp
+/// properties added (as `generate_filter!` does for the `*Filter`).
+macro_rules! generate_filter_input {
+	(
+		$input:ident {
+			$($field:ident: $ops:ty),* $(,)?
+		}
+	) => {
+		#[skip_serializing_none]
+		#[derive(InputObject, Serialize)]
+		pub struct $input {
+			id: Option<IntOps>,
+			$($field: Option<$ops>,)*
+
+			cid: Option<IntOps>,
+			ctime: Option<TimeOps>,
+			mid: Option<IntOps>,
+			mtime: Option<TimeOps>,
+		}
+	};
+}
+
+generate_filter_input!(ProjectFilterInput { name: StringOps });
+
+generate_filter_input!(TaskFilterInput {
+	project_id: IntOps,
+	title: StringOps,
+	done: BoolOps,
+});
+
+generate_filter_input!(TaskProgressFilterInput {
+	task_id: IntOps,
+	progress: IntOps,
+});
+
+generate_filter_input!(TaskTimeFilterInput {
+	task_id: IntOps,
+	comment: StringOps,
+	start_time: TimeOps,
+	stop_time: TimeOps,
+});
+
+generate_filter_input!(TimeRecordFilterInput {
+	place: StringOps,
+	place_id: IntOps,
+	start_time: TimeOps,
+	stop_time: TimeOps,
+});
+
+// endregion: --- Filters
+
+// region:    --- List Options
+
+#[skip_serializing_none]
+#[derive(InputObject, Serialize)]
+pub struct ListOptionsInput {
+	limit: Option<i64>,
+	offset: Option<i64>,
+	/// e.g., `["!title", "id"]` (`!` for descending).
+	order_bys: Option<Vec<String>>,
+}
+
+// endregion: --- List Options
+
+/// Returns the modql filters of the GraphQL filter inputs.
+pub fn to_filters<I, F>(inputs: Option<Vec<I>>) -> Result<Option<Vec<F>>>
+where
+	I: Serialize,
+	F: DeserializeOwned,
+{
+	let Some(inputs) = inputs else {
+		return Ok(None);
+	};
+
+	let filters = inputs
+		.into_iter()
+		.map(|input| Ok(serde_json::from_value(serde_json::to_value(input)?)?))
+		.collect::<Result<Vec<F>>>()?;
+
+	Ok(Some(filters))
+}
+
+pub fn to_list_options(
+	input: Option<ListOptionsInput>,
+) -> Result<Option<ListOptions>> {
+	let Some(input) = input else {
+		return Ok(None);
+	};
+
+	Ok(Some(serde_json::from_value(serde_json::to_value(input)?)?))
+}
@@ -0,0 +1,142 @@
+//! The `DataLoader` loader of the GraphQL relationships.
+//!
+//! Each key type loads its entities with a single list query (by pages of
+//! `LOAD_PAGE_SIZE`) for all the keys of a batch (e.g., the `TasksOfProject`
+//! of all the projects of a `projects` query are loaded with one `$in` filter).
+
+use async_graphql::dataloader::Loader;
+use lib_core::ctx::Ctx;
+use lib_core::model::project::{Project, ProjectBmc};
+use lib_core::model::task::{Task, TaskBmc};
+use lib_core::model::taskprogress::{TaskProgress, TaskProgressBmc};
+use lib_core::model::tasktime::{TaskTime, TaskTimeBmc};
+use lib_core::model::{self, ModelManager};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Page size of the loader list queries (at most the model `LIST_LIMIT_MAX`).
+const LOAD_PAGE_SIZE: i64 = 1000;
+
+pub struct ModelLoader {
+	pub ctx: Ctx,
+	pub mm: ModelManager,
+}
+
+// region:    --- Keys
+
+/// Entity by id keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProjectId(pub i64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(pub i64);
+
+/// Children by parent id keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TasksOfProject(pub i64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgressesOfTask(pub i64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskTimesOfTask(pub i64);
+
+// endregion: --- Keys
+
+// region:    --- Loader Impls
+
+/// Lists all the entities of the `$bmc` whose `$prop` is in `$values`.
+macro_rules! list_all_in {
+	($loader:ident, $bmc:ident, $prop:expr, $values:expr) => {{
+		let mut filter = Map::new();
+		filter.insert($prop.to_string(), json!({ "$in": $values }));
+		let filter = Value::Object(filter);
+
+		let mut entities = Vec::new();
+		loop {
+			let filter = serde_json::from_value(filter.clone())
+				.map_err(model::Error::from)?;
+			let list_options = serde_json::from_value(json!({
+				"limit": LOAD_PAGE_SIZE,
+				"offset": entities.len(),
+				"order_bys": "id",
+			}))
+			.map_err(model::Error::from)?;
+
+			let page = $bmc::list(
+				&$loader.ctx,
+				&$loader.mm,
+				Some(vec![filter]),
+				Some(list_options),
+			)
+			.await?;
+
+			let is_last_page = (page.len() as i64) < LOAD_PAGE_SIZE;
+			entities.extend(page);
+			if is_last_page {
+				break;
+			}
+		}
+		entities
+	}};
+}
+
+/// Implements the `Loader` of an entity by id key.
+macro_rules! impl_loader_by_id {
+	($key:ident, $bmc:ident, $entity:ty) => {
+		impl Loader<$key> for ModelLoader {
+			type Value = $entity;
+			type Error = Arc<model::Error>;
+
+			async fn load(
+				&self,
+				keys: &[$key],
+			) -> Result<HashMap<$key, $entity>, Self::Error> {
+				let ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+				let entities = list_all_in!(self, $bmc, "id", ids);
+
+				Ok(entities
+					.into_iter()
+					.map(|entity| ($key(entity.id), entity))
+					.collect())
+			}
+		}
+	};
+}
+
+/// Implements the `Loader` of the children of a parent id key
+/// (`$parent_id` being the property of the children entity).
+macro_rules! impl_loader_of_parent {
+	($key:ident, $bmc:ident, $entity:ty, $parent_id:ident) => {
+		impl Loader<$key> for ModelLoader {
+			type Value = Vec<$entity>;
+			type Error = Arc<model::Error>;
+
+			async fn load(
+				&self,
+				keys: &[$key],
+			) -> Result<HashMap<$key, Vec<$entity>>, Self::Error> {
+				let parent_ids: Vec<i64> = keys.iter().map(|key| key.0).collect();
+				let entities =
+					list_all_in!(self, $bmc, stringify!($parent_id), parent_ids);
+
+				let mut children: HashMap<$key, Vec<$entity>> = HashMap::new();
+				for entity in entities {
+					children
+						.entry($key(entity.$parent_id))
+						.or_default()
+						.push(entity);
+				}
+
+				Ok(children)
+			}
+		}
+	};
+}
+
+impl_loader_by_id!(ProjectId, ProjectBmc, Project);
+impl_loader_by_id!(TaskId, TaskBmc, Task);
+
+impl_loader_of_parent!(TasksOfProject, TaskBmc, Task, project_id);
+impl_loader_of_parent!(ProgressesOfTask, TaskProgressBmc, TaskProgress, task_id);
+impl_loader_of_parent!(TaskTimesOfTask, TaskTimeBmc, TaskTime, task_id);
+
+// endregion: --- Loader Impls
@@ -0,0 +1,70 @@
+//! GraphQL endpoint over the model layer (with the cargo feature `graphql`).
+//!
+//! - `POST /api/graphql` executes a (read only) query of the current user.
+//! - Each entity has a list query with `filters` (mirroring its `*Filter`,
+//!   see `filters`) and `listOptions`, and a get by `id` query.
+//! - The relationships (`Project.tasks`, `Task.project`, `Task.progresses`,
+//!   `Task.taskTimes`, `TaskProgress.task`, `TaskTime.task`) are resolved
+//!   by the request `DataLoader` (see `loaders`), which batches the ids of
+//!   the same level into a single list query per entity.
+//!
+//! e.g.,
+//!
+//! ```graphql
+//! {
+//!   projects(filters: [{name: {contains: "AAA"}}]) {
+//!     name
+//!     tasks { title done progresses { progress } taskTimes { startTime stopTime } }
+//!   }
+//! }
+//! ```
+
+// region:    --- Modules
+
+mod filters;
+mod loaders;
+mod nodes;
+mod query;
+
+use crate::web::mw_auth::CtxW;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::extract::State;
+use axum::routing::post;
+use axum::Router;
+use lib_core::model::ModelManager;
+use loaders::ModelLoader;
+use query::QueryRoot;
+
+// endregion: --- Modules
+
+type ModelSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+// Axum router for '/api/graphql'
+pub fn routes(mm: ModelManager) -> Router {
+	let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish();
+
+	Router::new()
+		.route("/graphql", post(graphql_handler))
+		.with_state((mm, schema))
+}
+
+async fn graphql_handler(
+	State((mm, schema)): State<(ModelManager, ModelSchema)>,
+	CtxW(ctx): CtxW,
+	req: GraphQLRequest,
+) -> GraphQLResponse {
+	// Note: The loader is per request, since it caches the entities of the ctx user.
+	let loader = DataLoader::new(
+		ModelLoader {
+			ctx: ctx.clone(),
+			mm: mm.clone(),
+		},
+		tokio::spawn,
+	);
+
+	let req = req.into_inner().data(ctx).data(mm).data(loader);
+
+	schema.execute(req).await.into()
+}
@@ -0,0 +1,245 @@
+//! The GraphQL object types of the lib-core entities, with their relationships
+//! resolved by the request `DataLoader<ModelLoader>`.
+
+use crate::web::routes_graphql::loaders::{
+	ModelLoader, ProgressesOfTask, ProjectId, TaskId, TaskTimesOfTask,
+	TasksOfProject,
+};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Object, Result};
+use lib_core::model::project::Project;
+use lib_core::model::task::Task;
+use lib_core::model::taskprogress::TaskProgress;
+use lib_core::model::tasktime::TaskTime;
+use lib_core::model::timerecord::TimeRecord;
+use time::OffsetDateTime;
+
+fn loader<'a>(gql_ctx: &'a Context<'_>) -> Result<&'a DataLoader<ModelLoader>> {
+	gql_ctx.data::<DataLoader<ModelLoader>>()
+}
+
+// region:    --- Project
+
+pub struct ProjectNode(pub Project);
+
+#[Object(name = "Project")]
+impl ProjectNode {
+	async fn id(&self) -> i64 {
+		self.0.id
+	}
+	async fn client_id(&self) -> Option<i64> {
+		self.0.client_id
+	}
+	async fn owner_id(&self) -> i64 {
+		self.0.owner_id
+	}
+	async fn name(&self) -> &str {
+		&self.0.name
+	}
+	async fn cid(&self) -> i64 {
+		self.0.cid
+	}
+	async fn ctime(&self) -> OffsetDateTime {
+		self.0.ctime
+	}
+	async fn mid(&self) -> i64 {
+		self.0.mid
+	}
+	async fn mtime(&self) -> OffsetDateTime {
+		self.0.mtime
+	}
+
+	async fn tasks(&self, gql_ctx: &Context<'_>) -> Result<Vec<TaskNode>> {
+		let tasks = loader(gql_ctx)?
+			.load_one(TasksOfProject(self.0.id))
+			.await?
+			.unwrap_or_default();
+
+		Ok(tasks.into_iter().map(TaskNode).collect())
+	}
+}
+
+// endregion: --- Project
+
+// region:    --- Task
+
+pub struct TaskNode(pub Task);
+
+#[Object(name = "Task")]
+impl TaskNode {
+	async fn id(&self) -> i64 {
+		self.0.id
+	}
+	async fn project_id(&self) -> i64 {
+		self.0.project_id
+	}
+	async fn title(&self) -> &str {
+		&self.0.title
+	}
+	async fn done(&self) -> bool {
+		self.0.done
+	}
+	async fn cid(&self) -> i64 {
+		self.0.cid
+	}
+	async fn ctime(&self) -> OffsetDateTime {
+		self.0.ctime
+	}
+	async fn mid(&self) -> i64 {
+		self.0.mid
+	}
+	async fn mtime(&self) -> OffsetDateTime {
+		self.0.mtime
+	}
+
+	async fn project(&self, gql_ctx: &Context<'_>) -> Result<Option<ProjectNode>> {
+		let project = loader(gql_ctx)?
+			.load_one(ProjectId(self.0.project_id))
+			.await?;
+
+		Ok(project.map(ProjectNode))
+	}
+
+	async fn progresses(
+		&self,
+		gql_ctx: &Context<'_>,
+	) -> Result<Vec<TaskProgressNode>> {
+		let progresses = loader(gql_ctx)?
+			.load_one(ProgressesOfTask(self.0.id))
+			.await?
+			.unwrap_or_default();
+
+		Ok(progresses.into_iter().map(TaskProgressNode).collect())
+	}
+
+	async fn task_times(&self, gql_ctx: &Context<'_>) -> Result<Vec<TaskTimeNode>> {
+		let task_times = loader(gql_ctx)?
+			.load_one(TaskTimesOfTask(self.0.id))
+			.await?
+			.unwrap_or_default();
+
+		Ok(task_times.into_iter().map(TaskTimeNode).collect())
+	}
+}
+
+// endregion: --- Task
+
+// region:    --- TaskProgress
+
+pub struct TaskProgressNode(pub TaskProgress);
+
+#[Object(name = "TaskProgress")]
+impl TaskProgressNode {
+	async fn id(&self) -> i64 {
+		self.0.id
+	}
+	async fn task_id(&self) -> i64 {
+		self.0.task_id
+	}
+	async fn progress(&self) -> i32 {
+		self.0.progress
+	}
+	async fn cid(&self) -> i64 {
+		self.0.cid
+	}
+	async fn ctime(&self) -> OffsetDateTime {
+		self.0.ctime
+	}
+	async fn mid(&self) -> i64 {
+		self.0.mid
+	}
+	async fn mtime(&self) -> OffsetDateTime {
+		self.0.mtime
+	}
+
+	async fn task(&self, gql_ctx: &Context<'_>) -> Result<Option<TaskNode>> {
+		let task = loader(gql_ctx)?.load_one(TaskId(self.0.task_id)).await?;
+
+		Ok(task.map(TaskNode))
+	}
+}
+
+// endregion: --- TaskProgress
+
+// region:    --- TaskTime
+
+pub struct TaskTimeNode(pub TaskTime);
+
+#[Object(name = "TaskTime")]
+impl TaskTimeNode {
+	async fn id(&self) -> i64 {
+		self.0.id
+	}
+	async fn task_id(&self) -> i64 {
+		self.0.task_id
+	}
+	async fn comment(&self) -> &str {
+		&self.0.comment
+	}
+	async fn start_time(&self) -> OffsetDateTime {
+		self.0.start_time
+	}
+	async fn stop_time(&self) -> OffsetDateTime {
+		self.0.stop_time
+	}
+	async fn billable(&self) -> bool {
+		self.0.billable
+	}
+	async fn cid(&self) -> i64 {
+		self.0.cid
+	}
+	async fn ctime(&self) -> OffsetDateTime {
+		self.0.ctime
+	}
+	async fn mid(&self) -> i64 {
+		self.0.mid
+	}
+	async fn mtime(&self) -> OffsetDateTime {
+		self.0.mtime
+	}
+
+	async fn task(&self, gql_ctx: &Context<'_>) -> Result<Option<TaskNode>> {
+		let task = loader(gql_ctx)?.load_one(TaskId(self.0.task_id)).await?;
+
+		Ok(task.map(TaskNode))
+	}
+}
+
+// endregion: --- TaskTime
+
+// region:    --- TimeRecord
+
+pub struct TimeRecordNode(pub TimeRecord);
+
+#[Object(name = "TimeRecord")]
+impl TimeRecordNode {
+	async fn id(&self) -> i64 {
+		self.0.id
+	}
+	async fn place(&self) -> &str {
+		&self.0.place
+	}
+	async fn place_id(&self) -> Option<i64> {
+		self.0.place_id
+	}
+	async fn start_time(&self) -> OffsetDateTime {
+		self.0.start_time
+	}
+	async fn stop_time(&self) -> OffsetDateTime {
+		self.0.stop_time
+	}
+	async fn cid(&self) -> i64 {
+		self.0.cid
+	}
+	async fn ctime(&self) -> OffsetDateTime {
+		self.0.ctime
+	}
+	async fn mid(&self) -> i64 {
+		self.0.mid
+	}
+	async fn mtime(&self) -> OffsetDateTime {
+		self.0.mtime
+	}
+}
+
+// endregion: --- TimeRecord
@@ -0,0 +1,149 @@
+use crate::web::routes_graphql::filters::{
+	to_filters, to_list_options, ListOptionsInput, ProjectFilterInput,
+	TaskFilterInput, TaskProgressFilterInput, TaskTimeFilterInput,
+	TimeRecordFilterInput,
+};
+use crate::web::routes_graphql::nodes::{
+	ProjectNode, TaskNode, TaskProgressNode, TaskTimeNode, TimeRecordNode,
+};
+use async_graphql::{Context, Object, Result};
+use lib_core::ctx::Ctx;
+use lib_core::model::project::ProjectBmc;
+use lib_core::model::task::TaskBmc;
+use lib_core::model::taskprogress::TaskProgressBmc;
+use lib_core::model::tasktime::TaskTimeBmc;
+use lib_core::model::timerecord::TimeRecordBmc;
+use lib_core::model::ModelManager;
+
+pub struct QueryRoot;
+
+fn model_ctx<'a>(gql_ctx: &'a Context<'_>) -> Result<(&'a Ctx, &'a ModelManager)> {
+	Ok((gql_ctx.data::<Ctx>()?, gql_ctx.data::<ModelManager>()?))
+}
+
+#[Object]
+impl QueryRoot {
+	// -- Project
+	async fn projects(
+		&self,
+		gql_ctx: &Context<'_>,
+		filters: Option<Vec<ProjectFilterInput>>,
+		list_options: Option<ListOptionsInput>,
+	) -> Result<Vec<ProjectNode>> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+		let filters = to_filters(filters)?;
+		let list_options = to_list_options(list_options)?;
+
+		let projects = ProjectBmc::list(ctx, mm, filters, list_options).await?;
+
+		Ok(projects.into_iter().map(ProjectNode).collect())
+	}
+
+	async fn project(&self, gql_ctx: &Context<'_>, id: i64) -> Result<ProjectNode> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+
+		Ok(ProjectNode(ProjectBmc::get(ctx, mm, id).await?))
+	}
+
+	// -- Task
+	async fn tasks(
+		&self,
+		gql_ctx: &Context<'_>,
+		filters: Option<Vec<TaskFilterInput>>,
+		list_options: Option<ListOptionsInput>,
+	) -> Result<Vec<TaskNode>> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+		let filters = to_filters(filters)?;
+		let list_options = to_list_options(list_options)?;
+
+		let tasks = TaskBmc::list(ctx, mm, filters, list_options).await?;
+
+		Ok(tasks.into_iter().map(TaskNode).collect())
+	}
+
+	async fn task(&self, gql_ctx: &Context<'_>, id: i64) -> Result<TaskNode> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+
+		Ok(TaskNode(TaskBmc::get(ctx, mm, id).await?))
+	}
+
+	// -- TaskProgress
+	async fn task_progresses(
+		&self,
+		gql_ctx: &Context<'_>,
+		filters: Option<Vec<TaskProgressFilterInput>>,
+		list_options: Option<ListOptionsInput>,
+	) -> Result<Vec<TaskProgressNode>> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+		let filters = to_filters(filters)?;
+		let list_options = to_list_options(list_options)?;
+
+		let progresses =
+			TaskProgressBmc::list(ctx, mm, filters, list_options).await?;
+
+		Ok(progresses.into_iter().map(TaskProgressNode).collect())
+	}
+
+	async fn task_progress(
+		&self,
+		gql_ctx: &Context<'_>,
+		id: i64,
+	) -> Result<TaskProgressNode> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+
+		Ok(TaskProgressNode(TaskProgressBmc::get(ctx, mm, id).await?))
+	}
+
+	// -- TaskTime
+	async fn task_times(
+		&self,
+		gql_ctx: &Context<'_>,
+		filters: Option<Vec<TaskTimeFilterInput>>,
+		list_options: Option<ListOptionsInput>,
+	) -> Result<Vec<TaskTimeNode>> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+		let filters = to_filters(filters)?;
+		let list_options = to_list_options(list_options)?;
+
+		let task_times = TaskTimeBmc::list(ctx, mm, filters, list_options).await?;
+
+		Ok(task_times.into_iter().map(TaskTimeNode).collect())
+	}
+
+	async fn task_time(
+		&self,
+		gql_ctx: &Context<'_>,
+		id: i64,
+	) -> Result<TaskTimeNode> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+
+		Ok(TaskTimeNode(TaskTimeBmc::get(ctx, mm, id).await?))
+	}
+
+	// -- TimeRecord
+	async fn time_records(
+		&self,
+		gql_ctx: &Context<'_>,
+		filters: Option<Vec<TimeRecordFilterInput>>,
+		list_options: Option<ListOptionsInput>,
+	) -> Result<Vec<TimeRecordNode>> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+		let filters = to_filters(filters)?;
+		let list_options = to_list_options(list_options)?;
+
+		let time_records =
+			TimeRecordBmc::list(ctx, mm, filters, list_options).await?;
+
+		Ok(time_records.into_iter().map(TimeRecordNode).collect())
+	}
+
+	async fn time_record(
+		&self,
+		gql_ctx: &Context<'_>,
+		id: i64,
+	) -> Result<TimeRecordNode> {
+		let (ctx, mm) = model_ctx(gql_ctx)?;
+
+		Ok(TimeRecordNode(TimeRecordBmc::get(ctx, mm, id).await?))
+	}
+}
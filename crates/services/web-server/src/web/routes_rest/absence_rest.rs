@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::absence::{
+	Absence, AbsenceBmc, AbsenceFilter, AbsenceForCreate, AbsenceForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: AbsenceBmc,
+	Entity: Absence,
+	ForCreate: AbsenceForCreate,
+	ForUpdate: AbsenceForUpdate,
+	Filter: AbsenceFilter,
+	Path: "/absences",
+);
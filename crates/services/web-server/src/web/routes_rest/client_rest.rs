@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::client::{
+	Client, ClientBmc, ClientFilter, ClientForCreate, ClientForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: ClientBmc,
+	Entity: Client,
+	ForCreate: ClientForCreate,
+	ForUpdate: ClientForUpdate,
+	Filter: ClientFilter,
+	Path: "/clients",
+);
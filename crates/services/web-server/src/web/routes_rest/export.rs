@@ -0,0 +1,136 @@
+//! Streaming exports of the REST api (`GET /api/v1/{entities}/export`).
+//!
+//! - `?format=ndjson` (default) streams one json entity per line
+//!   (`application/x-ndjson`).
+//! - `?format=csv` streams the entities as csv rows, after a header row
+//!   (`text/csv`).
+//! - The other params are the list filters and options (see `query`),
+//!   without the list limit cap (see `base::list_stream`).
+//!
+//! Note: The rows are written as they are fetched, so an error after the first
+//!       row (e.g., db connection lost) can only abort the response body.
+
+use crate::web::{Error, Result};
+use axum::body::Body;
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use lib_core::model::{self, EntityStream};
+use serde::Serialize;
+use std::io;
+use tracing::error;
+
+const FORMAT_PARAM: &str = "format";
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+	Ndjson,
+	Csv,
+}
+
+impl ExportFormat {
+	fn content_type(&self) -> &'static str {
+		match self {
+			ExportFormat::Ndjson => "application/x-ndjson",
+			ExportFormat::Csv => "text/csv",
+		}
+	}
+
+	fn extension(&self) -> &'static str {
+		match self {
+			ExportFormat::Ndjson => "ndjson",
+			ExportFormat::Csv => "csv",
+		}
+	}
+}
+
+/// Removes the `format` param from the query, and returns its `ExportFormat`.
+pub fn take_export_format(
+	query: &mut Vec<(String, String)>,
+) -> Result<ExportFormat> {
+	let mut format = ExportFormat::Ndjson;
+
+	let mut params = Vec::with_capacity(query.len());
+	for (param, value) in query.drain(..) {
+		if param != FORMAT_PARAM {
+			params.push((param, value));
+			continue;
+		}
+		format = match value.as_str() {
+			"ndjson" => ExportFormat::Ndjson,
+			"csv" => ExportFormat::Csv,
+			_ => {
+				return Err(Error::RestQueryInvalid {
+					param: Some(param),
+					cause: format!("'{value}' is not 'ndjson' or 'csv'"),
+				})
+			}
+		};
+	}
+	*query = params;
+
+	Ok(format)
+}
+
+/// The streaming response of the `entities` (`file_stem` being the
+/// attachment file name, without extension, e.g., "timerecords").
+pub fn export_response<E>(
+	format: ExportFormat,
+	file_stem: &str,
+	entities: EntityStream<E>,
+) -> Response
+where
+	E: Serialize + Send + 'static,
+{
+	let body = match format {
+		ExportFormat::Ndjson => Body::from_stream(entities.map(ndjson_line)),
+		ExportFormat::Csv => Body::from_stream(
+			entities
+				.enumerate()
+				.map(|(idx, entity)| csv_row(entity, idx == 0)),
+		),
+	};
+
+	let disposition = format!(
+		"attachment; filename=\"{file_stem}.{}\"",
+		format.extension()
+	);
+
+	(
+		[
+			(CONTENT_TYPE, format.content_type().to_string()),
+			(CONTENT_DISPOSITION, disposition),
+		],
+		body,
+	)
+		.into_response()
+}
+
+// region:    --- Support
+
+fn ndjson_line<E: Serialize>(entity: model::Result<E>) -> io::Result<Vec<u8>> {
+	let mut line = serde_json::to_vec(&entity.map_err(stream_error)?)?;
+	line.push(b'\n');
+
+	Ok(line)
+}
+
+fn csv_row<E: Serialize>(
+	entity: model::Result<E>,
+	with_header: bool,
+) -> io::Result<Vec<u8>> {
+	let mut writer = csv::WriterBuilder::new()
+		.has_headers(with_header)
+		.from_writer(Vec::new());
+	writer.serialize(entity.map_err(stream_error)?)?;
+
+	writer.into_inner().map_err(|ex| ex.into_error())
+}
+
+fn stream_error(ex: model::Error) -> io::Error {
+	error!("EXPORT STREAM ERROR - {ex:?}");
+
+	io::Error::other(ex.to_string())
+}
+
+// endregion: --- Support
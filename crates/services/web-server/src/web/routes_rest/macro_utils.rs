@@ -0,0 +1,145 @@
+/// Generates the REST handlers of an entity, delegating to its Bmc,
+/// and the `routes()` function of the module.
+///
+/// e.g.,
+///
+/// ```ignore
+/// generate_rest_routes!(
+///     Bmc: TaskBmc,
+///     Entity: Task,
+///     ForCreate: TaskForCreate,
+///     ForUpdate: TaskForUpdate,
+///     Filter: TaskFilter,
+///     Path: "/tasks",
+/// );
+/// ```
+/// Generates the `list`, `export`, `create`, `get`, `update`, and `delete`
+/// handlers, routed on `/tasks`, `/tasks/export`, and `/tasks/:id`.
+macro_rules! generate_rest_routes {
+	(
+		Bmc: $bmc:ident,
+		Entity: $entity:ty,
+		ForCreate: $for_create:ty,
+		ForUpdate: $for_update:ty,
+		Filter: $filter:ty,
+		Path: $path:literal
+		$(,)?
+	) => {
+		use axum::extract::rejection::{
+			JsonRejection, PathRejection, QueryRejection,
+		};
+		use axum::extract::{Path, Query, State};
+		use axum::http::StatusCode;
+		use axum::response::Response;
+		use axum::routing::get;
+		use axum::{Json, Router};
+		use lib_core::model::ModelManager;
+		use $crate::web::mw_auth::CtxW;
+		use $crate::web::routes_rest::export::{
+			export_response, take_export_format,
+		};
+		use $crate::web::routes_rest::query::parse_list_query;
+		use $crate::web::{Error, Result};
+
+		pub fn routes() -> Router<ModelManager> {
+			Router::new()
+				.route($path, get(list).post(create))
+				.route(concat!($path, "/export"), get(export))
+				.route(
+					concat!($path, "/:id"),
+					get(get_one).patch(update).delete(delete),
+				)
+		}
+
+		async fn list(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			query: std::result::Result<Query<Vec<(String, String)>>, QueryRejection>,
+		) -> Result<Json<Vec<$entity>>> {
+			let Query(query) = query.map_err(|ex| Error::RestQueryInvalid {
+				param: None,
+				cause: ex.body_text(),
+			})?;
+			let (filters, list_options) = parse_list_query::<$filter>(query)?;
+
+			let entities = $bmc::list(&ctx, &mm, filters, list_options).await?;
+
+			Ok(Json(entities))
+		}
+
+		async fn export(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			query: std::result::Result<Query<Vec<(String, String)>>, QueryRejection>,
+		) -> Result<Response> {
+			let Query(mut query) = query.map_err(|ex| Error::RestQueryInvalid {
+				param: None,
+				cause: ex.body_text(),
+			})?;
+			let format = take_export_format(&mut query)?;
+			let (filters, list_options) = parse_list_query::<$filter>(query)?;
+
+			let entities = $bmc::list_stream(&ctx, &mm, filters, list_options)?;
+
+			Ok(export_response(format, &$path[1..], entities))
+		}
+
+		async fn create(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			data: std::result::Result<Json<$for_create>, JsonRejection>,
+		) -> Result<(StatusCode, Json<$entity>)> {
+			let Json(data) =
+				data.map_err(|ex| Error::RestBodyInvalid(ex.body_text()))?;
+
+			let id = $bmc::create(&ctx, &mm, data).await?;
+			let entity = $bmc::get(&ctx, &mm, id).await?;
+
+			Ok((StatusCode::CREATED, Json(entity)))
+		}
+
+		async fn get_one(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			id: std::result::Result<Path<i64>, PathRejection>,
+		) -> Result<Json<$entity>> {
+			let Path(id) =
+				id.map_err(|ex| Error::RestPathInvalid(ex.body_text()))?;
+
+			let entity = $bmc::get(&ctx, &mm, id).await?;
+
+			Ok(Json(entity))
+		}
+
+		async fn update(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			id: std::result::Result<Path<i64>, PathRejection>,
+			data: std::result::Result<Json<$for_update>, JsonRejection>,
+		) -> Result<Json<$entity>> {
+			let Path(id) =
+				id.map_err(|ex| Error::RestPathInvalid(ex.body_text()))?;
+			let Json(data) =
+				data.map_err(|ex| Error::RestBodyInvalid(ex.body_text()))?;
+
+			$bmc::update(&ctx, &mm, id, data).await?;
+			let entity = $bmc::get(&ctx, &mm, id).await?;
+
+			Ok(Json(entity))
+		}
+
+		async fn delete(
+			State(mm): State<ModelManager>,
+			CtxW(ctx): CtxW,
+			id: std::result::Result<Path<i64>, PathRejection>,
+		) -> Result<StatusCode> {
+			let Path(id) =
+				id.map_err(|ex| Error::RestPathInvalid(ex.body_text()))?;
+
+			$bmc::delete(&ctx, &mm, id).await?;
+
+			Ok(StatusCode::NO_CONTENT)
+		}
+	};
+}
+pub(super) use generate_rest_routes;
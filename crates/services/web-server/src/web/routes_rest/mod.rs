@@ -0,0 +1,63 @@
+//! REST api of the entities, alongside the json-rpc api
+//! (e.g., for the integrations only doing plain REST).
+//!
+//! For each entity (e.g., `projects`):
+//!
+//! - `GET    /api/v1/projects`        list (filters and options, see `query`)
+//! - `GET    /api/v1/projects/export` streaming NDJSON or CSV (see `export`)
+//! - `POST   /api/v1/projects`        create (returns `201` with the entity)
+//! - `GET    /api/v1/projects/{id}`   get
+//! - `PATCH  /api/v1/projects/{id}`   update (returns the updated entity)
+//! - `DELETE /api/v1/projects/{id}`   delete (returns `204`)
+//!
+//! All delegate to the same Bmc functions as the json-rpc api,
+//! and errors are returned as RFC 7807 `application/problem+json` (see `problem`).
+
+// region:    --- Modules
+
+mod export;
+mod macro_utils;
+mod problem;
+mod query;
+
+mod absence_rest;
+mod client_rest;
+mod place_rest;
+mod project_rest;
+mod rate_rest;
+mod rounding_policy_rest;
+mod task_rest;
+mod taskprogress_rest;
+mod tasktime_rest;
+mod timerecord_rest;
+mod travel_rest;
+mod work_schedule_rest;
+
+pub use problem::problem_response;
+
+use axum::Router;
+use lib_core::model::ModelManager;
+
+// endregion: --- Modules
+
+/// The path prefix of the REST api (for `mw_res_map` to render problem+json errors).
+pub const REST_PATH_PREFIX: &str = "/api/v1/";
+
+// Axum router for '/api/v1'
+pub fn routes(mm: ModelManager) -> Router {
+	let routes_v1 = Router::new()
+		.merge(project_rest::routes())
+		.merge(task_rest::routes())
+		.merge(taskprogress_rest::routes())
+		.merge(tasktime_rest::routes())
+		.merge(timerecord_rest::routes())
+		.merge(place_rest::routes())
+		.merge(travel_rest::routes())
+		.merge(work_schedule_rest::routes())
+		.merge(absence_rest::routes())
+		.merge(client_rest::routes())
+		.merge(rate_rest::routes())
+		.merge(rounding_policy_rest::routes());
+
+	Router::new().nest("/v1", routes_v1).with_state(mm)
+}
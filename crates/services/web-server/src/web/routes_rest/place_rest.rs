@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::place::{
+	Place, PlaceBmc, PlaceFilter, PlaceForCreate, PlaceForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: PlaceBmc,
+	Entity: Place,
+	ForCreate: PlaceForCreate,
+	ForUpdate: PlaceForUpdate,
+	Filter: PlaceFilter,
+	Path: "/places",
+);
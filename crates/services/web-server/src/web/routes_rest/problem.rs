@@ -0,0 +1,70 @@
+//! RFC 7807 `application/problem+json` error responses of the REST api.
+//!
+//! e.g.,
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Not Found",
+//!   "status": 404,
+//!   "code": "ENTITY_NOT_FOUND",
+//!   "instance": "/api/v1/projects/123",
+//!   "req_uuid": "...",
+//!   "entity": "project",
+//!   "id": 123
+//! }
+//! ```
+//!
+//! The `code` is the `ClientError` variant, and its detail properties
+//! (if any) are added as extension members.
+
+use crate::web::ClientError;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::{json, to_value, Value};
+use uuid::Uuid;
+
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+pub fn problem_response(
+	status: StatusCode,
+	client_error: &ClientError,
+	instance: &str,
+	req_uuid: Uuid,
+) -> Response {
+	// Note: For REST, a missing entity is a `404` (`400` for json-rpc).
+	let status = match client_error {
+		ClientError::ENTITY_NOT_FOUND { .. } => StatusCode::NOT_FOUND,
+		_ => status,
+	};
+
+	let mut problem = json!({
+		"type": "about:blank",
+		"title": status.canonical_reason(),
+		"status": status.as_u16(),
+		"code": client_error.as_ref(),
+		"instance": instance,
+		"req_uuid": req_uuid.to_string(),
+	});
+
+	// -- Add the ClientError detail as extension members.
+	let detail = to_value(client_error)
+		.ok()
+		.and_then(|mut v| v.get_mut("detail").map(Value::take));
+	if let (Some(Value::Object(detail)), Some(problem)) =
+		(detail, problem.as_object_mut())
+	{
+		for (name, value) in detail {
+			problem.entry(name).or_insert(value);
+		}
+	}
+
+	(
+		status,
+		[(CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+		Json(problem),
+	)
+		.into_response()
+}
@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::project::{
+	Project, ProjectBmc, ProjectFilter, ProjectForCreate, ProjectForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: ProjectBmc,
+	Entity: Project,
+	ForCreate: ProjectForCreate,
+	ForUpdate: ProjectForUpdate,
+	Filter: ProjectFilter,
+	Path: "/projects",
+);
@@ -0,0 +1,249 @@
+//! Translation of the REST list query string into the entity `*Filter`
+//! and the `ListOptions`.
+//!
+//! - `prop=value` is the `$eq` condition of the filter property
+//!   (e.g., `?title=task%20AAA`).
+//! - `prop.$op=value` is the condition of any other modql operator
+//!   (e.g., `?title.$contains=AAA`), with comma separated values for the list
+//!   operators (e.g., `?project_id.$in=1000,1001`).
+//! - `limit`, `offset`, and `order_bys` (comma separated, e.g., `?order_bys=!title,id`)
+//!   are the `ListOptions`.
+//!
+//! All the conditions go in a single filter (i.e., they are ANDed), and the values
+//! are typed from the JSON Schema of their filter property (e.g., `integer`).
+
+use crate::web::{Error, Result};
+use modql::filter::ListOptions;
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+const LIST_OPTIONS_PARAMS: &[&str] = &["limit", "offset", "order_bys"];
+
+/// The modql operators taking a list of values.
+const LIST_OPS: &[&str] = &[
+	"$in",
+	"$notIn",
+	"$containsAny",
+	"$containsAll",
+	"$startsWithAny",
+	"$endsWithAny",
+];
+
+pub fn parse_list_query<F>(
+	query: Vec<(String, String)>,
+) -> Result<(Option<Vec<F>>, Option<ListOptions>)>
+where
+	F: DeserializeOwned + JsonSchema,
+{
+	let filter_schema = serde_json::to_value(schema_for!(F))?;
+
+	let mut filter = Map::new();
+	let mut list_options = Map::new();
+
+	for (param, value) in query {
+		// -- List Options
+		if LIST_OPTIONS_PARAMS.contains(&param.as_str()) {
+			let value = match param.as_str() {
+				"order_bys" => json!(value.split(',').collect::<Vec<_>>()),
+				_ => typed_value(&param, "integer", &value)?,
+			};
+			list_options.insert(param, value);
+			continue;
+		}
+
+		// -- Filter condition
+		let (prop, op) = param.split_once('.').unwrap_or((param.as_str(), "$eq"));
+		let value_type = filter_schema["properties"][prop]["anyOf"][0]["type"]
+			.as_str()
+			.ok_or_else(|| Error::RestQueryUnknownProperty(prop.to_string()))?;
+
+		let value = if LIST_OPS.contains(&op) {
+			let values = value
+				.split(',')
+				.map(|value| typed_value(&param, value_type, value))
+				.collect::<Result<Vec<_>>>()?;
+			Value::Array(values)
+		} else if op == "$null" {
+			typed_value(&param, "boolean", &value)?
+		} else {
+			typed_value(&param, value_type, &value)?
+		};
+
+		if let Some(ops) = filter
+			.entry(prop)
+			.or_insert_with(|| json!({}))
+			.as_object_mut()
+		{
+			ops.insert(op.to_string(), value);
+		}
+	}
+
+	// -- Deserialize
+	let filters = if filter.is_empty() {
+		None
+	} else {
+		let filter = serde_json::from_value::<F>(Value::Object(filter))
+			.map_err(|ex| query_invalid(None, ex.to_string()))?;
+		Some(vec![filter])
+	};
+	let list_options = if list_options.is_empty() {
+		None
+	} else {
+		let list_options = serde_json::from_value(Value::Object(list_options))
+			.map_err(|ex| query_invalid(None, ex.to_string()))?;
+		Some(list_options)
+	};
+
+	Ok((filters, list_options))
+}
+
+/// Returns the json value of the query `value` for the JSON Schema `value_type`.
+fn typed_value(param: &str, value_type: &str, value: &str) -> Result<Value> {
+	let typed_value = match value_type {
+		"integer" => value.parse::<i64>().ok().map(Value::from),
+		"number" => value.parse::<f64>().ok().map(Value::from),
+		"boolean" => value.parse::<bool>().ok().map(Value::from),
+		_ => Some(Value::from(value)),
+	};
+
+	typed_value.ok_or_else(|| {
+		query_invalid(Some(param), format!("'{value}' is not a {value_type}"))
+	})
+}
+
+fn query_invalid(param: Option<&str>, cause: String) -> Error {
+	Error::RestQueryInvalid {
+		param: param.map(|param| param.to_string()),
+		cause,
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::{anyhow, Result};
+	use lib_core::model::task::TaskFilter;
+
+	fn fx_query(params: &[(&str, &str)]) -> Vec<(String, String)> {
+		params
+			.iter()
+			.map(|(param, value)| (param.to_string(), value.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn test_parse_list_query_filter_ok() -> Result<()> {
+		// -- Fixtures
+		let fx_query = fx_query(&[
+			("title", "task AAA"),
+			("project_id.$in", "1000,1001"),
+			("done.$eq", "false"),
+		]);
+
+		// -- Exec
+		let (filters, list_options) = parse_list_query::<TaskFilter>(fx_query)?;
+
+		// -- Check
+		assert!(list_options.is_none());
+		let filters = filters.ok_or_else(|| anyhow!("should have filters"))?;
+		assert_eq!(filters.len(), 1);
+		let filter = format!("{:?}", filters[0]);
+		assert!(filter.contains(r#"title: Some(OpValsString([Eq("task AAA")]))"#));
+		assert!(filter.contains("project_id: Some(OpValsInt64([In([1000, 1001])]))"));
+		assert!(filter.contains("done: Some(OpValsBool([Eq(false)]))"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_list_query_list_options_ok() -> Result<()> {
+		// -- Fixtures
+		let fx_query = fx_query(&[
+			("limit", "10"),
+			("offset", "5"),
+			("order_bys", "!title,id"),
+		]);
+
+		// -- Exec
+		let (filters, list_options) = parse_list_query::<TaskFilter>(fx_query)?;
+
+		// -- Check
+		assert!(filters.is_none());
+		let list_options =
+			list_options.ok_or_else(|| anyhow!("should have list_options"))?;
+		assert_eq!(list_options.limit, Some(10));
+		assert_eq!(list_options.offset, Some(5));
+		assert_eq!(
+			format!("{:?}", list_options.order_bys),
+			r#"Some(OrderBys([Desc("title"), Asc("id")]))"#
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_list_query_empty_ok() -> Result<()> {
+		// -- Exec
+		let (filters, list_options) = parse_list_query::<TaskFilter>(Vec::new())?;
+
+		// -- Check
+		assert!(filters.is_none());
+		assert!(list_options.is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_list_query_err_invalid_value() -> Result<()> {
+		// -- Fixtures
+		let fx_cases = [("limit", "ten"), ("project_id", "abc"), ("done", "maybe")];
+
+		for (fx_param, fx_value) in fx_cases {
+			// -- Exec
+			let res =
+				parse_list_query::<TaskFilter>(fx_query(&[(fx_param, fx_value)]));
+
+			// -- Check
+			match res {
+				Err(Error::RestQueryInvalid {
+					param: Some(param), ..
+				}) => assert_eq!(param, fx_param),
+				other => panic!("should be RestQueryInvalid, but was {other:?}"),
+			}
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_list_query_err_unknown_property() -> Result<()> {
+		// -- Exec
+		let res = parse_list_query::<TaskFilter>(fx_query(&[("nope", "1")]));
+
+		// -- Check
+		assert!(
+			matches!(&res, Err(Error::RestQueryUnknownProperty(prop)) if prop == "nope"),
+			"should be RestQueryUnknownProperty, but was {res:?}"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_list_query_err_unknown_op() -> Result<()> {
+		// -- Exec
+		let res =
+			parse_list_query::<TaskFilter>(fx_query(&[("title.$nope", "AAA")]));
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::RestQueryInvalid { param: None, .. })),
+			"should be RestQueryInvalid, but was {res:?}"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::rate::{
+	Rate, RateBmc, RateFilter, RateForCreate, RateForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: RateBmc,
+	Entity: Rate,
+	ForCreate: RateForCreate,
+	ForUpdate: RateForUpdate,
+	Filter: RateFilter,
+	Path: "/rates",
+);
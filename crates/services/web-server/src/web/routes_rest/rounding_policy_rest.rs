@@ -0,0 +1,14 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::rounding::{
+	RoundingPolicy, RoundingPolicyBmc, RoundingPolicyFilter,
+	RoundingPolicyForCreate, RoundingPolicyForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: RoundingPolicyBmc,
+	Entity: RoundingPolicy,
+	ForCreate: RoundingPolicyForCreate,
+	ForUpdate: RoundingPolicyForUpdate,
+	Filter: RoundingPolicyFilter,
+	Path: "/rounding-policies",
+);
@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::task::{
+	Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: TaskBmc,
+	Entity: Task,
+	ForCreate: TaskForCreate,
+	ForUpdate: TaskForUpdate,
+	Filter: TaskFilter,
+	Path: "/tasks",
+);
@@ -0,0 +1,14 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::taskprogress::{
+	TaskProgress, TaskProgressBmc, TaskProgressFilter, TaskProgressForCreate,
+	TaskProgressForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: TaskProgressBmc,
+	Entity: TaskProgress,
+	ForCreate: TaskProgressForCreate,
+	ForUpdate: TaskProgressForUpdate,
+	Filter: TaskProgressFilter,
+	Path: "/taskprogresses",
+);
@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::tasktime::{
+	TaskTime, TaskTimeBmc, TaskTimeFilter, TaskTimeForCreate, TaskTimeForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: TaskTimeBmc,
+	Entity: TaskTime,
+	ForCreate: TaskTimeForCreate,
+	ForUpdate: TaskTimeForUpdate,
+	Filter: TaskTimeFilter,
+	Path: "/tasktimes",
+);
@@ -0,0 +1,14 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::timerecord::{
+	TimeRecord, TimeRecordBmc, TimeRecordFilter, TimeRecordForCreate,
+	TimeRecordForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: TimeRecordBmc,
+	Entity: TimeRecord,
+	ForCreate: TimeRecordForCreate,
+	ForUpdate: TimeRecordForUpdate,
+	Filter: TimeRecordFilter,
+	Path: "/timerecords",
+);
@@ -0,0 +1,13 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::travel::{
+	Travel, TravelBmc, TravelFilter, TravelForCreate, TravelForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: TravelBmc,
+	Entity: Travel,
+	ForCreate: TravelForCreate,
+	ForUpdate: TravelForUpdate,
+	Filter: TravelFilter,
+	Path: "/travels",
+);
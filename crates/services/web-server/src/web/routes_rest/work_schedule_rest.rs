@@ -0,0 +1,14 @@
+use crate::web::routes_rest::macro_utils::generate_rest_routes;
+use lib_core::model::work_schedule::{
+	WorkSchedule, WorkScheduleBmc, WorkScheduleFilter, WorkScheduleForCreate,
+	WorkScheduleForUpdate,
+};
+
+generate_rest_routes!(
+	Bmc: WorkScheduleBmc,
+	Entity: WorkSchedule,
+	ForCreate: WorkScheduleForCreate,
+	ForUpdate: WorkScheduleForUpdate,
+	Filter: WorkScheduleFilter,
+	Path: "/work-schedules",
+);
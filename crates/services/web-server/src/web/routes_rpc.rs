@@ -4,11 +4,9 @@ use axum::response::{IntoResponse, Response};
 use axum::routing::post;
 use axum::{Json, Router};
 use lib_core::model::ModelManager;
+use lib_rpc::interceptors::{RpcParamsLogInterceptor, RpcTimingInterceptor};
 use lib_rpc::router::RpcRouter;
-use lib_rpc::{
-	project_rpc, task_rpc, taskprogress_rpc, timerecord_rpc, RpcRequest,
-	RpcResources,
-};
+use lib_rpc::{all_rpc_router, RpcRequest, RpcResources};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
@@ -31,12 +29,7 @@ pub struct RpcInfo {
 
 // Axum router for '/api/rpc'
 pub fn routes(rpc_state: RpcState) -> Router {
-	// Build the combined RpcRouter.
-	let rpc_router = RpcRouter::new()
-		.extend(timerecord_rpc::rpc_router())
-		.extend(taskprogress_rpc::rpc_router())
-		.extend(task_rpc::rpc_router())
-		.extend(project_rpc::rpc_router());
+	let rpc_router = rpc_router();
 
 	// Build the Axum Router for '/rpc'
 	Router::new()
@@ -44,6 +37,14 @@ pub fn routes(rpc_state: RpcState) -> Router {
 		.with_state((rpc_state, Arc::new(rpc_router)))
 }
 
+/// Build the combined RpcRouter.
+/// (shared by the '/api/rpc' and '/api/ws' transports)
+pub fn rpc_router() -> RpcRouter {
+	all_rpc_router()
+		.with_interceptor(RpcTimingInterceptor::default())
+		.with_interceptor(RpcParamsLogInterceptor::default())
+}
+
 async fn rpc_axum_handler(
 	State((rpc_state, rpc_router)): State<(RpcState, Arc<RpcRouter>)>,
 	ctx: CtxW,
@@ -0,0 +1,215 @@
+//! WebSocket transport for the json-rpc `RpcRouter` ('/api/ws').
+//!
+//! - Each text message is a json-rpc request, dispatched to the same `RpcRouter`
+//!   and `RpcResources` as '/api/rpc', and answered with the same
+//!   `{id, result}` / `{id, error}` response bodies.
+//! - The connection is authenticated by the same auth-token cookie, when upgrading.
+//!   The token (as refreshed by the upgrade) is then re-validated on each message,
+//!   and every `AUTH_CHECK_INTERVAL`, and the socket is closed with the policy
+//!   violation code (1008) once it fails (e.g., expired, or logged off),
+//!   for the client to reconnect with a fresh token.
+//! - `subscribe` / `unsubscribe` (without params) are handled by the socket itself,
+//!   and turn on/off the push of the entity change events of the user, as
+//!   `{"method": "event", "params": {"entity", "id", "kind"}}` notifications.
+
+use crate::web::mw_auth::{request_token, validate_token, CtxExtError, CtxW};
+use crate::web::routes_rpc::{self, RpcState};
+use crate::web::{self, Error, Result};
+use axum::extract::ws::{
+	close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade,
+};
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use lib_auth::token::Token;
+use lib_core::ctx::Ctx;
+use lib_core::model::event::ModelEvent;
+use lib_rpc::router::RpcRouter;
+use lib_rpc::{RpcRequest, RpcResources};
+use serde_json::{json, to_value, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{interval_at, Instant};
+use tower_cookies::Cookies;
+use tracing::debug;
+
+/// The period of the auth token re-validation of an idle socket.
+const AUTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// Axum router for '/api/ws'
+pub fn routes(rpc_state: RpcState) -> Router {
+	let rpc_router = routes_rpc::rpc_router();
+
+	Router::new()
+		.route("/ws", get(ws_axum_handler))
+		.with_state((rpc_state, Arc::new(rpc_router)))
+}
+
+async fn ws_axum_handler(
+	State((rpc_state, rpc_router)): State<(RpcState, Arc<RpcRouter>)>,
+	ctx: CtxW,
+	cookies: Cookies,
+	headers: HeaderMap,
+	ws: WebSocketUpgrade,
+) -> Result<Response> {
+	let ctx = ctx.0;
+	let token = request_token(&cookies, &headers).map_err(Error::CtxExt)?;
+
+	Ok(ws.on_upgrade(move |socket| {
+		handle_socket(socket, ctx, token, rpc_state, rpc_router)
+	}))
+}
+
+async fn handle_socket(
+	mut socket: WebSocket,
+	ctx: Ctx,
+	token: Token,
+	rpc_state: RpcState,
+	rpc_router: Arc<RpcRouter>,
+) {
+	let mut events_rx: Option<broadcast::Receiver<ModelEvent>> = None;
+	let mut auth_check =
+		interval_at(Instant::now() + AUTH_CHECK_INTERVAL, AUTH_CHECK_INTERVAL);
+
+	loop {
+		let out_msg = tokio::select! {
+			in_msg = socket.recv() => {
+				let text = match in_msg {
+					Some(Ok(Message::Text(text))) => text,
+					Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+					// Note: Ping/Pong are handled by axum.
+					Some(Ok(_)) => continue,
+				};
+				if let Err(ex) = validate_token(&rpc_state.mm, &token).await {
+					close_auth_failed(&mut socket, ex).await;
+					break;
+				}
+
+				handle_text(&text, &ctx, &rpc_state, &rpc_router, &mut events_rx)
+					.await
+			}
+
+			event = recv_event(&mut events_rx) => {
+				match event {
+					Some(event) if event.is_visible_to(&ctx) => json!({
+						"method": "event",
+						"params": event,
+					}),
+					_ => continue,
+				}
+			}
+
+			_ = auth_check.tick() => {
+				if let Err(ex) = validate_token(&rpc_state.mm, &token).await {
+					close_auth_failed(&mut socket, ex).await;
+					break;
+				}
+				continue;
+			}
+		};
+
+		if socket
+			.send(Message::Text(out_msg.to_string()))
+			.await
+			.is_err()
+		{
+			break;
+		}
+	}
+
+	debug!("{:<12} - ws closed - {ctx:?}", "WS");
+}
+
+/// Closes the socket with the policy violation code (1008).
+async fn close_auth_failed(socket: &mut WebSocket, ex: CtxExtError) {
+	debug!("{:<12} - ws auth failed - {ex:?}", "WS");
+
+	let close_frame = CloseFrame {
+		code: close_code::POLICY,
+		reason: "auth token invalid or expired".into(),
+	};
+	// (the socket may already be gone, nothing more to do then)
+	let _ = socket.send(Message::Close(Some(close_frame))).await;
+}
+
+/// Handles one json-rpc text message, and returns the json-rpc response.
+async fn handle_text(
+	text: &str,
+	ctx: &Ctx,
+	rpc_state: &RpcState,
+	rpc_router: &RpcRouter,
+	events_rx: &mut Option<broadcast::Receiver<ModelEvent>>,
+) -> Value {
+	let rpc_req: RpcRequest = match serde_json::from_str(text) {
+		Ok(rpc_req) => rpc_req,
+		Err(ex) => return error_response(None, web::Error::from(ex)),
+	};
+	let RpcRequest { id, method, params } = rpc_req;
+
+	// -- Socket subscriptions
+	match method.as_str() {
+		"subscribe" => {
+			*events_rx = Some(rpc_state.mm.subscribe_events());
+			return json!({"id": id, "result": Value::Null});
+		}
+		"unsubscribe" => {
+			*events_rx = None;
+			return json!({"id": id, "result": Value::Null});
+		}
+		_ => (),
+	}
+
+	// -- Exec Rpc Route
+	let rpc_resources = RpcResources {
+		ctx: Some(ctx.clone()),
+		mm: rpc_state.mm.clone(),
+	};
+	match rpc_router.call(&method, rpc_resources, params).await {
+		Ok(result) => json!({"id": id, "result": result}),
+		Err(ex) => error_response(id, web::Error::from(ex)),
+	}
+}
+
+/// Waits for the next event of the subscription (forever when not subscribed).
+///
+/// Returns None when the events were lagged (i.e., some were skipped).
+async fn recv_event(
+	events_rx: &mut Option<broadcast::Receiver<ModelEvent>>,
+) -> Option<ModelEvent> {
+	let Some(rx) = events_rx else {
+		return std::future::pending().await;
+	};
+
+	match rx.recv().await {
+		Ok(event) => Some(event),
+		Err(RecvError::Lagged(_)) => None,
+		Err(RecvError::Closed) => {
+			*events_rx = None;
+			None
+		}
+	}
+}
+
+/// Builds the json-rpc error body, same as `mw_res_map` for '/api/rpc'.
+fn error_response(id: Option<Value>, web_error: web::Error) -> Value {
+	debug!("{:<12} - ws rpc error - {web_error:?}", "WS");
+
+	let (_, client_error) = web_error.client_status_and_error();
+	let client_error = to_value(client_error).ok();
+	let message = client_error.as_ref().and_then(|v| v.get("message"));
+	let detail = client_error.as_ref().and_then(|v| v.get("detail"));
+
+	json!({
+		"id": id,
+		"error": {
+			"message": message,
+			"data": {
+				"detail": detail
+			},
+		}
+	})
+}
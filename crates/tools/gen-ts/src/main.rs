@@ -0,0 +1,258 @@
+//! Generates the typed TypeScript rpc client from the `rpc.discover` document
+//! of `lib_rpc::all_rpc_router()`.
+//!
+//! Usage: `cargo run -p gen-ts -- [output_file.ts]` (prints to stdout without a file).
+//!
+//! - Each schema of `components.schemas` (e.g., `Project`, `TaskFilter`,
+//!   `ParamsList_for_TaskFilter`) becomes an exported TS type.
+//! - Each rpc method becomes a camelCase method of `RpcClient`
+//!   (e.g., `create_project` -> `createProject(params)`).
+//! - `OffsetDateTime` properties are `string` (Rfc3339), as in the json-rpc api.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::fmt::Write;
+use std::fs;
+
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+const TS_HEADER: &str = r#"// Generated by `cargo run -p gen-ts`. DO NOT EDIT.
+
+export interface RpcErrorBody {
+	message: string;
+	data?: { req_uuid?: string; detail?: unknown };
+}
+
+export class RpcError extends Error {
+	constructor(public readonly body: RpcErrorBody) {
+		super(body.message);
+	}
+}
+
+/** Transport calling a json-rpc method, and returning its `result`. */
+export type RpcCall = (method: string, params?: unknown) => Promise<unknown>;
+
+/** Default transport, posting to the web-server '/api/rpc' (auth by cookie). */
+export function httpRpcCall(url = "/api/rpc"): RpcCall {
+	let id = 0;
+	return async (method, params) => {
+		const res = await fetch(url, {
+			method: "POST",
+			credentials: "include",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ id: ++id, method, params }),
+		});
+		const body = await res.json();
+		if (body.error) {
+			throw new RpcError(body.error);
+		}
+		return body.result;
+	};
+}
+"#;
+
+fn main() -> Result<()> {
+	let discover_doc = lib_rpc::all_rpc_router().discover();
+	let ts_content = gen_ts(&discover_doc)?;
+
+	match std::env::args().nth(1) {
+		Some(file) => {
+			fs::write(&file, ts_content)?;
+			println!("->> TypeScript client generated: {file}");
+		}
+		None => print!("{ts_content}"),
+	}
+
+	Ok(())
+}
+
+fn gen_ts(discover_doc: &Value) -> Result<String> {
+	let mut ts = String::from(TS_HEADER);
+
+	// -- Types
+	let schemas = discover_doc["components"]["schemas"]
+		.as_object()
+		.ok_or_else(|| anyhow!("discover doc has no components.schemas"))?;
+	for (name, schema) in schemas {
+		ts.push('\n');
+		if let Some(description) = schema["description"].as_str() {
+			writeln!(ts, "/** {description} */")?;
+		}
+		writeln!(ts, "export type {name} = {};", ts_type(schema, 0))?;
+	}
+
+	// -- Client
+	let methods = discover_doc["methods"]
+		.as_array()
+		.ok_or_else(|| anyhow!("discover doc has no methods"))?;
+	ts.push_str("\nexport class RpcClient {\n");
+	ts.push_str(
+		"\tconstructor(private readonly call: RpcCall = httpRpcCall()) {}\n",
+	);
+	for method in methods {
+		let name = method["name"]
+			.as_str()
+			.ok_or_else(|| anyhow!("method without name"))?;
+		let result_type = ts_type(&method["result"]["schema"], 1);
+
+		ts.push('\n');
+		match method["params"].get(0) {
+			Some(param) => {
+				let params_type = ts_type(&param["schema"], 1);
+				writeln!(
+					ts,
+					"\t{}(params: {params_type}): Promise<{result_type}> {{",
+					camel_case(name)
+				)?;
+				writeln!(
+					ts,
+					"\t\treturn this.call(\"{name}\", params) as Promise<{result_type}>;"
+				)?;
+			}
+			None => {
+				writeln!(ts, "\t{}(): Promise<{result_type}> {{", camel_case(name))?;
+				writeln!(
+					ts,
+					"\t\treturn this.call(\"{name}\") as Promise<{result_type}>;"
+				)?;
+			}
+		}
+		ts.push_str("\t}\n");
+	}
+	ts.push_str("}\n");
+
+	Ok(ts)
+}
+
+// region:    --- JSON Schema to TS
+
+/// Returns the TypeScript type of a JSON Schema (as generated by schemars).
+fn ts_type(schema: &Value, indent: usize) -> String {
+	// -- Boolean schema (true is any)
+	if let Some(any) = schema.as_bool() {
+		return if any { "unknown" } else { "never" }.to_string();
+	}
+
+	// -- Reference
+	if let Some(reference) = schema["$ref"].as_str() {
+		return reference
+			.strip_prefix(SCHEMA_REF_PREFIX)
+			.unwrap_or(reference)
+			.to_string();
+	}
+
+	// -- Compositions
+	for (key, separator) in [("anyOf", " | "), ("oneOf", " | "), ("allOf", " & ")] {
+		if let Some(schemas) = schema[key].as_array() {
+			return schemas
+				.iter()
+				.map(|schema| ts_type(schema, indent))
+				.collect::<Vec<_>>()
+				.join(separator);
+		}
+	}
+
+	// -- Literals
+	if let Some(values) = schema["enum"].as_array() {
+		return values
+			.iter()
+			.map(Value::to_string)
+			.collect::<Vec<_>>()
+			.join(" | ");
+	}
+	if let Some(value) = schema.get("const") {
+		return value.to_string();
+	}
+
+	// -- Types (one or many, e.g., `["string", "null"]` for Option)
+	match &schema["type"] {
+		Value::String(typ) => ts_type_of(typ, schema, indent),
+		Value::Array(types) => types
+			.iter()
+			.filter_map(Value::as_str)
+			.map(|typ| ts_type_of(typ, schema, indent))
+			.collect::<Vec<_>>()
+			.join(" | "),
+		_ => "unknown".to_string(),
+	}
+}
+
+fn ts_type_of(typ: &str, schema: &Value, indent: usize) -> String {
+	match typ {
+		"string" => "string".to_string(),
+		"integer" | "number" => "number".to_string(),
+		"boolean" => "boolean".to_string(),
+		"null" => "null".to_string(),
+		"array" => match &schema["items"] {
+			// tuple
+			Value::Array(items) => {
+				let items: Vec<String> =
+					items.iter().map(|item| ts_type(item, indent)).collect();
+				format!("[{}]", items.join(", "))
+			}
+			Value::Null => "unknown[]".to_string(),
+			items => format!("Array<{}>", ts_type(items, indent)),
+		},
+		"object" => ts_object_type(schema, indent),
+		_ => "unknown".to_string(),
+	}
+}
+
+fn ts_object_type(schema: &Value, indent: usize) -> String {
+	let Some(properties) = schema["properties"].as_object() else {
+		return match &schema["additionalProperties"] {
+			Value::Null | Value::Bool(_) => "Record<string, unknown>".to_string(),
+			value_schema => {
+				format!("Record<string, {}>", ts_type(value_schema, indent))
+			}
+		};
+	};
+
+	let required: Vec<&str> = schema["required"]
+		.as_array()
+		.map(|required| required.iter().filter_map(Value::as_str).collect())
+		.unwrap_or_default();
+
+	let tabs = "\t".repeat(indent + 1);
+	let mut ts = String::from("{\n");
+	for (name, prop_schema) in properties {
+		let optional = if required.contains(&name.as_str()) {
+			""
+		} else {
+			"?"
+		};
+		let name = if name.starts_with('$') {
+			format!("\"{name}\"")
+		} else {
+			name.to_string()
+		};
+		let _ = writeln!(
+			ts,
+			"{tabs}{name}{optional}: {};",
+			ts_type(prop_schema, indent + 1)
+		);
+	}
+	ts.push_str(&"\t".repeat(indent));
+	ts.push('}');
+
+	ts
+}
+
+// endregion: --- JSON Schema to TS
+
+/// e.g., `list_tasks` -> `listTasks`
+fn camel_case(name: &str) -> String {
+	let mut camel = String::with_capacity(name.len());
+	let mut upper_next = false;
+	for c in name.chars() {
+		match c {
+			'_' | '.' => upper_next = true,
+			c if upper_next => {
+				camel.extend(c.to_uppercase());
+				upper_next = false;
+			}
+			c => camel.push(c),
+		}
+	}
+	camel
+}
@@ -0,0 +1,84 @@
+//! Imports the export file of another tracker for a user
+//! (see `lib_core::model::import`), and prints the json report.
+//!
+//! Usage:
+//! `cargo run -p import-time -- <username> <format> <file> [options]`
+//!
+//! - `format` is `toggl_csv`, `clockify_csv`, or `timewarrior_json`.
+//! - `--dry-run` only reports what would be imported.
+//! - `--utc-offset +02:00` sets the offset of the csv local times (default UTC).
+//!
+//! Note: Uses the `SERVICE_DB_URL` of the environment (e.g., `.cargo/config.toml`).
+
+use anyhow::{anyhow, Result};
+use lib_core::ctx::Ctx;
+use lib_core::export::parse_utc_offset;
+use lib_core::model::import::{ImportBmc, ImportFormat, ImportOptions};
+use lib_core::model::user::{User, UserBmc};
+use lib_core::model::ModelManager;
+use serde_json::Value;
+use std::fs;
+
+const USAGE: &str = concat!(
+	"usage: import-time <username> <toggl_csv|clockify_csv|timewarrior_json>",
+	" <file> [--dry-run] [--utc-offset +02:00]"
+);
+
+struct Args {
+	username: String,
+	format: ImportFormat,
+	file: String,
+	options: ImportOptions,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+	let Args {
+		username,
+		format,
+		file,
+		options,
+	} = parse_args(std::env::args().skip(1))?;
+	let content = fs::read_to_string(&file)?;
+
+	// -- Resolve the user ctx
+	let mm = ModelManager::new().await?;
+	let user: User = UserBmc::first_by_username(&Ctx::root_ctx(), &mm, &username)
+		.await?
+		.ok_or_else(|| anyhow!("user '{username}' not found"))?;
+	let ctx = Ctx::new(user.id)?;
+
+	// -- Import
+	let report = ImportBmc::import(&ctx, &mm, format, &content, options).await?;
+	println!("{}", serde_json::to_string_pretty(&report)?);
+
+	Ok(())
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args> {
+	let mut positionals = Vec::new();
+	let mut options = ImportOptions::default();
+
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--dry-run" => options.dry_run = true,
+			"--utc-offset" => {
+				let offset = args.next().ok_or_else(|| anyhow!(USAGE))?;
+				options.utc_offset = parse_utc_offset(&offset)?;
+			}
+			_ => positionals.push(arg),
+		}
+	}
+
+	let [username, format, file]: [String; 3] =
+		positionals.try_into().map_err(|_| anyhow!(USAGE))?;
+	let format: ImportFormat =
+		serde_json::from_value(Value::String(format)).map_err(|_| anyhow!(USAGE))?;
+
+	Ok(Args {
+		username,
+		format,
+		file,
+		options,
+	})
+}
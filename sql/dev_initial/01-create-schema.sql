@@ -12,6 +12,15 @@ CREATE TABLE "user" (
   pwd_salt uuid NOT NULL DEFAULT gen_random_uuid(),
   token_salt uuid NOT NULL DEFAULT gen_random_uuid(),
 
+  -- Calendar (read-only calendar url token, see lib_core::export)
+  calendar_token uuid UNIQUE,
+
+  -- Role (e.g., to lock all the users periods, see `model::period_lock`)
+  is_admin BOOLEAN NOT NULL DEFAULT false,
+
+  -- Team (the team lead approving the user timesheets, see `model::timesheet`)
+  approver_id BIGINT NULL,
+
   -- Timestamps
   cid bigint NOT NULL,
   ctime timestamp with time zone NOT NULL,
@@ -19,11 +28,31 @@ CREATE TABLE "user" (
   mtime timestamp with time zone NOT NULL  
 );
 
+-- Client (the invoiced customer of projects, see `model::client`)
+CREATE TABLE client (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  name varchar(256) NOT NULL,
+  address varchar(1024) NOT NULL DEFAULT '',
+  currency varchar(3) NOT NULL DEFAULT 'EUR', -- ISO 4217 (e.g., 'EUR', 'USD')
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
 -- Project
 CREATE TABLE project (
   -- PK
   id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
 
+  -- FK
+  client_id BIGINT NULL,
+
   -- Properties
   owner_id BIGINT NOT NULL,
   name varchar(256) NOT NULL,
@@ -72,6 +101,24 @@ CREATE TABLE taskprogress (
   mtime TIMESTAMP with time zone NOT NULL
 );
 
+-- Place (unique per user and normalized name, see `model::place`)
+CREATE TABLE place (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  name varchar(256) NOT NULL,
+  name_key varchar(256) NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL,
+
+  UNIQUE (cid, name_key)
+);
+
 -- TaskProgress
 CREATE TABLE timerecord (
   -- PK
@@ -83,7 +130,8 @@ CREATE TABLE timerecord (
   --task_id BIGINT NULL,
 
   -- Properties
-  place varchar(256) NOT NULL,
+  place varchar(256) NOT NULL, -- the place name (see place_id)
+  place_id BIGINT NULL,
   start_time TIMESTAMP with time zone NOT NULL,
   stop_time TIMESTAMP with time zone NOT NULL,
 
@@ -104,8 +152,100 @@ CREATE TABLE tasktime (
 
   -- Properties
   comment varchar(512) NOT NULL,
+  billable bool NOT NULL DEFAULT true,
+  start_time TIMESTAMP with time zone NOT NULL,
+  stop_time TIMESTAMP with time zone NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- Travel (between two places, e.g., a commute)
+CREATE TABLE travel (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  from_place_id BIGINT NOT NULL,
+  to_place_id BIGINT NOT NULL,
+
+  -- Properties
+  mode varchar(32) NOT NULL, -- see `model::travel::TravelMode`
+  distance_km DOUBLE PRECISION NULL,
+  start_time TIMESTAMP with time zone NOT NULL,
+  stop_time TIMESTAMP with time zone NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- WorkSchedule (contracted hours per weekday, from valid_from
+--   up to the next schedule valid_from, see `model::work_schedule`)
+CREATE TABLE work_schedule (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  valid_from DATE NOT NULL,
+  mon_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  tue_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  wed_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  thu_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  fri_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  sat_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+  sun_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL,
+
+  UNIQUE (cid, valid_from)
+);
+
+-- Absence (a day off, e.g., vacation, sick, holiday, see `model::absence`)
+CREATE TABLE absence (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  date DATE NOT NULL,
+  kind varchar(32) NOT NULL, -- see `model::absence::AbsenceKind`
+  hours DOUBLE PRECISION NULL, -- NULL for the full scheduled day
+  comment varchar(512) NOT NULL DEFAULT '',
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- PeriodLock (a closed period of a user, or of all the users with a NULL
+--   user_id, see `model::period_lock`)
+CREATE TABLE period_lock (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  user_id BIGINT NULL,
+
+  -- Properties
   start_time TIMESTAMP with time zone NOT NULL,
   stop_time TIMESTAMP with time zone NOT NULL,
+  comment varchar(1024) NOT NULL DEFAULT '',
+
+  -- Unlock (the locks are kept, as the audit trail)
+  unlocked_by BIGINT NULL,
+  unlocked_at TIMESTAMP with time zone NULL,
+  unlock_reason varchar(1024) NULL,
 
   -- Timestamps
   cid BIGINT NOT NULL,
@@ -114,6 +254,184 @@ CREATE TABLE tasktime (
   mtime TIMESTAMP with time zone NOT NULL
 );
 
+-- Timesheet (the entries of a user period, submitted for approval,
+--   see `model::timesheet`)
+CREATE TABLE timesheet (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  user_id BIGINT NOT NULL,
+  approver_id BIGINT NULL, -- the user approver, when submitted
+  lock_id BIGINT NULL, -- the period lock, when approved
+
+  -- Properties
+  start_time TIMESTAMP with time zone NOT NULL,
+  stop_time TIMESTAMP with time zone NOT NULL,
+  status varchar(16) NOT NULL DEFAULT 'draft', -- see `TimesheetStatus`
+  submitted_at TIMESTAMP with time zone NULL,
+  reviewed_by BIGINT NULL,
+  reviewed_at TIMESTAMP with time zone NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL,
+
+  UNIQUE (user_id, start_time)
+);
+
+-- TimesheetComment (the comment of a timesheet submit, approve, or reject)
+CREATE TABLE timesheet_comment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  timesheet_id BIGINT NOT NULL,
+
+  -- Properties
+  action varchar(16) NOT NULL, -- 'submit' | 'approve' | 'reject'
+  text varchar(1024) NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- RoundingPolicy (the rounding of the entry durations, per project,
+--   or the user default with a NULL project_id, see `model::rounding`)
+CREATE TABLE rounding_policy (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  project_id BIGINT NULL,
+
+  -- Properties
+  mode varchar(16) NOT NULL, -- see `model::rounding::RoundingMode`
+  increment_sec BIGINT NOT NULL,
+  min_sec BIGINT NOT NULL DEFAULT 0,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL,
+
+  UNIQUE NULLS NOT DISTINCT (cid, project_id)
+);
+
+-- Rate (hourly rate, for a project, task and/or user, from valid_from,
+--   see `model::rate`)
+CREATE TABLE rate (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK (NULL for any)
+  project_id BIGINT NULL,
+  task_id BIGINT NULL,
+  user_id BIGINT NULL,
+
+  -- Properties
+  hourly_rate_cents BIGINT NOT NULL, -- in the client currency
+  valid_from DATE NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- Invoice (locked once issued, see `model::invoice`)
+CREATE TABLE invoice (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  client_id BIGINT NOT NULL,
+
+  -- Properties
+  number BIGINT NULL, -- sequential per user, set when issued
+  status varchar(16) NOT NULL, -- 'draft' | 'issued'
+  period_start DATE NOT NULL,
+  period_end DATE NOT NULL, -- inclusive
+  currency varchar(3) NOT NULL,
+  total_cents BIGINT NOT NULL,
+  issued_at TIMESTAMP with time zone NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL,
+
+  UNIQUE (cid, number)
+);
+
+-- InvoiceLine (one per invoiced tasktime)
+CREATE TABLE invoice_line (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  invoice_id BIGINT NOT NULL,
+  tasktime_id BIGINT NULL,
+  project_id BIGINT NOT NULL,
+  task_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL,
+
+  -- Properties
+  date DATE NOT NULL,
+  description varchar(1024) NOT NULL,
+  duration_sec BIGINT NOT NULL,
+  rounded_sec BIGINT NOT NULL, -- the billed duration (see `model::rounding`)
+  hourly_rate_cents BIGINT NOT NULL,
+  amount_cents BIGINT NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL,
+  mid BIGINT NOT NULL,
+  mtime TIMESTAMP with time zone NOT NULL
+);
+
+-- ChangeLog (sync change feed, see `model::sync`)
+CREATE TABLE change_log (
+  -- PK (the monotonic change sequence)
+  seq BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+
+  -- Properties
+  entity varchar(64) NOT NULL, -- table name
+  entity_id BIGINT NOT NULL,
+  op varchar(16) NOT NULL, -- 'upsert' | 'delete'
+
+  -- Timestamps
+  --   (cid is the entity owner, i.e., its cid, not the change author)
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL
+);
+
+-- SyncUuid (client generated uuid to server entity id)
+CREATE TABLE sync_uuid (
+  -- PK
+  uuid uuid PRIMARY KEY,
+
+  -- Properties
+  entity varchar(64) NOT NULL, -- table name
+  entity_id BIGINT NOT NULL,
+
+  -- Timestamps
+  cid BIGINT NOT NULL,
+  ctime TIMESTAMP with time zone NOT NULL
+);
+
+-- Note: The model deletes the task, taskprogress, tasktime, travel, and
+--       rounding_policy children first (see `base::delete_children`),
+--       these `ON DELETE CASCADE` are only a fallback.
 ALTER TABLE task ADD CONSTRAINT fk_project
   FOREIGN KEY (project_id) REFERENCES project(id)
   ON DELETE CASCADE;
@@ -125,3 +443,39 @@ ALTER TABLE taskprogress ADD CONSTRAINT fk_task
 ALTER TABLE tasktime ADD CONSTRAINT fk_task
     FOREIGN KEY (task_id) REFERENCES task(id)
         ON DELETE CASCADE;
+
+ALTER TABLE timerecord ADD CONSTRAINT fk_place
+    FOREIGN KEY (place_id) REFERENCES place(id)
+        ON DELETE SET NULL;
+
+ALTER TABLE travel ADD CONSTRAINT fk_from_place
+    FOREIGN KEY (from_place_id) REFERENCES place(id)
+        ON DELETE CASCADE;
+
+ALTER TABLE travel ADD CONSTRAINT fk_to_place
+    FOREIGN KEY (to_place_id) REFERENCES place(id)
+        ON DELETE CASCADE;
+
+ALTER TABLE project ADD CONSTRAINT fk_client
+    FOREIGN KEY (client_id) REFERENCES client(id)
+        ON DELETE SET NULL;
+
+ALTER TABLE invoice ADD CONSTRAINT fk_client
+    FOREIGN KEY (client_id) REFERENCES client(id)
+        ON DELETE RESTRICT;
+
+ALTER TABLE invoice_line ADD CONSTRAINT fk_invoice
+    FOREIGN KEY (invoice_id) REFERENCES invoice(id)
+        ON DELETE CASCADE;
+
+ALTER TABLE invoice_line ADD CONSTRAINT fk_tasktime
+    FOREIGN KEY (tasktime_id) REFERENCES tasktime(id)
+        ON DELETE SET NULL;
+
+ALTER TABLE rounding_policy ADD CONSTRAINT fk_project
+    FOREIGN KEY (project_id) REFERENCES project(id)
+        ON DELETE CASCADE;
+
+ALTER TABLE timesheet_comment ADD CONSTRAINT fk_timesheet
+    FOREIGN KEY (timesheet_id) REFERENCES timesheet(id)
+        ON DELETE CASCADE;
@@ -1,7 +1,7 @@
 -- root user (at id = 0)
 INSERT INTO "user" 
-    (id, username, cid, ctime, mid, mtime) VALUES 
-    (0, 'root',  0,   now(), 0,   now());
+    (id, username, is_admin, cid, ctime, mid, mtime) VALUES 
+    (0, 'root',  true,     0,   now(), 0,   now());
 
 -- User demo1
 INSERT INTO "user" 